@@ -0,0 +1,93 @@
+//! Calibration over inputs too big to hold as a `Vec<String>`.
+//!
+//! The input is read a chunk at a time, cut back to the last newline so that
+//! every line is whole, and the lines of each chunk are valued in parallel.
+//! The cut-off tail is carried into the next chunk, so a digit word split by
+//! a chunk boundary is still found.
+
+use std::io::{self, Read};
+
+use rayon::prelude::*;
+
+/// Large enough that each chunk keeps every thread busy for a while.
+pub const DEFAULT_CHUNK_SIZE: usize = 64 << 20;
+
+/// The value of `line` from its first and last ASCII digits.
+pub fn line_value(line: &[u8]) -> Option<u32> {
+    let first = line.iter().find(|b| b.is_ascii_digit())?;
+    let last = line.iter().rev().find(|b| b.is_ascii_digit())?;
+    Some(u32::from(first - b'0') * 10 + u32::from(last - b'0'))
+}
+
+/// Sum `value` over the lines of `reader`, skipping lines it has no value
+/// for.
+///
+/// Lines may end in `\n` or `\r\n`, as for [`std::io::BufRead::lines`]. A
+/// line longer than `chunk_size` just makes its chunk grow until the line
+/// ends.
+pub fn sum_lines<F>(mut reader: impl Read, chunk_size: usize, value: F) -> io::Result<u64>
+where
+    F: Fn(&[u8]) -> Option<u32> + Sync,
+{
+    let mut total = 0;
+    let mut chunk = Vec::with_capacity(chunk_size);
+    loop {
+        let read = (&mut reader)
+            .take(chunk_size.max(1) as u64)
+            .read_to_end(&mut chunk)?;
+        if read == 0 {
+            // whatever is left is a last line without a newline
+            return Ok(total + sum_chunk(&chunk, &value));
+        }
+        if let Some(end) = chunk.iter().rposition(|&b| b == b'\n') {
+            let tail = chunk[end + 1..].to_vec();
+            chunk.truncate(end + 1);
+            total += sum_chunk(&chunk, &value);
+            chunk.clear();
+            chunk.extend_from_slice(&tail);
+        }
+    }
+}
+
+fn sum_chunk<F>(chunk: &[u8], value: &F) -> u64
+where
+    F: Fn(&[u8]) -> Option<u32> + Sync,
+{
+    chunk
+        .par_split(|&b| b == b'\n')
+        .filter_map(|line| value(line.strip_suffix(b"\r").unwrap_or(line)))
+        .map(u64::from)
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::get_line_calibration_value;
+
+    const INPUT: &str = "1abc2\npqr3stu8vwx\r\n\nnone\na1b2c3d4e5f\ntreb7uchet";
+
+    #[test]
+    fn same_as_lines() -> io::Result<()> {
+        let expected: u32 = INPUT
+            .lines()
+            .filter_map(|line| get_line_calibration_value(line).ok())
+            .sum();
+        assert_eq!(expected, 142);
+        for chunk_size in 1..=INPUT.len() + 1 {
+            assert_eq!(
+                sum_lines(INPUT.as_bytes(), chunk_size, line_value)?,
+                u64::from(expected),
+                "chunks of {chunk_size}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn empty() -> io::Result<()> {
+        assert_eq!(sum_lines(&b""[..], 4, line_value)?, 0);
+        assert_eq!(sum_lines(&b"\n\n"[..], 1, line_value)?, 0);
+        Ok(())
+    }
+}
@@ -1,22 +1,48 @@
+use aoc2023_solution::Solution;
 use color_eyre::{eyre::eyre, Result};
 
+pub mod chunked;
+
 pub fn get_line_calibration_value(input: &str) -> Result<u32> {
     let first_digit = input
         .chars()
-        .find(|c| c.is_numeric())
+        .find(char::is_ascii_digit)
         .ok_or(eyre!("No digits!"))?
         .to_digit(10)
         .unwrap();
     let last_digit = input
         .chars()
         .rev()
-        .find(|c| c.is_numeric())
+        .find(char::is_ascii_digit)
         .ok_or(eyre!("No digits!"))?
         .to_digit(10)
         .unwrap();
     Ok(first_digit * 10 + last_digit)
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 1;
+    const PART: u8 = 1;
+
+    /// The calibration value of each line that has one.
+    type Input = Vec<u32>;
+    type Params = ();
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input
+            .lines()
+            .filter_map(|line| get_line_calibration_value(line).ok())
+            .collect())
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(input.iter().sum())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1,13 +1,26 @@
-use std::io;
+use std::{fs::File, io, path::PathBuf};
 
-use aoc2023_01_1::get_line_calibration_value;
+use aoc2023_01_1::chunked::{line_value, sum_lines, DEFAULT_CHUNK_SIZE};
+use clap::Parser;
+use color_eyre::Result;
 
-fn main() {
-    let answer = io::stdin()
-        .lines()
-        .filter_map(|l| l.ok())
-        .filter_map(|s| get_line_calibration_value(s.as_str()).ok())
-        .reduce(|acc, v| acc + v)
-        .unwrap();
+#[derive(Parser)]
+#[command(about = "Sum calibration values, reading in chunks summed in parallel")]
+struct Cli {
+    /// Read from this file instead of stdin
+    input: Option<PathBuf>,
+    /// Bytes to read at a time
+    #[arg(long, default_value_t = DEFAULT_CHUNK_SIZE)]
+    chunk_size: usize,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let cli = Cli::parse();
+    let answer = match &cli.input {
+        Some(path) => sum_lines(File::open(path)?, cli.chunk_size, line_value)?,
+        None => sum_lines(io::stdin().lock(), cli.chunk_size, line_value)?,
+    };
     println!("{answer}");
+    Ok(())
 }
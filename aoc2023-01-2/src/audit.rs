@@ -0,0 +1,223 @@
+//! A line by line account of how a calibration total was reached, for when
+//! someone disputes it.
+
+use std::fmt;
+
+use aoc2023_parse::ParseError;
+use serde::Serialize;
+
+use crate::{lexicon::Token, DigitLexicon};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Skip {
+    EmptyLine,
+    NoDigits,
+}
+
+impl fmt::Display for Skip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyLine => write!(f, "empty line"),
+            Self::NoDigits => write!(f, "no digits or digit words"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineAudit {
+    /// 1-based, like an editor.
+    pub line: usize,
+    pub text: String,
+    pub first: Option<Token>,
+    pub last: Option<Token>,
+    pub value: Option<u32>,
+    /// The first and last tokens share letters, as in `eightwo`.
+    pub overlapping: bool,
+    pub skipped: Option<Skip>,
+}
+
+impl LineAudit {
+    pub fn new(line: usize, text: String, lexicon: &DigitLexicon) -> Self {
+        let first = lexicon.first_token(&text);
+        let last = lexicon.last_token(&text);
+        let value = first
+            .as_ref()
+            .zip(last.as_ref())
+            .map(|(f, l)| 10 * f.value + l.value);
+        let overlapping = match (&first, &last) {
+            (Some(first), Some(last)) => first.start < last.start && last.start < first.end(),
+            _ => false,
+        };
+        let skipped = match (value, text.is_empty()) {
+            (Some(_), _) => None,
+            (None, true) => Some(Skip::EmptyLine),
+            (None, false) => Some(Skip::NoDigits),
+        };
+        Self {
+            line,
+            text,
+            first,
+            last,
+            value,
+            overlapping,
+            skipped,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Audit {
+    pub lines: Vec<LineAudit>,
+    pub total: u32,
+    pub skipped: usize,
+}
+
+impl Audit {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("audits always serialize")
+    }
+}
+
+impl FromIterator<LineAudit> for Audit {
+    fn from_iter<I: IntoIterator<Item = LineAudit>>(iter: I) -> Self {
+        let lines: Vec<LineAudit> = iter.into_iter().collect();
+        Self {
+            total: lines.iter().filter_map(|line| line.value).sum(),
+            skipped: lines.iter().filter(|line| line.skipped.is_some()).count(),
+            lines,
+        }
+    }
+}
+
+pub fn audit(it: impl Iterator<Item = String>, lexicon: &DigitLexicon) -> Audit {
+    it.enumerate()
+        .map(|(i, text)| LineAudit::new(i + 1, text, lexicon))
+        .collect()
+}
+
+/// As [`audit`], but failing at the first line that has no value.
+pub fn audit_strict(
+    it: impl Iterator<Item = String>,
+    lexicon: &DigitLexicon,
+) -> Result<Audit, ParseError> {
+    it.enumerate()
+        .map(|(i, text)| {
+            let line = LineAudit::new(i + 1, text, lexicon);
+            match line.skipped {
+                None => Ok(line),
+                Some(_) => {
+                    Err(ParseError::at(&line.text, 0, "a digit or digit word").at_line(line.line))
+                }
+            }
+        })
+        .collect()
+}
+
+fn token(token: &Option<Token>) -> String {
+    match token {
+        Some(token) => format!("{} [{}..{}]", token.text, token.start, token.end()),
+        None => "-".to_string(),
+    }
+}
+
+impl fmt::Display for Audit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .lines
+            .iter()
+            .flat_map(|line| [token(&line.first).len(), token(&line.last).len()])
+            .max()
+            .unwrap_or(0)
+            .max("first".len());
+        writeln!(
+            f,
+            "{:>5}  {:<width$}  {:<width$}  value  note",
+            "line", "first", "last"
+        )?;
+        for line in &self.lines {
+            let value = line.value.map_or("-".to_string(), |v| v.to_string());
+            let note = match (line.skipped, line.overlapping) {
+                (Some(skip), _) => format!("skipped: {skip}"),
+                (None, true) => "overlapping".to_string(),
+                (None, false) => String::new(),
+            };
+            let row = format!(
+                "{:>5}  {:<width$}  {:<width$}  {value:>5}  {note}",
+                line.line,
+                token(&line.first),
+                token(&line.last),
+            );
+            writeln!(f, "{}", row.trim_end())?;
+        }
+        write!(
+            f,
+            "total {} from {} lines, {} skipped",
+            self.total,
+            self.lines.len(),
+            self.skipped
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+
+    const EXAMPLE: &str = indoc! {"
+        two1nine
+        eightwothree
+
+        xyz
+        fünf7
+        oneight
+    "};
+
+    fn example() -> impl Iterator<Item = String> {
+        EXAMPLE.lines().map(String::from)
+    }
+
+    #[test]
+    fn lines() {
+        let audit = audit(example(), &DigitLexicon::default());
+        assert_eq!((audit.total, audit.skipped), (29 + 83 + 77 + 18, 2));
+        assert_eq!(
+            audit.lines[1].last,
+            Some(Token {
+                text: "three".into(),
+                start: 7,
+                value: 3
+            })
+        );
+        assert_eq!(audit.lines[2].skipped, Some(Skip::EmptyLine));
+        assert_eq!(audit.lines[3].skipped, Some(Skip::NoDigits));
+        // byte offsets, so the multi-byte ü counts twice
+        assert_eq!(audit.lines[4].first.as_ref().map(|t| t.start), Some(5));
+        assert!(audit.lines[5].overlapping);
+        assert!(!audit.lines[0].overlapping);
+
+        let table = audit.to_string();
+        assert!(table.contains("one [0..3]"), "{table}");
+        assert!(table
+            .lines()
+            .any(|l| l.contains("eight [2..7]") && l.ends_with("overlapping")));
+        assert!(table.ends_with("total 207 from 6 lines, 2 skipped"));
+    }
+
+    #[test]
+    fn json() {
+        let audit = audit(example().take(2), &DigitLexicon::digits());
+        let json: serde_json::Value = serde_json::from_str(&audit.to_json()).unwrap();
+        assert_eq!(json["total"], 11);
+        assert_eq!(json["lines"][0]["first"]["text"], "1");
+        assert_eq!(json["lines"][1]["skipped"], "no_digits");
+    }
+
+    #[test]
+    fn strict() {
+        let err = audit_strict(example(), &DigitLexicon::default()).unwrap_err();
+        assert_eq!(err.line(), 3);
+        assert!(audit_strict(example().take(2), &DigitLexicon::default()).is_ok());
+    }
+}
@@ -0,0 +1,185 @@
+//! The words, in any language, which spell out calibration digits.
+//!
+//! Lexicon files hold one `word=value` per line, with blank lines and `#`
+//! comments ignored:
+//!
+//! ```text
+//! # French
+//! un=1
+//! deux=2
+//! ```
+//!
+//! Values must be single digits. The digits `0` to `9` are always
+//! recognised on top of the listed words. French, German and Roman numerals
+//! are in the crate's `lexicons` directory.
+
+use std::{fs, path::Path, str::FromStr};
+
+use aho_corasick::{AhoCorasick, MatchKind};
+use aoc2023_parse::{finish, parse_line, ParseError};
+use color_eyre::{eyre::WrapErr, Result};
+use nom::{
+    bytes::complete::take_till1,
+    character::complete::{char, space0, u32},
+    sequence::{delimited, separated_pair},
+};
+use serde::Serialize;
+
+pub(crate) const ENGLISH: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// Digit words compiled into automata for finding the first and last in a
+/// line, each in a single pass.
+#[derive(Debug, Clone)]
+pub struct DigitLexicon {
+    values: Vec<u32>,
+    forward: AhoCorasick,
+    /// Matches the reversed words against reversed lines.
+    backward: AhoCorasick,
+}
+
+impl DigitLexicon {
+    /// `words` with their values, alongside the digits themselves.
+    pub fn new<S: AsRef<str>>(words: impl IntoIterator<Item = (S, u32)>) -> Self {
+        let (words, values): (Vec<Vec<u8>>, Vec<u32>) = ('0'..='9')
+            .zip(0..)
+            .map(|(digit, value)| (vec![digit as u8], value))
+            .chain(
+                words
+                    .into_iter()
+                    .map(|(word, value)| (word.as_ref().as_bytes().to_vec(), value)),
+            )
+            .unzip();
+        let reversed: Vec<Vec<u8>> = words
+            .iter()
+            .map(|word| word.iter().rev().copied().collect())
+            .collect();
+        Self {
+            values,
+            forward: compile(&words),
+            backward: compile(&reversed),
+        }
+    }
+
+    pub fn english() -> Self {
+        Self::new(ENGLISH.into_iter().zip(0..))
+    }
+
+    /// Only the digits, as in part 1 of the puzzle.
+    pub fn digits() -> Self {
+        Self::new::<&str>([])
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let lexicon = fs::read_to_string(path)
+            .wrap_err_with(|| format!("reading lexicon {}", path.display()))?;
+        Ok(lexicon.parse()?)
+    }
+
+    /// The first word or digit in `line`.
+    pub fn first_token(&self, line: &str) -> Option<Token> {
+        let found = self.forward.find(line)?;
+        Some(Token {
+            text: line[found.range()].to_string(),
+            start: found.start(),
+            value: self.values[found.pattern()],
+        })
+    }
+
+    /// The last word or digit in `line`.
+    pub fn last_token(&self, line: &str) -> Option<Token> {
+        let found = self.backward.find(&reversed(line.as_bytes()))?;
+        let start = line.len() - found.end();
+        Some(Token {
+            text: line[start..line.len() - found.start()].to_string(),
+            start,
+            value: self.values[found.pattern()],
+        })
+    }
+
+    /// The value of the first word or digit in `line`, which needn't be
+    /// UTF-8.
+    pub fn first(&self, line: impl AsRef<[u8]>) -> Option<u32> {
+        let line = line.as_ref();
+        let found = self.forward.find(line)?;
+        Some(self.values[found.pattern()])
+    }
+
+    /// The value of the last word or digit in `line`, which needn't be
+    /// UTF-8.
+    pub fn last(&self, line: impl AsRef<[u8]>) -> Option<u32> {
+        let line = line.as_ref();
+        let found = self.backward.find(&reversed(line))?;
+        Some(self.values[found.pattern()])
+    }
+}
+
+fn reversed(line: &[u8]) -> Vec<u8> {
+    line.iter().rev().copied().collect()
+}
+
+/// A digit or word found in a line, `start` being its byte offset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Token {
+    pub text: String,
+    pub start: usize,
+    pub value: u32,
+}
+
+impl Token {
+    pub fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+}
+
+impl Default for DigitLexicon {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+fn compile(patterns: &[Vec<u8>]) -> AhoCorasick {
+    // of two words starting at the same place, the longer is the real one
+    AhoCorasick::builder()
+        .match_kind(MatchKind::LeftmostLongest)
+        .build(patterns)
+        .expect("lexicon is small enough to compile")
+}
+
+struct Entry(String, u32);
+
+impl FromStr for Entry {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let word = take_till1(|c: char| c == '=' || c.is_whitespace());
+        let s = s.trim();
+        let entry = separated_pair(word, delimited(space0, char('='), space0), u32)(s);
+        let (word, value) = finish(s, entry)?;
+        if value > 9 {
+            let column = s.rfind(|c: char| !c.is_ascii_digit()).map_or(0, |i| i + 1);
+            return Err(ParseError::at(s, column, "a digit from 0 to 9"));
+        }
+        Ok(Entry(word.to_string(), value))
+    }
+}
+
+impl FromStr for DigitLexicon {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words: Vec<(String, u32)> = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let Entry(word, value) = parse_line(i, line)?;
+            if words.iter().any(|(w, _)| *w == word) {
+                return Err(ParseError::at(line, 0, "a word not already listed").at_line(i + 1));
+            }
+            words.push((word, value));
+        }
+        Ok(Self::new(words))
+    }
+}
@@ -1,84 +1,57 @@
-use std::{collections::HashMap, sync::OnceLock};
+use std::io::{self, Read};
 
+use aoc2023_01_1::chunked::sum_lines;
+use aoc2023_solution::{lines, Solution};
 use color_eyre::{eyre::eyre, Result};
 
-trait EndScanner {
-    type Return;
+pub mod audit;
+mod lexicon;
+pub mod phrase;
 
-    fn first_n(&self, n: usize) -> Self::Return;
-    fn last_n(&self, n: usize) -> Self::Return;
-}
+pub use lexicon::{DigitLexicon, Token};
 
-impl<'a> EndScanner for &'a String {
-    type Return = &'a str;
-    fn first_n(&self, n: usize) -> Self::Return {
-        &self.as_str()[0..n]
-    }
-    fn last_n(&self, n: usize) -> Self::Return {
-        // TODO
-        &self.as_str()[0..n]
-    }
+fn calibration(v: &str, lexicon: &DigitLexicon) -> Result<u32> {
+    let first = lexicon.first(v).ok_or(eyre!("No digit found in {v}"))?;
+    let last = lexicon.last(v).ok_or(eyre!("No digit found in {v}"))?;
+    Ok(10 * first + last)
 }
 
-fn stats_with_digit_repr(s: &str) -> Option<u32> {
-    static DIGITS: OnceLock<HashMap<&str, u32>> = OnceLock::new();
-    DIGITS
-        .get_or_init(|| {
-            HashMap::from([
-                ("0", 0),
-                ("1", 1),
-                ("2", 2),
-                ("3", 3),
-                ("4", 4),
-                ("5", 5),
-                ("6", 6),
-                ("7", 7),
-                ("8", 8),
-                ("9", 9),
-                ("zero", 0),
-                ("one", 1),
-                ("two", 2),
-                ("three", 3),
-                ("four", 4),
-                ("five", 5),
-                ("six", 6),
-                ("seven", 7),
-                ("eight", 8),
-                ("nine", 9),
-            ])
-        })
-        .iter()
-        .find(|(match_me, _)| s.starts_with(*match_me))
-        .map(|(_, v)| v)
-        .copied()
+pub fn sum_calibration(it: impl Iterator<Item = String>, lexicon: &DigitLexicon) -> Result<u32> {
+    it.filter_map(|line| calibration(&line, lexicon).ok())
+        .reduce(|acc, v| acc + v)
+        .ok_or(eyre!("Some calibration summing error 🤷"))
 }
 
-fn first_digit(v: &str) -> Result<u32> {
-    for i in 0..v.len() {
-        if let Some(digit) = stats_with_digit_repr(&v[i..]) {
-            return Ok(digit);
-        }
-    }
-    Err(eyre!("No digit found in {v}"))
+/// As [`sum_calibration`], but over bytes read a chunk at a time and summed
+/// in parallel, for inputs too big to read line by line.
+pub fn sum_calibration_chunked(
+    reader: impl Read,
+    chunk_size: usize,
+    lexicon: &DigitLexicon,
+) -> io::Result<u64> {
+    sum_lines(reader, chunk_size, |line| {
+        Some(10 * lexicon.first(line)? + lexicon.last(line)?)
+    })
 }
 
-fn last_digit(v: &str) -> Result<u32> {
-    for i in (0..v.len()).rev() {
-        if let Some(digit) = stats_with_digit_repr(&v[i..]) {
-            return Ok(digit);
-        }
-    }
-    Err(eyre!("No digit found in {v}"))
-}
+pub struct Solver;
 
-fn calibration(v: &str) -> Result<u32> {
-    Ok(10 * first_digit(v)? + last_digit(v)?)
-}
+impl Solution for Solver {
+    const DAY: u8 = 1;
+    const PART: u8 = 2;
 
-pub fn sum_calibration(it: impl Iterator<Item = String>) -> Result<u32> {
-    it.filter_map(|line| calibration(&line).ok())
-        .reduce(|acc, v| acc + v)
-        .ok_or(eyre!("Some calibration summing error 🤷"))
+    type Input = Vec<String>;
+    /// The digit words to look for, English by default.
+    type Params = DigitLexicon;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(lines(input))
+    }
+
+    fn solve(input: Self::Input, lexicon: &Self::Params) -> Result<Self::Answer> {
+        sum_calibration(input.into_iter(), lexicon)
+    }
 }
 
 #[cfg(test)]
@@ -98,7 +71,7 @@ mod tests {
             ("7pqrstsixteen", 76),
         ] {
             assert_eq!(
-                calibration(line)?,
+                calibration(line, &DigitLexicon::default())?,
                 expected,
                 "Expect \"{line}\" => {expected}"
             );
@@ -117,7 +90,86 @@ mod tests {
             zoneight234
             7pqrstsixteen
         "};
-        assert_eq!(sum_calibration(example.lines().map(String::from))?, 281);
+        assert_eq!(
+            sum_calibration(example.lines().map(String::from), &DigitLexicon::default())?,
+            281
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn chunked() -> Result<()> {
+        let example = "two1nine\neightwothree\r\nabcone2threexyz\n\nxyz\nzoneight\nsevenine";
+        let lexicon = DigitLexicon::default();
+        let expected = sum_calibration(example.lines().map(String::from), &lexicon)?;
+        assert_eq!(expected, 29 + 83 + 13 + 18 + 79);
+        // every possible cut, including through the middle of words
+        for chunk_size in 1..=example.len() + 1 {
+            assert_eq!(
+                sum_calibration_chunked(example.as_bytes(), chunk_size, &lexicon)?,
+                u64::from(expected),
+                "chunks of {chunk_size}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn other_languages() -> Result<()> {
+        let lexicon: DigitLexicon = indoc! {"
+            # German, with an alias
+            eins=1
+            zwei = 2
+            drei=3
+            fünf=5
+            sieben=7
+            acht=8
+            neun=9
+            zwo=2
+        "}
+        .parse()?;
+        for (line, expected) in [
+            ("xfünfzweix", 52),
+            ("achtzwo", 82),
+            ("einsiebenx", 17),
+            ("7neunacht", 78),
+            ("dreineun", 39),
+        ] {
+            assert_eq!(calibration(line, &lexicon)?, expected, "{line}");
+        }
+        assert!(calibration("onetwo", &lexicon).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn longest_word_wins() -> Result<()> {
+        let roman = DigitLexicon::new([("i", 1), ("ii", 2), ("iii", 3), ("iv", 4), ("v", 5)]);
+        assert_eq!(calibration("xiiix", &roman)?, 33);
+        assert_eq!(calibration("ivxv", &roman)?, 45);
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_lexicon() {
+        let err = "un=1\n\ndeux 2\n".parse::<DigitLexicon>().unwrap_err();
+        assert_eq!((err.line(), err.column()), (3, 6));
+        let err = "un=1\nun=2\n".parse::<DigitLexicon>().unwrap_err();
+        assert_eq!(err.line(), 2);
+        // values are digits, so calibrations stay two digits long
+        let err = "un=1\ndix = 10\n".parse::<DigitLexicon>().unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 7));
+        assert_eq!(err.expected(), "a digit from 0 to 9");
+    }
+
+    #[test]
+    fn shipped_lexicons() -> Result<()> {
+        for (lexicon, line, expected) in [
+            (include_str!("../lexicons/french.txt"), "xneufdeuxzéro", 90),
+            (include_str!("../lexicons/german.txt"), "fünfsiebenzwo", 52),
+            (include_str!("../lexicons/roman.txt"), "viiiiv", 84),
+        ] {
+            assert_eq!(calibration(line, &lexicon.parse()?)?, expected, "{line}");
+        }
         Ok(())
     }
 }
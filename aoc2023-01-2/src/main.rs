@@ -1,11 +1,71 @@
+use std::{io, path::PathBuf};
+
+use aoc2023_01_1::chunked::DEFAULT_CHUNK_SIZE;
+use aoc2023_01_2::{
+    audit::{audit, audit_strict},
+    phrase::{sum_phrase_calibration, Combine},
+    sum_calibration_chunked, DigitLexicon,
+};
+use clap::{Parser, ValueEnum};
 use color_eyre::Result;
-use std::io;
 
-use aoc2023_01_2::sum_calibration;
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+}
+
+#[derive(Parser)]
+#[command(about = "Sum calibration values read from stdin")]
+struct Cli {
+    /// Digit words to use in place of English, as `word=value` lines
+    lexicon: Option<PathBuf>,
+    /// Only count digits, as in part 1
+    #[arg(long, conflicts_with = "lexicon")]
+    digits_only: bool,
+    /// Explain the total line by line
+    #[arg(long)]
+    audit: Option<Format>,
+    /// Fail on the first line without a digit, instead of skipping it
+    #[arg(long)]
+    strict: bool,
+    /// Read whole English numbers like `one hundred and five`, combining the
+    /// first and last by `concatenate` or `tens-and-units`
+    #[arg(long, conflicts_with_all = ["lexicon", "digits_only", "audit", "strict"])]
+    phrases: Option<Combine>,
+    /// Bytes to read at a time when just summing
+    #[arg(long, default_value_t = DEFAULT_CHUNK_SIZE)]
+    chunk_size: usize,
+}
 
 fn main() -> Result<()> {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = sum_calibration(lines)?;
-    println!("{answer}");
+    color_eyre::install()?;
+    let cli = Cli::parse();
+    let lexicon = match (&cli.lexicon, cli.digits_only) {
+        (Some(path), _) => DigitLexicon::load(path)?,
+        (None, true) => DigitLexicon::digits(),
+        (None, false) => DigitLexicon::default(),
+    };
+    if let Some(combine) = cli.phrases {
+        let lines = io::stdin().lines().map_while(Result::ok);
+        println!("{}", sum_phrase_calibration(lines, combine)?);
+        return Ok(());
+    }
+    if cli.audit.is_none() && !cli.strict {
+        let total = sum_calibration_chunked(io::stdin().lock(), cli.chunk_size, &lexicon)?;
+        println!("{total}");
+        return Ok(());
+    }
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let audit = if cli.strict {
+        audit_strict(lines, &lexicon)?
+    } else {
+        audit(lines, &lexicon)
+    };
+    match cli.audit {
+        Some(Format::Table) => println!("{audit}"),
+        Some(Format::Json) => println!("{}", audit.to_json()),
+        None => println!("{}", audit.total),
+    }
     Ok(())
 }
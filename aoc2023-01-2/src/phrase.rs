@@ -0,0 +1,384 @@
+//! Whole English numbers, like `twenty-one` or `one hundred and five`, for
+//! calibrating free text rather than puzzle input.
+//!
+//! Words within a number are separated by spaces or hyphens, and matching
+//! ignores ASCII case. As with digit words, numbers may overlap: `eightwo`
+//! holds both 8 and 2. A lone digit is a number of its own, so `42` is 4
+//! then 2, just as for the puzzle.
+
+use std::{fmt, iter, str::FromStr, sync::OnceLock};
+
+use thiserror::Error;
+
+use crate::lexicon::{DigitLexicon, ENGLISH};
+
+const TEENS: [&str; 10] = [
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [(&str, u64); 8] = [
+    ("twenty", 20),
+    ("thirty", 30),
+    ("forty", 40),
+    ("fifty", 50),
+    ("sixty", 60),
+    ("seventy", 70),
+    ("eighty", 80),
+    ("ninety", 90),
+];
+
+const SCALES: [(&str, u64); 3] = [
+    ("billion", 1_000_000_000),
+    ("million", 1_000_000),
+    ("thousand", 1_000),
+];
+
+/// How the first and last numbers of a line make its calibration value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Combine {
+    /// `21` and `5` make `215`.
+    #[default]
+    Concatenate,
+    /// `first * 10 + last`, so `21` and `5` make `215` but `2` and `15`
+    /// make `35`.
+    TensAndUnits,
+}
+
+impl Combine {
+    /// The combined value, unless it's too big for a `u64`.
+    pub fn apply(self, first: u64, last: u64) -> Option<u64> {
+        let scale = match self {
+            Self::Concatenate => 10u64.checked_pow(last.checked_ilog10().unwrap_or(0) + 1)?,
+            Self::TensAndUnits => 10,
+        };
+        first.checked_mul(scale)?.checked_add(last)
+    }
+}
+
+/// First and last numbers whose combined value is too big for a `u64`.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("{first} and {last} combine to more than {}", u64::MAX)]
+pub struct Overflow {
+    pub first: u64,
+    pub last: u64,
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhraseError {
+    /// A line, counting from 1, whose value overflows.
+    #[error("Unable to calibrate line {line}")]
+    Line {
+        line: usize,
+        #[source]
+        source: Overflow,
+    },
+    #[error("The calibration values add up to more than {}", u64::MAX)]
+    Sum,
+}
+
+/// A number found in a line, spanning the bytes `start..end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Number {
+    pub start: usize,
+    pub end: usize,
+    pub value: u64,
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}..{}]", self.value, self.start, self.end)
+    }
+}
+
+/// The longest number starting at each position of `line`, found by
+/// scanning for the words and digits a number can start with.
+pub fn numbers(line: &str) -> impl Iterator<Item = Number> + '_ {
+    let lower = line.to_ascii_lowercase();
+    let mut from = 0;
+    iter::from_fn(move || {
+        let start = from + starters().first_token(&lower[from..])?.start;
+        from = start + 1;
+        let (len, value) =
+            number(&lower.as_bytes()[start..]).expect("every starter begins a number");
+        Some(Number {
+            start,
+            end: start + len,
+            value,
+        })
+    })
+}
+
+/// The number starting earliest, taking as much of it as possible.
+pub fn first_number(line: &str) -> Option<Number> {
+    numbers(line).next()
+}
+
+/// The number ending latest, taking as much of it as possible, so the last
+/// number of `forty-two` is 42 rather than 2.
+pub fn last_number(line: &str) -> Option<Number> {
+    numbers(line).reduce(|last, n| if n.end > last.end { n } else { last })
+}
+
+/// The line's value, or `None` if it holds no numbers.
+pub fn calibration(line: &str, combine: Combine) -> Result<Option<u64>, Overflow> {
+    let (Some(first), Some(last)) = (first_number(line), last_number(line)) else {
+        return Ok(None);
+    };
+    let (first, last) = (first.value, last.value);
+    combine
+        .apply(first, last)
+        .map(Some)
+        .ok_or(Overflow { first, last })
+}
+
+/// Sum of the lines' values, skipping lines without numbers.
+pub fn sum_phrase_calibration(
+    it: impl Iterator<Item = String>,
+    combine: Combine,
+) -> Result<u64, PhraseError> {
+    it.enumerate().try_fold(0u64, |sum, (i, line)| {
+        let value = calibration(&line, combine).map_err(|source| PhraseError::Line {
+            line: i + 1,
+            source,
+        })?;
+        sum.checked_add(value.unwrap_or(0)).ok_or(PhraseError::Sum)
+    })
+}
+
+/// Every word a number below a hundred can be made of, with its value.
+fn words() -> &'static [(&'static str, u64)] {
+    static WORDS: OnceLock<Vec<(&str, u64)>> = OnceLock::new();
+    WORDS.get_or_init(|| {
+        let units = ENGLISH.iter().copied().zip(0..);
+        let teens = TEENS.iter().copied().zip(10..);
+        units.chain(teens).chain(TENS).collect()
+    })
+}
+
+/// The digits and words a number can start with.
+fn starters() -> &'static DigitLexicon {
+    static STARTERS: OnceLock<DigitLexicon> = OnceLock::new();
+    STARTERS.get_or_init(|| {
+        DigitLexicon::new(words().iter().map(|&(word, value)| (word, value as u32)))
+    })
+}
+
+/// The length and value of the number at the start of `s`.
+fn number(s: &[u8]) -> Option<(usize, u64)> {
+    if let Some(digit) = s.first().filter(|b| b.is_ascii_digit()) {
+        return Some((1, u64::from(digit - b'0')));
+    }
+    let (mut len, mut group) = below_thousand(s)?;
+    let mut total = 0;
+    let mut scales = &SCALES[..];
+    // each scale word may follow only the larger ones, as in "two million
+    // five thousand and six"
+    while let Some((after, i)) = separated(s, len, |rest| longest(rest, scales)) {
+        total += group * scales[i].1;
+        scales = &scales[i + 1..];
+        len = after;
+        group = 0;
+        if let Some((after, more)) = remainder(s, len) {
+            len = after;
+            group = more;
+        }
+    }
+    Some((len, total + group))
+}
+
+/// 0 to 999.
+fn below_thousand(s: &[u8]) -> Option<(usize, u64)> {
+    let (len, value) = below_hundred(s)?;
+    if !(1..10).contains(&value) {
+        return Some((len, value));
+    }
+    let Some((len, _)) = separated(s, len, |rest| word(rest, "hundred")) else {
+        return Some((len, value));
+    };
+    match remainder(s, len) {
+        Some((len, rest)) if rest < 100 => Some((len, value * 100 + rest)),
+        _ => Some((len, value * 100)),
+    }
+}
+
+/// 0 to 99.
+fn below_hundred(s: &[u8]) -> Option<(usize, u64)> {
+    let words = words();
+    let (len, i) = longest(s, words)?;
+    let value = words[i].1;
+    if value < 20 {
+        return Some((len, value));
+    }
+    // only one to nine may follow the tens
+    match separated(s, len, |rest| longest(rest, &words[1..10])) {
+        Some((len, unit)) => Some((len, value + unit as u64 + 1)),
+        None => Some((len, value)),
+    }
+}
+
+/// The part of a number after `hundred` or a scale, with an optional `and`.
+fn remainder(s: &[u8], at: usize) -> Option<(usize, u64)> {
+    let and = separated(s, at, |rest| word(rest, "and"));
+    let at = and.map_or(at, |(len, _)| len);
+    separated(s, at, below_thousand)
+}
+
+/// `parse` applied after at least one space or hyphen from `at`, returning
+/// the total length up to the end of what it parsed.
+fn separated<T>(
+    s: &[u8],
+    at: usize,
+    parse: impl FnOnce(&[u8]) -> Option<(usize, T)>,
+) -> Option<(usize, T)> {
+    let gap = s[at..]
+        .iter()
+        .take_while(|&&b| b == b' ' || b == b'-')
+        .count();
+    if gap == 0 {
+        return None;
+    }
+    let (len, value) = parse(&s[at + gap..])?;
+    Some((at + gap + len, value))
+}
+
+fn word(s: &[u8], word: &str) -> Option<(usize, ())> {
+    s.starts_with(word.as_bytes()).then_some((word.len(), ()))
+}
+
+/// The length and index of the longest of `words` that `s` starts with.
+fn longest<T>(s: &[u8], words: &[(&str, T)]) -> Option<(usize, usize)> {
+    words
+        .iter()
+        .enumerate()
+        .filter(|(_, (w, _))| s.starts_with(w.as_bytes()))
+        .max_by_key(|(_, (w, _))| w.len())
+        .map(|(i, (w, _))| (w.len(), i))
+}
+
+impl FromStr for Combine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "concatenate" => Ok(Self::Concatenate),
+            "tens-and-units" => Ok(Self::TensAndUnits),
+            _ => Err(format!(
+                "expected concatenate or tens-and-units, found {s:?}"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn phrases() {
+        for (text, expected) in [
+            ("seven", 7),
+            ("seventeen", 17),
+            ("twenty-one", 21),
+            ("Ninety Nine", 99),
+            ("one hundred and five", 105),
+            ("three hundred twelve", 312),
+            ("two thousand and twenty-three", 2023),
+            ("one million two hundred thousand", 1_200_000),
+            ("four billion", 4_000_000_000),
+        ] {
+            let n = first_number(text).unwrap();
+            assert_eq!(
+                (n.start, n.end, n.value),
+                (0, text.len(), expected),
+                "{text}"
+            );
+        }
+        // a dangling word is left off the number
+        assert_eq!(first_number("five hundred and").unwrap().value, 500);
+        assert_eq!(first_number("twentyone").unwrap().value, 20);
+        assert_eq!(first_number("hundred"), None);
+    }
+
+    #[test]
+    fn first_and_last() {
+        let last = |line| last_number(line).unwrap().value;
+        assert_eq!(last("reading: forty-two"), 42);
+        assert_eq!(last("eightwo"), 2);
+        assert_eq!(last("3 then one hundred and five"), 105);
+        assert_eq!(last("fifty-oneight"), 8);
+        assert_eq!(first_number("xtwone3four").unwrap().value, 2);
+        assert_eq!(
+            last_number("one hundred and five").map(|n| n.to_string()),
+            Some("105 [0..20]".to_string())
+        );
+    }
+
+    #[test]
+    fn combining() {
+        assert_eq!(Combine::Concatenate.apply(21, 5), Some(215));
+        assert_eq!(Combine::Concatenate.apply(2, 15), Some(215));
+        assert_eq!(Combine::Concatenate.apply(4, 0), Some(40));
+        assert_eq!(Combine::TensAndUnits.apply(2, 15), Some(35));
+        assert_eq!(Combine::Concatenate.apply(2, u64::MAX), None);
+        assert_eq!(Combine::TensAndUnits.apply(u64::MAX / 10, 6), None);
+        assert_eq!("tens-and-units".parse(), Ok(Combine::TensAndUnits));
+        assert!("sum".parse::<Combine>().is_err());
+    }
+
+    #[test]
+    fn sensor_log() {
+        let log = indoc! {"
+            sensor A read twenty-one then five
+            Sensor B: one hundred and five, later 7
+            nothing to report
+            7pqrstsixteen
+        "};
+        let lines = || log.lines().map(String::from);
+        assert_eq!(
+            sum_phrase_calibration(lines(), Combine::Concatenate),
+            Ok(215 + 1057 + 716)
+        );
+        assert_eq!(
+            sum_phrase_calibration(lines(), Combine::TensAndUnits),
+            Ok(215 + 1057 + 86)
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let log = indoc! {"
+            one
+            two billion
+        "};
+        assert_eq!(
+            sum_phrase_calibration(log.lines().map(String::from), Combine::Concatenate),
+            Err(PhraseError::Line {
+                line: 2,
+                source: Overflow {
+                    first: 2_000_000_000,
+                    last: 2_000_000_000
+                }
+            })
+        );
+        assert_eq!(
+            sum_phrase_calibration(log.lines().map(String::from), Combine::TensAndUnits),
+            Ok(11 + 22_000_000_000)
+        );
+        // each line fits, at 10^19 + 10^9, but not both
+        let log = "one billion\none billion\n";
+        assert_eq!(
+            sum_phrase_calibration(log.lines().map(String::from), Combine::Concatenate),
+            Err(PhraseError::Sum)
+        );
+    }
+}
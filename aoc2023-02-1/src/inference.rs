@@ -0,0 +1,285 @@
+//! What the rounds of a game say about the bag they came from.
+//!
+//! The `BitOr` fold over rounds only gives the fewest cubes the bag could
+//! hold. Here each round is instead a handful drawn without replacement and
+//! then put back, so a bag of `b` cubes per colour shows the counts `k` in a
+//! round with probability
+//!
+//! ```text
+//! C(b_red, k_red) × C(b_green, k_green) × … / C(Σ b, Σ k)
+//! ```
+//!
+//! Rounds are independent, so a game's likelihood is the product over its
+//! rounds. With every bag holding up to some limit of each colour equally
+//! likely beforehand, the posterior is that likelihood, normalised.
+
+use std::ops::RangeInclusive;
+
+use crate::{Game, GameRound};
+
+/// Bags with up to `limit` cubes of each of `colours`.
+///
+/// Every combination is visited, so the work grows as `(limit + 1)` to the
+/// power of the number of colours.
+#[derive(Debug, Clone)]
+pub struct Inference {
+    colours: Vec<String>,
+    limit: u32,
+    /// `ln(n!)` for every `n` up to the largest bag.
+    ln_factorial: Vec<f64>,
+}
+
+impl Inference {
+    pub fn new<S: Into<String>>(colours: impl IntoIterator<Item = S>, limit: u32) -> Self {
+        let colours: Vec<String> = colours.into_iter().map(Into::into).collect();
+        let largest = limit as usize * colours.len();
+        let ln_factorial = (0..=largest)
+            .scan(0.0, |acc, n| {
+                if n > 0 {
+                    *acc += (n as f64).ln();
+                }
+                Some(*acc)
+            })
+            .collect();
+        Self {
+            colours,
+            limit,
+            ln_factorial,
+        }
+    }
+
+    fn ln_choose(&self, n: u64, k: u64) -> f64 {
+        if k > n {
+            return f64::NEG_INFINITY;
+        }
+        let f = |i: u64| self.ln_factorial[i as usize];
+        f(n) - f(k) - f(n - k)
+    }
+
+    /// `ln` of the probability of drawing `round` from `bag`.
+    fn ln_round(&self, round: &GameRound, bag: &[u32]) -> f64 {
+        let shown = round.total();
+        let listed: u64 = self.colours.iter().map(|c| u64::from(round.get(c))).sum();
+        let held: u64 = bag.iter().map(|&b| u64::from(b)).sum();
+        if shown > listed || shown > held {
+            // a colour this bag can't hold, or more cubes than it holds
+            return f64::NEG_INFINITY;
+        }
+        let ways: f64 = self
+            .colours
+            .iter()
+            .zip(bag)
+            .map(|(colour, &b)| self.ln_choose(b.into(), round.get(colour).into()))
+            .sum();
+        ways - self.ln_choose(held, shown)
+    }
+
+    fn ln_likelihood<'a>(&self, games: impl IntoIterator<Item = &'a Game>, bag: &[u32]) -> f64 {
+        games
+            .into_iter()
+            .flat_map(|game| &game.rounds)
+            .map(|round| self.ln_round(round, bag))
+            .sum()
+    }
+
+    fn counts(&self, bag: &GameRound) -> Option<Vec<u32>> {
+        let counts: Vec<u32> = self.colours.iter().map(|c| bag.get(c)).collect();
+        let listed: u64 = counts.iter().map(|&n| u64::from(n)).sum();
+        let fits = counts.iter().all(|&n| n <= self.limit);
+        (fits && listed == bag.total()).then_some(counts)
+    }
+
+    /// The probability of `games` showing what they did, had they all been
+    /// played with `bag`.
+    pub fn likelihood<'a>(
+        &self,
+        games: impl IntoIterator<Item = &'a Game>,
+        bag: &GameRound,
+    ) -> f64 {
+        let Some(counts) = self.counts(bag) else {
+            return 0.0;
+        };
+        self.ln_likelihood(games, &counts).exp()
+    }
+
+    /// The probability of each possible bag, given `games` were all played
+    /// with the same one.
+    pub fn posterior<'a>(&self, games: impl IntoIterator<Item = &'a Game> + Clone) -> Posterior {
+        let mut bags = Vec::new();
+        let mut bag = vec![0; self.colours.len()];
+        loop {
+            let ln = self.ln_likelihood(games.clone(), &bag);
+            if ln > f64::NEG_INFINITY {
+                bags.push((bag.clone(), ln));
+            }
+            // count up through every bag like an odometer
+            let Some(i) = bag.iter().rposition(|&n| n < self.limit) else {
+                break;
+            };
+            bag[i] += 1;
+            bag[i + 1..].fill(0);
+        }
+        // scale by the most likely before leaving log space, so nothing
+        // underflows to zero
+        let most = bags
+            .iter()
+            .map(|(_, ln)| *ln)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let total: f64 = bags.iter().map(|(_, ln)| (ln - most).exp()).sum();
+        Posterior {
+            colours: self.colours.clone(),
+            bags: bags
+                .into_iter()
+                .map(|(bag, ln)| (bag, (ln - most).exp() / total))
+                .collect(),
+        }
+    }
+}
+
+/// Possible bags and how likely each is, in the order of
+/// [`Inference::posterior`]'s search.
+#[derive(Debug, Clone)]
+pub struct Posterior {
+    colours: Vec<String>,
+    bags: Vec<(Vec<u32>, f64)>,
+}
+
+impl Posterior {
+    fn round(&self, counts: &[u32]) -> GameRound {
+        self.colours
+            .iter()
+            .map(String::as_str)
+            .zip(counts.iter().copied())
+            .collect()
+    }
+
+    /// The most likely bag, or the smallest of several equally likely ones.
+    /// `None` if no bag within the limit could have been used.
+    pub fn most_likely(&self) -> Option<GameRound> {
+        let (bag, _) = self
+            .bags
+            .iter()
+            .reduce(|best, bag| if bag.1 > best.1 { bag } else { best })?;
+        Some(self.round(bag))
+    }
+
+    /// How likely it is that `bag` was used.
+    pub fn probability(&self, bag: &GameRound) -> f64 {
+        self.bags
+            .iter()
+            .find(|(counts, _)| self.round(counts) == *bag)
+            .map_or(0.0, |(_, p)| *p)
+    }
+
+    /// The probability of each count of `colour`, from none upwards.
+    pub fn marginal(&self, colour: &str) -> Vec<f64> {
+        let Some(i) = self.colours.iter().position(|c| c == colour) else {
+            return Vec::new();
+        };
+        let mut marginal = Vec::new();
+        for (bag, p) in &self.bags {
+            let n = bag[i] as usize;
+            if marginal.len() <= n {
+                marginal.resize(n + 1, 0.0);
+            }
+            marginal[n] += p;
+        }
+        marginal
+    }
+
+    /// The counts of `colour` holding the central `level` (e.g. 0.9) of its
+    /// probability, cutting off equal tails either side.
+    pub fn credible_range(&self, colour: &str, level: f64) -> Option<RangeInclusive<u32>> {
+        let tail = (1.0 - level) / 2.0;
+        let marginal = self.marginal(colour);
+        let mut seen = 0.0;
+        let mut low = None;
+        for (n, p) in marginal.iter().enumerate() {
+            seen += p;
+            if low.is_none() && seen > tail {
+                low = Some(n as u32);
+            }
+            if seen >= 1.0 - tail - f64::EPSILON * 8.0 {
+                return Some(low?..=n as u32);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Palette;
+
+    fn game(line: &str) -> Game {
+        Game::parse(line, &Palette::Inferred).unwrap()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn likelihood() {
+        let inference = Inference::new(["red", "blue"], 5);
+        let bag = GameRound::default().red(2).blue(1);
+        // C(2,1) C(1,1) / C(3,2)
+        let one_each = game("Game 1: 1 red, 1 blue");
+        assert!(close(inference.likelihood([&one_each], &bag), 2.0 / 3.0));
+        // rounds are put back in between
+        let twice = game("Game 1: 1 red, 1 blue; 1 blue, 1 red");
+        assert!(close(inference.likelihood([&twice], &bag), 4.0 / 9.0));
+        assert!(close(
+            inference.likelihood([&one_each, &twice], &bag),
+            8.0 / 27.0
+        ));
+        assert_eq!(inference.likelihood([&game("Game 1: 2 blue")], &bag), 0.0);
+        assert_eq!(inference.likelihood([&game("Game 1: 1 teal")], &bag), 0.0);
+        let too_big = GameRound::default().red(6).blue(1);
+        assert_eq!(inference.likelihood([&one_each], &too_big), 0.0);
+        // fewer cubes in the bag than the round shows
+        let small = GameRound::default().red(1);
+        assert_eq!(inference.likelihood([&game("Game 1: 2 red")], &small), 0.0);
+        let empty = GameRound::default();
+        assert_eq!(inference.likelihood([&one_each], &empty), 0.0);
+    }
+
+    #[test]
+    fn posterior() {
+        let inference = Inference::new(["red", "blue"], 3);
+        let posterior = inference.posterior([&game("Game 1: 2 red")]);
+        // an all red bag is certain to show two red, however big
+        assert_eq!(posterior.most_likely(), Some(GameRound::default().red(2)));
+        let total: f64 = posterior.marginal("red").iter().sum();
+        assert!(close(total, 1.0));
+        assert_eq!(posterior.marginal("red")[..2], [0.0, 0.0]);
+        assert_eq!(posterior.credible_range("red", 0.9), Some(2..=3));
+        assert_eq!(posterior.probability(&GameRound::default().red(1)), 0.0);
+
+        let none = inference.posterior([&game("Game 1: 4 red")]);
+        assert_eq!(none.most_likely(), None);
+        assert_eq!(none.credible_range("red", 0.9), None);
+    }
+
+    #[test]
+    fn puzzle_bag() {
+        let inference = Inference::new(["red", "green", "blue"], 15);
+        let bag = GameRound::default().red(12).green(13).blue(14);
+        let possible = game("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green");
+        let posterior = inference.posterior([&possible]);
+        let p = posterior.probability(&bag);
+        assert!(p > 0.0 && p < 1.0, "{p}");
+        let most_likely = posterior.most_likely().unwrap();
+        assert!(possible.cubes.lte(&most_likely));
+        assert!(posterior.probability(&most_likely) >= p);
+        for colour in ["red", "green", "blue"] {
+            let range = posterior.credible_range(colour, 0.9).unwrap();
+            assert!(range.contains(&most_likely.get(colour)), "{colour}");
+            assert!(*range.start() >= possible.cubes.get(colour));
+        }
+
+        let impossible = game("Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green");
+        assert_eq!(inference.likelihood([&impossible], &bag), 0.0);
+    }
+}
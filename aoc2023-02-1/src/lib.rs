@@ -1,105 +1,206 @@
-use std::{cmp, num::ParseIntError, ops::BitOr, sync::OnceLock};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    num::ParseIntError,
+    ops::BitOr,
+    sync::OnceLock,
+};
 
+use aoc2023_solution::{Result, Solution};
 use regex::Regex;
 use thiserror::Error;
 
-#[derive(Debug, Default, Eq, PartialEq)]
-pub struct GameRound {
-    pub red: u32,
-    pub green: u32,
-    pub blue: u32,
+pub mod inference;
+pub mod query;
+
+/// The colours cubes come in.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Palette {
+    /// Only these colours, any other being an error.
+    Declared(BTreeSet<String>),
+    /// Whatever colours turn up in the input.
+    Inferred,
 }
 
+impl Palette {
+    pub fn new<S: Into<String>>(colours: impl IntoIterator<Item = S>) -> Self {
+        Self::Declared(colours.into_iter().map(Into::into).collect())
+    }
+
+    fn check(&self, colour: &str) -> std::result::Result<(), GameRoundSpecError> {
+        match self {
+            Self::Declared(colours) if !colours.contains(colour) => {
+                Err(GameRoundSpecError::UnreachableColour(colour.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The declared colours, or else every colour in `rounds`.
+    pub fn colours<'a>(&'a self, rounds: impl IntoIterator<Item = &'a GameRound>) -> Vec<&'a str> {
+        match self {
+            Self::Declared(colours) => colours.iter().map(String::as_str).collect(),
+            Self::Inferred => rounds
+                .into_iter()
+                .flat_map(|round| round.0.keys().map(String::as_str))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+/// Red, green and blue, as in the puzzle.
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new(["red", "green", "blue"])
+    }
+}
+
+/// A number of cubes of each colour, with colours not mentioned counting as
+/// none.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct GameRound(BTreeMap<String, u32>);
+
 impl GameRound {
-    pub fn red(mut self, red: u32) -> Self {
-        self.red = red;
+    pub fn with(mut self, colour: &str, count: u32) -> Self {
+        // no zero counts are kept, so equal sets compare equal
+        if count == 0 {
+            self.0.remove(colour);
+        } else {
+            self.0.insert(colour.to_string(), count);
+        }
         self
     }
-    pub fn green(mut self, green: u32) -> Self {
-        self.green = green;
-        self
+    pub fn red(self, red: u32) -> Self {
+        self.with("red", red)
     }
-    pub fn blue(mut self, blue: u32) -> Self {
-        self.blue = blue;
-        self
+    pub fn green(self, green: u32) -> Self {
+        self.with("green", green)
+    }
+    pub fn blue(self, blue: u32) -> Self {
+        self.with("blue", blue)
+    }
+
+    pub fn get(&self, colour: &str) -> u32 {
+        self.0.get(colour).copied().unwrap_or(0)
+    }
+
+    /// How many cubes of any colour.
+    pub fn total(&self) -> u64 {
+        self.0.values().map(|&count| u64::from(count)).sum()
+    }
+
+    /// Whether these cubes could all have come out of `bag`.
+    pub fn lte(&self, bag: &GameRound) -> bool {
+        self.0
+            .iter()
+            .all(|(colour, &count)| count <= bag.get(colour))
+    }
+
+    /// The product of the counts of each of `colours`, unless it overflows.
+    pub fn power<'a>(&self, colours: impl IntoIterator<Item = &'a str>) -> Option<u64> {
+        colours.into_iter().try_fold(1u64, |power, colour| {
+            power.checked_mul(self.get(colour).into())
+        })
+    }
+
+    /// Parse a round such as ` 3 blue, 4 red`, allowing only colours in
+    /// `palette`.
+    pub fn parse(value: &str, palette: &Palette) -> std::result::Result<Self, GameRoundSpecError> {
+        static CUBE_COUNT: OnceLock<Regex> = OnceLock::new();
+        let matches = CUBE_COUNT
+            .get_or_init(|| Regex::new(r"\b(\d+)\s+(\w+)\b").unwrap())
+            .captures_iter(value)
+            .map(|c| c.extract().1);
+
+        let mut round = Self::default();
+        for [num, colour] in matches {
+            palette.check(colour)?;
+            round = round.with(colour, num.parse()?);
+        }
+        Ok(round)
     }
 }
 
+impl<S: AsRef<str>> FromIterator<(S, u32)> for GameRound {
+    fn from_iter<I: IntoIterator<Item = (S, u32)>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::default(), |round, (colour, count)| {
+                round.with(colour.as_ref(), count)
+            })
+    }
+}
+
+/// As in the puzzle, e.g. `3 blue, 4 red`, with colours in alphabetical
+/// order.
+impl fmt::Display for GameRound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cubes: Vec<String> = self
+            .0
+            .iter()
+            .map(|(colour, count)| format!("{count} {colour}"))
+            .collect();
+        write!(f, "{}", cubes.join(", "))
+    }
+}
+
+/// The most of each colour seen in either.
 impl BitOr for GameRound {
     type Output = Self;
 
-    fn bitor(self, rhs: Self) -> Self::Output {
-        Self::Output {
-            red: cmp::max(self.red, rhs.red),
-            green: cmp::max(self.green, rhs.green),
-            blue: cmp::max(self.blue, rhs.blue),
+    fn bitor(mut self, rhs: Self) -> Self::Output {
+        for (colour, count) in rhs.0 {
+            let most = self.0.entry(colour).or_default();
+            *most = (*most).max(count);
         }
+        self
     }
 }
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, PartialEq)]
 pub enum GameRoundSpecError {
-    #[error("Unreachable colour 🤷")]
+    #[error("Unreachable colour {0} 🤷")]
     UnreachableColour(String),
     #[error("Unable to parse number of cubes")]
     CubeCount(#[from] ParseIntError),
 }
 
+/// Parses red, green and blue only.
 impl TryFrom<&str> for GameRound {
     type Error = GameRoundSpecError;
 
     fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
-        let mut red = 0;
-        let mut green = 0;
-        let mut blue = 0;
-
-        static CUBE_COUNT: OnceLock<Regex> = OnceLock::new();
-        let matches = CUBE_COUNT
-            .get_or_init(|| Regex::new(r"\b(\d+)\s+(red|green|blue)\b").unwrap())
-            .captures_iter(value)
-            .map(|c| c.extract().1);
-
-        for [num, colour] in matches {
-            match colour {
-                "red" => red = num.parse()?,
-                "green" => green = num.parse()?,
-                "blue" => blue = num.parse()?,
-                unk => return Err(GameRoundSpecError::UnreachableColour(unk.to_string())),
-            }
-        }
-
-        Ok(Self { red, green, blue })
+        Self::parse(value, &Palette::default())
     }
 }
 
 #[derive(Debug, Eq, PartialEq)]
-struct Game {
-    id: u32,
-    red: u32,
-    green: u32,
-    blue: u32,
+pub struct Game {
+    pub id: u32,
+    /// The cubes shown in each round, in order.
+    pub rounds: Vec<GameRound>,
+    /// The most of each colour shown in any round.
+    pub cubes: GameRound,
 }
 
 impl Game {
-    fn lte(&self, max: &GameRound) -> bool {
-        self.red <= max.red && self.green <= max.green && self.blue <= max.blue
+    pub fn lte(&self, bag: &GameRound) -> bool {
+        self.cubes.lte(bag)
     }
-}
 
-#[derive(Error, Debug)]
-pub enum GameSpecError {
-    #[error("Unable to parse game ID")]
-    GameIdParse,
-    #[error("Unable to parse game ID")]
-    GameIdFormat(#[from] ParseIntError),
-    #[error("Unable to parse rounds")]
-    RoundsParse,
-}
-
-impl TryFrom<&str> for Game {
-    type Error = GameSpecError;
+    /// Check a game read with an inferred palette shows only colours in
+    /// `palette`.
+    pub fn check(&self, palette: &Palette) -> std::result::Result<(), GameSpecError> {
+        for colour in self.cubes.0.keys() {
+            palette.check(colour)?;
+        }
+        Ok(())
+    }
 
-    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+    /// Parse a game, any round with a colour outside `palette` being an
+    /// error.
+    pub fn parse(value: &str, palette: &Palette) -> std::result::Result<Self, GameSpecError> {
         static GAME_ID: OnceLock<Regex> = OnceLock::new();
         let id = GAME_ID
             .get_or_init(|| Regex::new(r"^Game (?<id>\d+):").unwrap())
@@ -110,32 +211,122 @@ impl TryFrom<&str> for Game {
             .as_str()
             .parse::<u32>()?;
         static GAME_ROUND: OnceLock<Regex> = OnceLock::new();
-        let GameRound { red, green, blue } = GAME_ROUND
+        let rounds: Vec<GameRound> = GAME_ROUND
             // .get_or_init(|| Regex::new(r"[;:]([^;]+)[;$]").unwrap()) // this doesn't work
             // because `captures_iter` finds strictly non-overlapping matches
             .get_or_init(|| Regex::new(r"[;:]([^;]+)").unwrap())
             .captures_iter(value)
             .map(|c| (c.extract::<1>().1)[0])
-            .map(GameRound::try_from)
-            .filter_map(|r| r.ok())
+            .map(|round| GameRound::parse(round, palette))
+            .collect::<std::result::Result<_, _>>()?;
+        let cubes = rounds
+            .iter()
+            .cloned()
             .reduce(|acc, v| acc | v)
             .ok_or(GameSpecError::RoundsParse)?;
 
-        Ok(Self {
-            id,
-            red,
-            green,
-            blue,
+        Ok(Self { id, rounds, cubes })
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum GameSpecError {
+    #[error("Unable to parse game ID")]
+    GameIdParse,
+    #[error("Unable to parse game ID")]
+    GameIdFormat(#[from] ParseIntError),
+    #[error("Unable to parse rounds")]
+    RoundsParse,
+    #[error("Unable to parse round")]
+    Round(#[from] GameRoundSpecError),
+}
+
+/// A game that wouldn't parse, and the line it's on, counting from 1.
+#[derive(Error, Debug, PartialEq)]
+#[error("Unable to parse the game on line {line}")]
+pub struct GameLogError {
+    pub line: usize,
+    #[source]
+    pub source: GameSpecError,
+}
+
+/// Parse a game from each line, allowing only colours in `palette`.
+pub fn read_games(
+    it: impl Iterator<Item = String>,
+    palette: &Palette,
+) -> std::result::Result<Vec<Game>, GameLogError> {
+    it.enumerate()
+        .map(|(i, line)| {
+            Game::parse(&line, palette).map_err(|source| GameLogError {
+                line: i + 1,
+                source,
+            })
         })
+        .collect()
+}
+
+/// Parses red, green and blue only.
+impl TryFrom<&str> for Game {
+    type Error = GameSpecError;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        Self::parse(value, &Palette::default())
     }
 }
 
-pub fn sum_possible_ids(it: impl Iterator<Item = String>, max: &GameRound) -> u32 {
-    it.filter_map(|line| Game::try_from(line.as_str()).ok())
-        .filter(|game| game.lte(max))
+/// Sum the ids of games which `bag` could have been used for.
+pub fn possible_ids(games: &[Game], bag: &GameRound) -> u32 {
+    games
+        .iter()
+        .filter(|game| game.lte(bag))
         .fold(0, |acc, v| acc + v.id)
 }
 
+/// Sum the ids of games which `bag` could have been used for, whatever
+/// colours it holds.
+pub fn sum_possible_ids(
+    it: impl Iterator<Item = String>,
+    bag: &GameRound,
+) -> std::result::Result<u32, GameLogError> {
+    Ok(possible_ids(&read_games(it, &Palette::Inferred)?, bag))
+}
+
+/// The bag contents to test each game against.
+pub struct Params {
+    pub bag: GameRound,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            bag: GameRound::default().red(12).green(13).blue(14),
+        }
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 2;
+    const PART: u8 = 1;
+
+    /// The games, in whatever colours they show.
+    type Input = Vec<Game>;
+    type Params = Params;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(read_games(
+            input.lines().map(String::from),
+            &Palette::Inferred,
+        )?)
+    }
+
+    fn solve(input: Self::Input, params: &Self::Params) -> Result<Self::Answer> {
+        Ok(possible_ids(&input, &params.bag))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,38 +336,17 @@ mod tests {
     #[test]
     fn round_deserialize() -> Result<()> {
         let round = GameRound::try_from(" 3 blue, 4 red")?;
-        assert_eq!(
-            round,
-            GameRound {
-                red: 4,
-                green: 0,
-                blue: 3
-            }
-        );
+        assert_eq!(round, GameRound::default().red(4).blue(3));
+        assert_eq!(round.get("green"), 0);
         Ok(())
     }
 
     #[test]
     fn round_or() {
-        let round1 = GameRound {
-            red: 10,
-            green: 20,
-            blue: 30,
-        };
-        let round2 = GameRound {
-            red: 15,
-            green: 15,
-            blue: 20,
-        };
+        let round1 = GameRound::default().red(10).green(20).blue(30);
+        let round2 = GameRound::default().red(15).green(15).blue(20);
         let round3 = round1 | round2;
-        assert_eq!(
-            round3,
-            GameRound {
-                red: 15,
-                green: 20,
-                blue: 30
-            }
-        );
+        assert_eq!(round3, GameRound::default().red(15).green(20).blue(30));
     }
 
     #[test]
@@ -186,16 +356,71 @@ mod tests {
             game,
             Game {
                 id: 1,
-                red: 4,
-                green: 2,
-                blue: 6
+                rounds: vec![
+                    GameRound::default().red(4).blue(3),
+                    GameRound::default().red(1).green(2).blue(6),
+                    GameRound::default().green(2),
+                ],
+                cubes: GameRound::default().red(4).green(2).blue(6)
             }
         );
         Ok(())
     }
 
     #[test]
-    fn full_calculation() {
+    fn palettes() -> Result<()> {
+        let round = " 2 teal, 1 red";
+        assert!(matches!(
+            GameRound::try_from(round),
+            Err(GameRoundSpecError::UnreachableColour(colour)) if colour == "teal"
+        ));
+        let teal = Palette::new(["red", "teal"]);
+        let cubes = GameRound::parse(round, &teal)?;
+        assert_eq!(cubes, [("teal", 2), ("red", 1)].into_iter().collect());
+        let inferred = GameRound::parse(round, &Palette::Inferred)?
+            | GameRound::parse("5 mauve", &Palette::Inferred)?;
+        assert_eq!(
+            Palette::Inferred.colours([&inferred]),
+            ["mauve", "red", "teal"]
+        );
+        assert_eq!(
+            inferred.power(Palette::Inferred.colours([&inferred])),
+            Some(10)
+        );
+        assert_eq!(inferred.power(teal.colours([])), Some(2));
+        let big = GameRound::default().red(u32::MAX).green(u32::MAX).blue(2);
+        assert_eq!(big.power(["red", "green", "blue"]), None);
+        assert!(matches!(
+            Game::try_from("Game 1: 3 blue; 2 teal"),
+            Err(GameSpecError::Round(GameRoundSpecError::UnreachableColour(colour))) if colour == "teal"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn more_colours() -> Result<()> {
+        let example = indoc! {"
+            Game 1: 3 blue, 4 red; 1 teal
+            Game 2: 1 blue, 2 green
+            Game 3: 3 teal, 1 red; 2 teal
+        "};
+        let bag: GameRound = [("red", 5), ("green", 5), ("blue", 5), ("teal", 2)]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            sum_possible_ids(example.lines().map(String::from), &bag)?,
+            3
+        );
+        let no_teal = GameRound::default().red(5).green(5).blue(5);
+        assert_eq!(
+            sum_possible_ids(example.lines().map(String::from), &no_teal)?,
+            2
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn full_calculation() -> Result<()> {
         let example = indoc! {"
             Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
             Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
@@ -207,8 +432,36 @@ mod tests {
             sum_possible_ids(
                 example.lines().map(String::from),
                 &GameRound::default().red(12).green(13).blue(14)
-            ),
+            )?,
             8
         );
+        Ok(())
+    }
+
+    #[test]
+    fn solution() -> Result<()> {
+        let example = indoc! {"
+            Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+            Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+            Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+            Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+            Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+        "};
+        assert_eq!(Solver::run(example)?, 8);
+        let bag = GameRound::default().red(20).green(13).blue(15);
+        assert_eq!(Solver::solve(Solver::parse(example)?, &Params { bag })?, 15);
+        Ok(())
+    }
+
+    #[test]
+    fn unparsable_game() {
+        let example = indoc! {"
+            Game 1: 3 blue, 4 red
+            Game two: 1 blue
+        "};
+        let err = sum_possible_ids(example.lines().map(String::from), &Params::default().bag)
+            .unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(matches!(err.source, GameSpecError::GameIdParse));
     }
 }
@@ -1,11 +1,66 @@
 use color_eyre::Result;
 use std::io;
 
-use aoc2023_02_1::{sum_possible_ids, GameRound};
+use aoc2023_02_1::{
+    inference::Inference, query::query, read_games, sum_possible_ids, GameRound, Palette,
+};
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(about = "Sum the ids of games possible with a bag of cubes")]
+struct Cli {
+    /// The bag's contents, in any colours
+    #[arg(long, default_value = "12 red, 13 green, 14 blue", value_parser = bag)]
+    bag: GameRound,
+    /// Instead, answer a question such as `avg(power) where any(blue > 10)`
+    #[arg(long, conflicts_with = "infer")]
+    query: Option<String>,
+    /// Instead, infer each game's bag from its rounds, considering bags with
+    /// up to this many of each of the bag's colours
+    #[arg(long, value_name = "LIMIT")]
+    infer: Option<u32>,
+    /// How much probability each colour's credible range should hold
+    #[arg(long, default_value_t = 0.9, requires = "infer")]
+    level: f64,
+}
+
+fn bag(s: &str) -> Result<GameRound, String> {
+    GameRound::parse(s, &Palette::Inferred).map_err(|e| e.to_string())
+}
 
 fn main() -> Result<()> {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = sum_possible_ids(lines, &GameRound::default().red(12).green(13).blue(14));
-    println!("{answer}");
+    let cli = Cli::parse();
+    let lines = io::stdin().lines().map_while(Result::ok);
+    if let Some(q) = &cli.query {
+        println!("{}", query(lines, q)?);
+        return Ok(());
+    }
+    let Some(limit) = cli.infer else {
+        println!("{}", sum_possible_ids(lines, &cli.bag)?);
+        return Ok(());
+    };
+    let colours = Palette::Inferred.colours([&cli.bag]);
+    let inference = Inference::new(colours.iter().copied(), limit);
+    for game in read_games(lines, &Palette::Inferred)? {
+        let posterior = inference.posterior([&game]);
+        let Some(most_likely) = posterior.most_likely() else {
+            println!("Game {}: no bag within the limit", game.id);
+            continue;
+        };
+        let ranges: Vec<String> = colours
+            .iter()
+            .filter_map(|colour| {
+                let range = posterior.credible_range(colour, cli.level)?;
+                Some(format!("{colour} {}-{}", range.start(), range.end()))
+            })
+            .collect();
+        println!(
+            "Game {}: most likely {most_likely}; {}; P({}) = {:.3e}",
+            game.id,
+            ranges.join(", "),
+            cli.bag,
+            posterior.probability(&cli.bag)
+        );
+    }
     Ok(())
 }
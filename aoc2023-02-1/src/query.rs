@@ -0,0 +1,507 @@
+//! A little language for asking questions of game logs.
+//!
+//! ```text
+//! any(blue > 10)                        ids of games with a round of over 10 blue
+//! avg(power) where id > 50
+//! count where all(total <= 5) or not red >= 3
+//! sum(id) where red <= 12 and green <= 13 and blue <= 14
+//! ```
+//!
+//! A query is a condition, answered with the ids of the games meeting it, or
+//! one of `count`, `sum(..)`, `max(..)`, `min(..)` or `avg(..)` over the games
+//! meeting an optional `where` condition.
+//!
+//! Games have an `id`, a `power`, a number of `rounds` and the most of each
+//! colour shown in any one round. Within `any(..)` and `all(..)`, which ask
+//! about a game's rounds, there's the `total` number of cubes and each
+//! colour's count. Comparisons are `<`, `<=`, `>`, `>=`, `=` and `!=`, with
+//! `not` binding tighter than `and`, and `and` tighter than `or`. A power or
+//! sum too big for 64 bits is an error rather than an answer.
+
+use std::{fmt, str::FromStr};
+
+use aoc2023_parse::{finish, ParseError};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while1},
+    character::complete::{char, multispace0, satisfy, u64},
+    combinator::{cut, map, not, opt, value},
+    multi::many0,
+    sequence::{pair, preceded, terminated, tuple},
+    IResult,
+};
+use thiserror::Error;
+
+use crate::{read_games, Game, GameLogError, GameRound, Palette};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum QueryError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Games(#[from] GameLogError),
+    #[error("The power of game {0} is more than {max}", max = u64::MAX)]
+    Power(u32),
+    #[error("The sum is more than {}", u64::MAX)]
+    Sum,
+}
+
+/// What can be asked of a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameField {
+    Id,
+    /// The product of the most of each colour, over every colour in the log.
+    Power,
+    Rounds,
+    /// The most shown in any round.
+    Colour(String),
+}
+
+/// What can be asked of a round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoundField {
+    Total,
+    Colour(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Cmp {
+    fn holds(self, a: u64, b: u64) -> bool {
+        match self {
+            Self::Lt => a < b,
+            Self::Le => a <= b,
+            Self::Gt => a > b,
+            Self::Ge => a >= b,
+            Self::Eq => a == b,
+            Self::Ne => a != b,
+        }
+    }
+}
+
+/// A condition on games, or with `F` as [`RoundField`], on rounds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition<F> {
+    Compare(F, Cmp, u64),
+    Not(Box<Self>),
+    And(Box<Self>, Box<Self>),
+    Or(Box<Self>, Box<Self>),
+    /// At least one of a game's rounds meets the condition.
+    Any(Box<Condition<RoundField>>),
+    /// Every one of a game's rounds meets the condition.
+    All(Box<Condition<RoundField>>),
+}
+
+/// Something conditions can be checked against.
+trait Subject<F> {
+    fn value(&self, field: &F) -> Result<u64, QueryError>;
+    fn rounds(&self) -> &[GameRound];
+}
+
+/// A game, along with every colour in the log for working out its power.
+struct Scored<'a> {
+    game: &'a Game,
+    colours: &'a [&'a str],
+}
+
+impl Subject<GameField> for Scored<'_> {
+    fn value(&self, field: &GameField) -> Result<u64, QueryError> {
+        Ok(match field {
+            GameField::Id => self.game.id.into(),
+            GameField::Power => self
+                .game
+                .cubes
+                .power(self.colours.iter().copied())
+                .ok_or(QueryError::Power(self.game.id))?,
+            GameField::Rounds => self.game.rounds.len() as u64,
+            GameField::Colour(colour) => self.game.cubes.get(colour).into(),
+        })
+    }
+
+    fn rounds(&self) -> &[GameRound] {
+        &self.game.rounds
+    }
+}
+
+impl Subject<RoundField> for GameRound {
+    fn value(&self, field: &RoundField) -> Result<u64, QueryError> {
+        Ok(match field {
+            RoundField::Total => self.total(),
+            RoundField::Colour(colour) => self.get(colour).into(),
+        })
+    }
+
+    fn rounds(&self) -> &[GameRound] {
+        &[]
+    }
+}
+
+impl<F> Condition<F> {
+    fn holds(&self, subject: &impl Subject<F>) -> Result<bool, QueryError> {
+        Ok(match self {
+            Self::Compare(field, cmp, n) => cmp.holds(subject.value(field)?, *n),
+            Self::Not(c) => !c.holds(subject)?,
+            Self::And(a, b) => a.holds(subject)? && b.holds(subject)?,
+            Self::Or(a, b) => a.holds(subject)? || b.holds(subject)?,
+            Self::Any(c) => {
+                for round in subject.rounds() {
+                    if c.holds(round)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+            Self::All(c) => {
+                for round in subject.rounds() {
+                    if !c.holds(round)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Aggregate {
+    Count,
+    Sum(GameField),
+    Max(GameField),
+    Min(GameField),
+    Average(GameField),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    /// Without one, the answer is the matching games' ids.
+    pub aggregate: Option<Aggregate>,
+    /// Without one, every game matches.
+    pub filter: Option<Condition<GameField>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Games(Vec<u32>),
+    Number(u64),
+    Average(f64),
+    /// The max, min or average of no games at all.
+    Nothing,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Games(ids) => {
+                let ids: Vec<String> = ids.iter().map(u32::to_string).collect();
+                write!(f, "{}", ids.join(" "))
+            }
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Average(n) => write!(f, "{n}"),
+            Self::Nothing => write!(f, "-"),
+        }
+    }
+}
+
+impl Query {
+    pub fn eval(&self, games: &[Game]) -> Result<Answer, QueryError> {
+        let colours = Palette::Inferred.colours(games.iter().map(|game| &game.cubes));
+        let mut matching: Vec<Scored> = Vec::new();
+        for game in games {
+            let game = Scored {
+                game,
+                colours: &colours,
+            };
+            if self.filter.as_ref().map_or(Ok(true), |c| c.holds(&game))? {
+                matching.push(game);
+            }
+        }
+        let values = |field| {
+            matching
+                .iter()
+                .map(|game| game.value(field))
+                .collect::<Result<Vec<u64>, _>>()
+        };
+        let sum = |field| {
+            values(field)?
+                .into_iter()
+                .try_fold(0u64, u64::checked_add)
+                .ok_or(QueryError::Sum)
+        };
+        Ok(match &self.aggregate {
+            None => Answer::Games(matching.iter().map(|game| game.game.id).collect()),
+            Some(Aggregate::Count) => Answer::Number(matching.len() as u64),
+            Some(Aggregate::Sum(field)) => Answer::Number(sum(field)?),
+            Some(Aggregate::Max(field)) => values(field)?
+                .into_iter()
+                .max()
+                .map_or(Answer::Nothing, Answer::Number),
+            Some(Aggregate::Min(field)) => values(field)?
+                .into_iter()
+                .min()
+                .map_or(Answer::Nothing, Answer::Number),
+            Some(Aggregate::Average(_)) if matching.is_empty() => Answer::Nothing,
+            Some(Aggregate::Average(field)) => {
+                Answer::Average(sum(field)? as f64 / matching.len() as f64)
+            }
+        })
+    }
+}
+
+/// Answer `query` about the games logged in `it`, in any colours.
+pub fn query(it: impl Iterator<Item = String>, query: &str) -> Result<Answer, QueryError> {
+    let query: Query = query.parse()?;
+    query.eval(&read_games(it, &Palette::Inferred)?)
+}
+
+/// The fields and extra conditions of each level a condition can be about.
+trait Operand: Sized {
+    fn operand(input: &str) -> IResult<&str, Self>;
+
+    /// Conditions only this level has.
+    fn special(input: &str) -> IResult<&str, Condition<Self>> {
+        nom::combinator::fail(input)
+    }
+}
+
+impl Operand for GameField {
+    fn operand(input: &str) -> IResult<&str, Self> {
+        map(name, |name| match name {
+            "id" => Self::Id,
+            "power" => Self::Power,
+            "rounds" => Self::Rounds,
+            colour => Self::Colour(colour.to_string()),
+        })(input)
+    }
+
+    fn special(input: &str) -> IResult<&str, Condition<Self>> {
+        let rounds = || parenthesised(or::<RoundField>);
+        alt((
+            map(preceded(keyword("any"), rounds()), |c| {
+                Condition::Any(Box::new(c))
+            }),
+            map(preceded(keyword("all"), rounds()), |c| {
+                Condition::All(Box::new(c))
+            }),
+        ))(input)
+    }
+}
+
+impl Operand for RoundField {
+    fn operand(input: &str) -> IResult<&str, Self> {
+        map(name, |name| match name {
+            "total" => Self::Total,
+            colour => Self::Colour(colour.to_string()),
+        })(input)
+    }
+}
+
+fn name(input: &str) -> IResult<&str, &str> {
+    ws(take_while1(|c: char| c.is_alphanumeric() || c == '_'))(input)
+}
+
+fn ws<'a, O>(
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    preceded(multispace0, parser)
+}
+
+/// `(parser)`, with anything but that being an error once the `(` is seen.
+fn parenthesised<'a, O>(
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    preceded(ws(char('(')), cut(terminated(parser, ws(char(')')))))
+}
+
+/// `word`, but not as the start of a longer name.
+fn keyword<'a>(word: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+    ws(terminated(
+        tag(word),
+        not(satisfy(|c| c.is_alphanumeric() || c == '_')),
+    ))
+}
+
+fn or<F: Operand>(input: &str) -> IResult<&str, Condition<F>> {
+    let (input, first) = and(input)?;
+    let (input, rest) = many0(preceded(keyword("or"), and))(input)?;
+    let condition = rest
+        .into_iter()
+        .fold(first, |a, b| Condition::Or(Box::new(a), Box::new(b)));
+    Ok((input, condition))
+}
+
+fn and<F: Operand>(input: &str) -> IResult<&str, Condition<F>> {
+    let (input, first) = negated(input)?;
+    let (input, rest) = many0(preceded(keyword("and"), negated))(input)?;
+    let condition = rest
+        .into_iter()
+        .fold(first, |a, b| Condition::And(Box::new(a), Box::new(b)));
+    Ok((input, condition))
+}
+
+fn negated<F: Operand>(input: &str) -> IResult<&str, Condition<F>> {
+    alt((
+        map(preceded(keyword("not"), cut(negated)), |c| {
+            Condition::Not(Box::new(c))
+        }),
+        F::special,
+        parenthesised(or),
+        compare,
+    ))(input)
+}
+
+fn compare<F: Operand>(input: &str) -> IResult<&str, Condition<F>> {
+    let cmp = alt((
+        value(Cmp::Le, tag("<=")),
+        value(Cmp::Ge, tag(">=")),
+        value(Cmp::Ne, tag("!=")),
+        value(Cmp::Lt, tag("<")),
+        value(Cmp::Gt, tag(">")),
+        value(Cmp::Eq, tag("=")),
+    ));
+    map(
+        tuple((F::operand, ws(cmp), cut(ws(u64)))),
+        |(field, cmp, n)| Condition::Compare(field, cmp, n),
+    )(input)
+}
+
+fn aggregate(input: &str) -> IResult<&str, Aggregate> {
+    let field = || parenthesised(GameField::operand);
+    alt((
+        value(Aggregate::Count, keyword("count")),
+        map(preceded(keyword("sum"), field()), Aggregate::Sum),
+        map(preceded(keyword("max"), field()), Aggregate::Max),
+        map(preceded(keyword("min"), field()), Aggregate::Min),
+        map(preceded(keyword("avg"), field()), Aggregate::Average),
+    ))(input)
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let query = alt((
+            map(
+                pair(aggregate, opt(preceded(keyword("where"), cut(or)))),
+                |(aggregate, filter)| Query {
+                    aggregate: Some(aggregate),
+                    filter,
+                },
+            ),
+            map(or, |filter| Query {
+                aggregate: None,
+                filter: Some(filter),
+            }),
+        ))(s);
+        finish(s, query)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+
+    const EXAMPLE: &str = indoc! {"
+        Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+    "};
+
+    fn ask(q: &str) -> Answer {
+        query(EXAMPLE.lines().map(String::from), q).unwrap()
+    }
+
+    #[test]
+    fn puzzle() {
+        assert_eq!(
+            ask("sum(id) where red <= 12 and green <= 13 and blue <= 14"),
+            Answer::Number(8)
+        );
+        assert_eq!(ask("sum(power)"), Answer::Number(2286));
+    }
+
+    #[test]
+    fn conditions() {
+        assert_eq!(ask("any(blue > 10)"), Answer::Games(vec![4]));
+        assert_eq!(ask("all(total <= 9)"), Answer::Games(vec![1, 2]));
+        assert_eq!(ask("not rounds = 3"), Answer::Games(vec![5]));
+        // `and` first, so this is game 1 or games 4 and 5 with 3 green
+        assert_eq!(
+            ask("id = 1 or id >= 4 and green = 3"),
+            Answer::Games(vec![1, 4, 5])
+        );
+        assert_eq!(
+            ask("(id = 1 or id >= 4) and green = 3"),
+            Answer::Games(vec![4, 5])
+        );
+        assert_eq!(ask("teal > 0"), Answer::Games(vec![]));
+    }
+
+    #[test]
+    fn aggregates() {
+        assert_eq!(ask("count where any(red > 5)"), Answer::Number(3));
+        assert_eq!(ask("max(blue)"), Answer::Number(15));
+        assert_eq!(ask("min(power) where id != 1"), Answer::Number(12));
+        assert_eq!(ask("avg(power) where id > 3"), Answer::Average(333.0));
+        assert_eq!(ask("max(id) where id > 5"), Answer::Nothing);
+        assert_eq!(ask("avg(id) where id > 5").to_string(), "-");
+    }
+
+    #[test]
+    fn errors() {
+        let err = "count where any(red >)".parse::<Query>().unwrap_err();
+        assert_eq!((err.column(), err.expected()), (22, "a number"));
+        let err = "sum(id".parse::<Query>().unwrap_err();
+        assert_eq!(err.column(), 7);
+        assert!("sum(id) where".parse::<Query>().is_err());
+        assert!("notable > 3".parse::<Query>().is_ok());
+    }
+
+    #[test]
+    fn overflow() {
+        let big = indoc! {"
+            Game 1: 4294967295 red, 4294967295 green, 2 blue
+            Game 2: 4294967295 red, 4294967295 green, 1 blue
+            Game 3: 4294967295 red, 3 green, 1 blue
+        "};
+        let ask = |q| query(big.lines().map(String::from), q);
+        assert_eq!(ask("sum(power)"), Err(QueryError::Power(1)));
+        assert_eq!(ask("count where power > 1"), Err(QueryError::Power(1)));
+        assert_eq!(
+            ask("min(power) where id > 1"),
+            Ok(Answer::Number(12884901885))
+        );
+        assert_eq!(ask("sum(power) where id > 1"), Err(QueryError::Sum));
+        assert_eq!(ask("avg(power) where id > 1"), Err(QueryError::Sum));
+        assert_eq!(ask("sum(red)"), Ok(Answer::Number(12884901885)));
+        let big = "Game 1: 4294967295 red, 1 blue";
+        assert_eq!(
+            query(big.lines().map(String::from), "any(total > 4294967295)"),
+            Ok(Answer::Games(vec![1]))
+        );
+    }
+
+    #[test]
+    fn unparsable_game() {
+        let log = indoc! {"
+            Game 1: 3 blue, 4 red
+            Gmae 2: 1 blue
+        "};
+        let err = query(log.lines().map(String::from), "count").unwrap_err();
+        assert!(matches!(
+            err,
+            QueryError::Games(GameLogError { line: 2, .. })
+        ));
+    }
+}
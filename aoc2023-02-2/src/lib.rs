@@ -1,209 +1,132 @@
-use std::{cmp, num::ParseIntError, ops::BitOr, sync::OnceLock};
-
-use regex::Regex;
+use aoc2023_02_1::{read_games, Game, GameLogError, Palette};
+use aoc2023_solution::{Result, Solution};
 use thiserror::Error;
 
-#[derive(Debug, Default, Eq, PartialEq)]
-pub struct GameRound {
-    pub red: u32,
-    pub green: u32,
-    pub blue: u32,
-}
-
-impl GameRound {
-    pub fn red(mut self, red: u32) -> Self {
-        self.red = red;
-        self
-    }
-    pub fn green(mut self, green: u32) -> Self {
-        self.green = green;
-        self
-    }
-    pub fn blue(mut self, blue: u32) -> Self {
-        self.blue = blue;
-        self
-    }
-}
-
-impl BitOr for GameRound {
-    type Output = Self;
-
-    fn bitor(self, rhs: Self) -> Self::Output {
-        Self::Output {
-            red: cmp::max(self.red, rhs.red),
-            green: cmp::max(self.green, rhs.green),
-            blue: cmp::max(self.blue, rhs.blue),
-        }
-    }
-}
-
 #[derive(Error, Debug)]
-pub enum GameRoundSpecError {
-    #[error("Unreachable colour 🤷")]
-    UnreachableColour(String),
-    #[error("Unable to parse number of cubes")]
-    CubeCount(#[from] ParseIntError),
+pub enum PowerError {
+    #[error(transparent)]
+    Game(#[from] GameLogError),
+    #[error("The powers add up to more than {}", u64::MAX)]
+    Overflow,
 }
 
-impl TryFrom<&str> for GameRound {
-    type Error = GameRoundSpecError;
-
-    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
-        let mut red = 0;
-        let mut green = 0;
-        let mut blue = 0;
-
-        static CUBE_COUNT: OnceLock<Regex> = OnceLock::new();
-        let matches = CUBE_COUNT
-            .get_or_init(|| Regex::new(r"\b(\d+)\s+(red|green|blue)\b").unwrap())
-            .captures_iter(value)
-            .map(|c| c.extract().1);
-
-        for [num, colour] in matches {
-            match colour {
-                "red" => red = num.parse()?,
-                "green" => green = num.parse()?,
-                "blue" => blue = num.parse()?,
-                unk => return Err(GameRoundSpecError::UnreachableColour(unk.to_string())),
-            }
-        }
-
-        Ok(Self { red, green, blue })
-    }
-}
-
-#[derive(Debug, Eq, PartialEq)]
-struct Game {
-    id: u32,
-    red: u32,
-    green: u32,
-    blue: u32,
+/// Sum the powers of the smallest bag for each game, over the colours of
+/// `palette`.
+pub fn sum_powers(
+    it: impl Iterator<Item = String>,
+    palette: &Palette,
+) -> std::result::Result<u64, PowerError> {
+    total_power(&read_games(it, &Palette::Inferred)?, palette)
 }
 
-impl Game {
-    fn power(&self) -> u32 {
-        self.red * self.green * self.blue
+/// Sum the powers of games read with an inferred palette, any game showing a
+/// colour outside `palette` being an error.
+pub fn total_power(games: &[Game], palette: &Palette) -> std::result::Result<u64, PowerError> {
+    for (i, game) in games.iter().enumerate() {
+        game.check(palette).map_err(|source| GameLogError {
+            line: i + 1,
+            source,
+        })?;
     }
+    let colours = palette.colours(games.iter().map(|game| &game.cubes));
+    games
+        .iter()
+        .try_fold(0u64, |sum, game| {
+            sum.checked_add(game.cubes.power(colours.iter().copied())?)
+        })
+        .ok_or(PowerError::Overflow)
 }
 
-#[derive(Error, Debug)]
-pub enum GameSpecError {
-    #[error("Unable to parse game ID")]
-    GameIdParse,
-    #[error("Unable to parse game ID")]
-    GameIdFormat(#[from] ParseIntError),
-    #[error("Unable to parse rounds")]
-    RoundsParse,
-}
+pub struct Solver;
 
-impl TryFrom<&str> for Game {
-    type Error = GameSpecError;
+impl Solution for Solver {
+    const DAY: u8 = 2;
+    const PART: u8 = 2;
 
-    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
-        static GAME_ID: OnceLock<Regex> = OnceLock::new();
-        let id = GAME_ID
-            .get_or_init(|| Regex::new(r"^Game (?<id>\d+):").unwrap())
-            .captures(value)
-            .ok_or(GameSpecError::GameIdParse)?
-            .name("id")
-            .ok_or(GameSpecError::GameIdParse)?
-            .as_str()
-            .parse::<u32>()?;
-        static GAME_ROUND: OnceLock<Regex> = OnceLock::new();
-        let GameRound { red, green, blue } = GAME_ROUND
-            // .get_or_init(|| Regex::new(r"[;:]([^;]+)[;$]").unwrap()) // this doesn't work
-            // because `captures_iter` finds strictly non-overlapping matches
-            .get_or_init(|| Regex::new(r"[;:]([^;]+)").unwrap())
-            .captures_iter(value)
-            .map(|c| (c.extract::<1>().1)[0])
-            .map(GameRound::try_from)
-            .filter_map(|r| r.ok())
-            .reduce(|acc, v| acc | v)
-            .ok_or(GameSpecError::RoundsParse)?;
+    /// The games, in whatever colours they show.
+    type Input = Vec<Game>;
+    /// The colours to multiply together, red, green and blue by default.
+    type Params = Palette;
+    type Answer = u64;
 
-        Ok(Self {
-            id,
-            red,
-            green,
-            blue,
-        })
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(read_games(
+            input.lines().map(String::from),
+            &Palette::Inferred,
+        )?)
     }
-}
 
-pub fn sum_powers(it: impl Iterator<Item = String>) -> u32 {
-    it.filter_map(|line| Game::try_from(line.as_str()).ok())
-        .map(|game| game.power())
-        .reduce(|acc, v| v + acc)
-        .unwrap()
+    fn solve(input: Self::Input, palette: &Self::Params) -> Result<Self::Answer> {
+        Ok(total_power(&input, palette)?)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use color_eyre::Result;
     use indoc::indoc;
 
     #[test]
-    fn round_deserialize() -> Result<()> {
-        let round = GameRound::try_from(" 3 blue, 4 red")?;
-        assert_eq!(
-            round,
-            GameRound {
-                red: 4,
-                green: 0,
-                blue: 3
-            }
-        );
-        Ok(())
+    fn full_calculation() {
+        let example = indoc! {"
+            Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+            Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+            Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+            Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+            Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+        "};
+        let lines = || example.lines().map(String::from);
+        assert_eq!(sum_powers(lines(), &Palette::default()).unwrap(), 2286);
+        assert_eq!(sum_powers(lines(), &Palette::Inferred).unwrap(), 2286);
     }
 
     #[test]
-    fn round_or() {
-        let round1 = GameRound {
-            red: 10,
-            green: 20,
-            blue: 30,
-        };
-        let round2 = GameRound {
-            red: 15,
-            green: 15,
-            blue: 20,
-        };
-        let round3 = round1 | round2;
-        assert_eq!(
-            round3,
-            GameRound {
-                red: 15,
-                green: 20,
-                blue: 30
-            }
-        );
+    fn more_colours() {
+        let example = indoc! {"
+            Game 1: 3 blue, 4 red, 2 teal; 1 green
+            Game 2: 2 blue, 2 green, 2 red; 1 teal, 3 blue
+        "};
+        let lines = || example.lines().map(String::from);
+        assert_eq!(sum_powers(lines(), &Palette::Inferred).unwrap(), 24 + 12);
+        let four = Palette::new(["red", "green", "blue", "teal"]);
+        assert_eq!(sum_powers(lines(), &four).unwrap(), 24 + 12);
+        // teal isn't declared, so game 1 is in error
+        let err = sum_powers(lines(), &Palette::default()).unwrap_err();
+        assert!(matches!(
+            err,
+            PowerError::Game(GameLogError { line: 1, .. })
+        ));
+        // and a colour no game shows makes every power zero
+        let pink = Palette::new(["red", "green", "blue", "teal", "pink"]);
+        assert_eq!(sum_powers(lines(), &pink).unwrap(), 0);
     }
 
     #[test]
-    fn game_deserialize() -> Result<()> {
-        let game = Game::try_from("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green")?;
+    fn overflow() {
+        let five = "Game 1: 100 red, 100 green, 100 blue, 100 teal, 100 pink";
+        let lines = || five.lines().map(String::from);
         assert_eq!(
-            game,
-            Game {
-                id: 1,
-                red: 4,
-                green: 2,
-                blue: 6
-            }
+            sum_powers(lines(), &Palette::Inferred).unwrap(),
+            10_000_000_000
         );
-        Ok(())
-    }
-
-    #[test]
-    fn full_calculation() {
-        let example = indoc! {"
-            Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-            Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-            Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-            Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-            Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+        let big = indoc! {"
+            Game 1: 4294967295 red, 4294967295 green, 2 blue
+            Game 2: 1 red, 1 green, 1 blue
+        "};
+        let lines = || big.lines().map(String::from);
+        assert!(matches!(
+            sum_powers(lines(), &Palette::default()),
+            Err(PowerError::Overflow)
+        ));
+        // each power fits, but not their sum
+        let big = indoc! {"
+            Game 1: 4294967295 red, 4294967295 green, 1 blue
+            Game 2: 4294967295 red, 3 green, 1 blue
         "};
-        assert_eq!(sum_powers(example.lines().map(String::from),), 2286);
+        let lines = || big.lines().map(String::from);
+        assert!(matches!(
+            sum_powers(lines(), &Palette::default()),
+            Err(PowerError::Overflow)
+        ));
     }
 }
@@ -1,11 +1,30 @@
 use color_eyre::Result;
 use std::io;
 
+use aoc2023_02_1::Palette;
 use aoc2023_02_2::sum_powers;
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(about = "Sum the powers of the smallest bag for each game")]
+struct Cli {
+    /// The colours cubes come in, instead of red, green and blue
+    #[arg(long, value_delimiter = ',', conflicts_with = "infer")]
+    colours: Vec<String>,
+    /// Take the colours from the input
+    #[arg(long)]
+    infer: bool,
+}
 
 fn main() -> Result<()> {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = sum_powers(lines);
+    let cli = Cli::parse();
+    let palette = match (cli.infer, cli.colours.is_empty()) {
+        (true, _) => Palette::Inferred,
+        (false, true) => Palette::default(),
+        (false, false) => Palette::new(cli.colours),
+    };
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = sum_powers(lines, &palette)?;
     println!("{answer}");
     Ok(())
 }
@@ -1,91 +1,262 @@
-use std::sync::OnceLock;
+use std::{collections::VecDeque, iter, sync::OnceLock};
 
+use aoc2023_solution::{lines, Result, Solution};
 use bitvec::vec::BitVec;
+use itertools::process_results;
 use regex::{Match, Matches, Regex};
+use serde::Serialize;
+use thiserror::Error;
 
-#[derive(Debug, Default)]
-struct HitMatrix {
-    matrix: [BitVec; 3],
-    current: usize,
-}
+pub mod rule;
 
-impl HitMatrix {
-    fn prev_mut(&mut self) -> &mut BitVec {
-        &mut self.matrix[(self.current + 2) % 3]
-    }
+pub use rule::Rule;
+
+/// Lines fed in one at a time and handed back `radius` lines later, so
+/// scanners can see that far ahead of the line they're on.
+#[derive(Debug)]
+pub struct Lookahead {
+    radius: usize,
+    pending: VecDeque<Option<String>>,
+}
 
-    fn size_up(&mut self, len: usize) {
-        for row in self.matrix.iter_mut() {
-            row.resize(len, false);
+impl Lookahead {
+    pub fn new(radius: usize) -> Self {
+        Self {
+            radius,
+            pending: VecDeque::with_capacity(radius + 1),
         }
     }
 
-    fn set_hit(&mut self, idx: usize) {
-        for row in self.matrix.iter_mut() {
-            if idx > 0 {
-                row.set(idx - 1, true);
-            }
-            row.set(idx, true);
-            if idx < row.len() - 1 {
-                row.set(idx + 1, true);
-            }
+    /// Take the next line, or `None` past the end, and give back the line
+    /// `radius` before it if there is one.
+    pub fn push(&mut self, next: Option<String>) -> Option<String> {
+        self.pending.push_back(next);
+        match self.pending.len() > self.radius {
+            true => self.pending.pop_front().flatten(),
+            false => None,
         }
     }
+}
 
-    pub fn read(&mut self, line: &str) {
-        // make sure we have capacity
-        self.size_up(line.len());
-        // find all occurrences of a symbol on the line
-        static SYMBOL: OnceLock<Regex> = OnceLock::new();
-        let symbol_indices = SYMBOL
-            .get_or_init(|| Regex::new(r"[^.0-9]").unwrap())
-            .find_iter(line)
-            .map(|m| m.start());
-        self.prev_mut().fill(false);
-        for i in symbol_indices {
-            self.set_hit(i);
+/// Feed `it` to `step`, followed by enough `None`s to flush a lookahead of
+/// `radius`, passing on anything it fails with.
+pub fn stream<'a, T: 'a, E: 'a>(
+    it: impl Iterator<Item = String> + 'a,
+    radius: usize,
+    mut step: impl FnMut(Option<String>) -> Result<Vec<T>, E> + 'a,
+) -> impl Iterator<Item = Result<T, E>> + 'a {
+    it.map(Some)
+        .chain(iter::repeat_n(None, radius))
+        .flat_map(move |next| match step(next) {
+            Ok(found) => found.into_iter().map(Ok).collect(),
+            Err(err) => vec![Err(err)],
+        })
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchematicError {
+    /// Where the number starts, counting from 1.
+    #[error("The number at line {line}, column {column} is more than {}", u64::MAX)]
+    TooBig { line: usize, column: usize },
+    #[error("The numbers combine to more than {}", u64::MAX)]
+    Overflow,
+}
+
+/// The value of a number found in `row`, unless it's too big for a `u64`.
+pub fn number_value(row: usize, mtch: &Match) -> Result<u64, SchematicError> {
+    mtch.as_str().parse().map_err(|_| SchematicError::TooBig {
+        line: row + 1,
+        column: mtch.start() + 1,
+    })
+}
+
+/// A symbol and where it is, rows and columns counting from 0.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Symbol {
+    pub row: usize,
+    pub column: usize,
+    pub symbol: char,
+}
+
+/// A number in the schematic, spanning columns `start..end`, with the
+/// symbols it neighbours.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Number {
+    pub row: usize,
+    pub start: usize,
+    pub end: usize,
+    pub value: u64,
+    pub symbols: Vec<Symbol>,
+}
+
+/// The symbols of the lines within a rule's radius of the one being
+/// scanned for numbers, in a ring.
+#[derive(Debug)]
+struct HitMatrix<'a> {
+    rule: &'a Rule,
+    matrix: Vec<BitVec>,
+    /// The row number and text of each line in the ring.
+    lines: Vec<(usize, String)>,
+    /// The oldest line, to be replaced by the next.
+    oldest: usize,
+    next_row: usize,
+}
+
+impl<'a> HitMatrix<'a> {
+    fn new(rule: &'a Rule) -> Self {
+        let size = 2 * rule.radius + 1;
+        Self {
+            rule,
+            matrix: vec![BitVec::new(); size],
+            lines: vec![(0, String::new()); size],
+            oldest: 0,
+            next_row: 0,
         }
     }
 
-    pub fn inc(&mut self) {
-        self.current = (self.current + 1) % 3;
+    /// Take in the symbols of the next line, or of a blank one past the end.
+    pub fn read(&mut self, line: Option<&str>) {
+        let row = &mut self.matrix[self.oldest];
+        row.clear();
+        self.lines[self.oldest].1.clear();
+        if let Some(line) = line {
+            row.resize(line.len(), false);
+            for i in self.rule.symbol_columns(line) {
+                row.set(i, true);
+            }
+            self.lines[self.oldest] = (self.next_row, line.to_string());
+            self.next_row += 1;
+        }
+        self.oldest = (self.oldest + 1) % self.matrix.len();
     }
 
-    pub fn hit_test(&self, mtch: &Match) -> Option<u32> {
-        match self.matrix[self.current][mtch.start()..mtch.end()].any() {
-            true => Some(mtch.as_str().parse::<u32>().unwrap()),
-            false => None,
-        }
+    /// The symbols neighbouring `mtch` in the middle line, in reading order.
+    pub fn hits(&self, mtch: &Match) -> Vec<Symbol> {
+        let start = mtch.start().saturating_sub(self.rule.radius);
+        let end = mtch.end() + self.rule.radius;
+        let mut hits: Vec<Symbol> = self
+            .matrix
+            .iter()
+            .zip(&self.lines)
+            .filter(|(bits, _)| start < bits.len())
+            .flat_map(|(bits, (row, line))| {
+                bits[start..end.min(bits.len())]
+                    .iter_ones()
+                    .map(move |i| Symbol {
+                        row: *row,
+                        column: start + i,
+                        symbol: line[start + i..].chars().next().unwrap(),
+                    })
+            })
+            .collect();
+        hits.sort_by_key(|symbol| (symbol.row, symbol.column));
+        hits
     }
 }
 
-fn number_captures(line: &str) -> Matches {
+fn number_captures(line: &str) -> Matches<'_, '_> {
     static NUMBER: OnceLock<Regex> = OnceLock::new();
     NUMBER
         .get_or_init(|| Regex::new(r"\d+").unwrap())
         .find_iter(line)
 }
 
-pub fn sum_part_numbers(it: impl Iterator<Item = String>) -> u32 {
-    let mut matrix = HitMatrix::default();
-    let mut total = 0;
-    let mut it = it.peekable();
-    matrix.inc();
-    if let Some(ref line) = it.peek() {
-        matrix.read(&line);
-    }
-    while let Some(line) = it.next() {
-        if let Some(line) = it.peek() {
-            matrix.read(&line);
+/// Finds every number and its neighbouring symbols, a line at a time.
+#[derive(Debug)]
+pub struct NumberScanner<'a> {
+    matrix: HitMatrix<'a>,
+    lookahead: Lookahead,
+    row: usize,
+}
+
+impl<'a> NumberScanner<'a> {
+    pub fn new(rule: &'a Rule) -> Self {
+        Self {
+            matrix: HitMatrix::new(rule),
+            lookahead: Lookahead::new(rule.radius),
+            row: 0,
         }
-        for cap in number_captures(&line) {
-            if let Some(num) = matrix.hit_test(&cap) {
-                total += num;
-            }
+    }
+
+    /// Read the next line, or `None` past the end, giving the numbers of
+    /// the line `radius` before it.
+    pub fn step(&mut self, next: Option<String>) -> Result<Vec<Number>, SchematicError> {
+        self.matrix.read(next.as_deref());
+        let Some(line) = self.lookahead.push(next) else {
+            return Ok(Vec::new());
+        };
+        let numbers = number_captures(&line)
+            .map(|cap| {
+                Ok(Number {
+                    row: self.row,
+                    start: cap.start(),
+                    end: cap.end(),
+                    value: number_value(self.row, &cap)?,
+                    symbols: self.matrix.hits(&cap),
+                })
+            })
+            .collect();
+        self.row += 1;
+        numbers
+    }
+}
+
+/// Every number in the schematic, whether or not it's a part number, without
+/// holding more than a few lines at once.
+pub fn numbers<'a>(
+    it: impl Iterator<Item = String> + 'a,
+    rule: &'a Rule,
+) -> impl Iterator<Item = Result<Number, SchematicError>> + 'a {
+    let mut scanner = NumberScanner::new(rule);
+    stream(it, rule.radius, move |next| scanner.step(next))
+}
+
+/// Combine the numbers with the neighbouring symbols `rule` asks for.
+pub fn part_numbers(it: impl Iterator<Item = String>, rule: &Rule) -> Result<u64, SchematicError> {
+    process_results(numbers(it, rule), |numbers| {
+        rule.combine(
+            numbers
+                .filter(|number| rule.accepts(number.symbols.len()))
+                .map(|number| number.value),
+        )
+    })?
+    .ok_or(SchematicError::Overflow)
+}
+
+pub fn sum_part_numbers(it: impl Iterator<Item = String>) -> Result<u64, SchematicError> {
+    part_numbers(it, &Rule::part_numbers())
+}
+
+/// The rule to find part numbers by.
+pub struct Params {
+    pub rule: Rule,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            rule: Rule::part_numbers(),
         }
-        matrix.inc()
     }
-    total
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 3;
+    const PART: u8 = 1;
+
+    type Input = Vec<String>;
+    type Params = Params;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(lines(input))
+    }
+
+    fn solve(input: Self::Input, params: &Self::Params) -> Result<Self::Answer> {
+        Ok(part_numbers(input.into_iter(), &params.rule)?)
+    }
 }
 
 #[cfg(test)]
@@ -93,45 +264,88 @@ mod test {
     use super::*;
     use bitvec::prelude::*;
     use indoc::indoc;
+    use rule::{Aggregate, Neighbours, Symbols};
 
     #[test]
     fn hit_matrix_basic_read() {
-        let mut matrix = HitMatrix::default();
-        let line = "617*......";
-        matrix.read(line);
+        let rule = Rule::part_numbers();
+        let mut matrix = HitMatrix::new(&rule);
+        matrix.read(Some("617*......"));
 
-        assert_eq!(matrix.matrix[0], bitvec![0, 0, 1, 1, 1, 0, 0, 0, 0, 0]);
-        assert_eq!(matrix.matrix[1], bitvec![0, 0, 1, 1, 1, 0, 0, 0, 0, 0]);
-        assert_eq!(matrix.matrix[2], bitvec![0, 0, 1, 1, 1, 0, 0, 0, 0, 0]);
+        assert_eq!(matrix.matrix[0], bitvec![0, 0, 0, 1, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(matrix.matrix[1], bitvec![]);
+        assert_eq!(matrix.matrix[2], bitvec![]);
+        assert_eq!(matrix.oldest, 1);
     }
 
     #[test]
     fn hit_matrix_cycle() {
-        let mut matrix = HitMatrix::default();
-        matrix.inc();
-        matrix.read("467..114..");
-        assert_eq!(matrix.current, 1);
-        assert_eq!(matrix.matrix[0], bitvec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
-        assert_eq!(matrix.matrix[1], bitvec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0]); // <-- current
-        assert_eq!(matrix.matrix[2], bitvec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
-        matrix.inc();
-        matrix.read("...*......");
-        assert_eq!(matrix.current, 2);
-        assert_eq!(matrix.matrix[0], bitvec![0, 0, 1, 1, 1, 0, 0, 0, 0, 0]);
-        assert_eq!(matrix.matrix[1], bitvec![0, 0, 1, 1, 1, 0, 0, 0, 0, 0]);
-        assert_eq!(matrix.matrix[2], bitvec![0, 0, 1, 1, 1, 0, 0, 0, 0, 0]); // <-- current
-        matrix.inc();
-        matrix.read("..35..633.");
-        assert_eq!(matrix.current, 0);
-        assert_eq!(matrix.matrix[0], bitvec![0, 0, 1, 1, 1, 0, 0, 0, 0, 0]); // <-- current
-        assert_eq!(matrix.matrix[1], bitvec![0, 0, 1, 1, 1, 0, 0, 0, 0, 0]);
-        assert_eq!(matrix.matrix[2], bitvec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
-        matrix.inc();
-        matrix.read("......#...");
-        assert_eq!(matrix.current, 1);
-        assert_eq!(matrix.matrix[0], bitvec![0, 0, 0, 0, 0, 1, 1, 1, 0, 0]);
-        assert_eq!(matrix.matrix[1], bitvec![0, 0, 1, 1, 1, 1, 1, 1, 0, 0]); // <-- current
-        assert_eq!(matrix.matrix[2], bitvec![0, 0, 0, 0, 0, 1, 1, 1, 0, 0]);
+        let rule = Rule::part_numbers();
+        let mut matrix = HitMatrix::new(&rule);
+        let number = |line| number_captures(line).next().unwrap();
+        let symbol = |row, column, symbol| Symbol {
+            row,
+            column,
+            symbol,
+        };
+        matrix.read(Some("467..114.."));
+        matrix.read(Some("...*......"));
+        matrix.read(Some("..35..633."));
+        assert_eq!(matrix.oldest, 0);
+        // the middle line's numbers
+        assert_eq!(matrix.hits(&number("..2.......")), [symbol(1, 3, '*')]);
+        assert_eq!(matrix.hits(&number("..35......")), [symbol(1, 3, '*')]);
+        assert_eq!(matrix.hits(&number("......633.")), []);
+        matrix.read(Some("......#..."));
+        assert_eq!(matrix.oldest, 1);
+        assert_eq!(matrix.matrix[0], bitvec![0, 0, 0, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(matrix.hits(&number("......633.")), [symbol(3, 6, '#')]);
+        assert_eq!(matrix.hits(&number("..3456....")).len(), 2);
+        matrix.read(None);
+        matrix.read(None);
+        assert_eq!(matrix.hits(&number("..35......")), []);
+    }
+
+    #[test]
+    fn lookahead() {
+        let mut lookahead = Lookahead::new(2);
+        let line = |s: &str| Some(s.to_string());
+        assert_eq!(lookahead.push(line("a")), None);
+        assert_eq!(lookahead.push(line("b")), None);
+        assert_eq!(lookahead.push(line("c")), line("a"));
+        assert_eq!(lookahead.push(None), line("b"));
+        assert_eq!(lookahead.push(None), line("c"));
+        assert_eq!(lookahead.push(None), None);
+        assert_eq!(Lookahead::new(0).push(line("a")), line("a"));
+    }
+
+    #[test]
+    fn every_number() {
+        let example = indoc! {"
+            467..114..
+            ...*......
+            ..35..633.
+        "};
+        let rule = Rule::part_numbers();
+        let numbers: Vec<Number> = numbers(example.lines().map(String::from), &rule)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(numbers.len(), 4);
+        assert_eq!(
+            numbers[2],
+            Number {
+                row: 2,
+                start: 2,
+                end: 4,
+                value: 35,
+                symbols: vec![Symbol {
+                    row: 1,
+                    column: 3,
+                    symbol: '*'
+                }],
+            }
+        );
+        assert!(numbers[1].symbols.is_empty());
     }
 
     #[test]
@@ -148,6 +362,62 @@ mod test {
             ...$.*....
             .664.598..
         "};
-        assert_eq!(sum_part_numbers(example.lines().map(String::from)), 4361);
+        assert_eq!(
+            sum_part_numbers(example.lines().map(String::from)),
+            Ok(4361)
+        );
+    }
+
+    #[test]
+    fn other_rules() {
+        let example = indoc! {"
+            467..114..
+            ...*......
+            ..35..633.
+            ......#...
+            617*......
+            .....+.58.
+            ..592.....
+            ......755.
+            ...$.*....
+            .664.598..
+        "};
+        let lines = || example.lines().map(String::from);
+        let rule = Rule::part_numbers();
+        let hashes = rule.clone().symbols(Symbols::Only(vec!['#']));
+        assert_eq!(part_numbers(lines(), &hashes), Ok(633));
+        let largest = rule.clone().aggregate(Aggregate::Max);
+        assert_eq!(part_numbers(lines(), &largest), Ok(755));
+        // 114 and 58 reach symbols two cells away
+        let far = rule.clone().radius(2);
+        assert_eq!(part_numbers(lines(), &far), Ok(4361 + 114 + 58));
+        // only 467 has a single symbol within 3
+        let lonely = rule.radius(3).neighbours(Neighbours::Exactly(1));
+        assert_eq!(part_numbers(lines(), &lonely), Ok(467));
+    }
+
+    #[test]
+    fn too_big() {
+        let example = indoc! {"
+            ..1*..
+            .*99999999999999999999.
+        "};
+        let lines = || example.lines().map(String::from);
+        assert_eq!(
+            sum_part_numbers(lines()),
+            Err(SchematicError::TooBig { line: 2, column: 3 })
+        );
+        let example = indoc! {"
+            9999999999*9999999999
+        "};
+        let lines = || example.lines().map(String::from);
+        let product = Rule::part_numbers().aggregate(Aggregate::Product);
+        assert_eq!(
+            part_numbers(lines(), &product),
+            Err(SchematicError::Overflow)
+        );
+        let example = "18446744073709551615*1\n";
+        let lines = || example.lines().map(String::from);
+        assert_eq!(sum_part_numbers(lines()), Err(SchematicError::Overflow));
     }
 }
@@ -1,9 +1,48 @@
 use std::io;
 
-use aoc2023_03_1::sum_part_numbers;
+use aoc2023_03_1::{
+    part_numbers,
+    rule::{Aggregate, Neighbours, Symbols},
+    Rule,
+};
+use clap::Parser;
+use color_eyre::Result;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = sum_part_numbers(lines);
+#[derive(Parser)]
+#[command(about = "Combine the part numbers of an engine schematic")]
+struct Cli {
+    /// The symbols to look for, or `any` for anything but digits and `.`
+    #[arg(long)]
+    symbols: Option<Symbols>,
+    /// Neighbours needed, `N` for exactly N or `N+` for at least N
+    #[arg(long)]
+    neighbours: Option<Neighbours>,
+    /// How to combine numbers: `sum`, `product` or `max`
+    #[arg(long)]
+    aggregate: Option<Aggregate>,
+    /// How many rows and columns away neighbours can be
+    #[arg(long)]
+    radius: Option<usize>,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let cli = Cli::parse();
+    let mut rule = Rule::part_numbers();
+    if let Some(symbols) = cli.symbols {
+        rule = rule.symbols(symbols);
+    }
+    if let Some(neighbours) = cli.neighbours {
+        rule = rule.neighbours(neighbours);
+    }
+    if let Some(aggregate) = cli.aggregate {
+        rule = rule.aggregate(aggregate);
+    }
+    if let Some(radius) = cli.radius {
+        rule = rule.radius(radius);
+    }
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = part_numbers(lines, &rule)?;
     println!("{answer}");
+    Ok(())
 }
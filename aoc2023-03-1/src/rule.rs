@@ -0,0 +1,192 @@
+//! What counts as a symbol, a neighbour and an answer, for schematics other
+//! than the puzzle's.
+
+use std::str::FromStr;
+
+/// Which characters are symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Symbols {
+    /// Anything but digits and `.`.
+    Any,
+    Only(Vec<char>),
+}
+
+/// How many neighbours something needs to count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbours {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+/// How numbers are combined into an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Sum,
+    /// Saturating at `u64::MAX` rather than overflowing.
+    Product,
+    Max,
+}
+
+/// A number and a symbol are neighbours when the symbol is within `radius`
+/// rows and columns of any of the number's digits.
+///
+/// For part numbers, a number counts if it has the right number of symbols
+/// around it, and the numbers that count are aggregated. For gears, a
+/// symbol counts if it has the right number of numbers around it, and those
+/// numbers are aggregated before adding up over every gear.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub symbols: Symbols,
+    pub neighbours: Neighbours,
+    pub aggregate: Aggregate,
+    pub radius: usize,
+}
+
+impl Rule {
+    /// Part 1: numbers next to any symbol, summed.
+    pub fn part_numbers() -> Self {
+        Self {
+            symbols: Symbols::Any,
+            neighbours: Neighbours::AtLeast(1),
+            aggregate: Aggregate::Sum,
+            radius: 1,
+        }
+    }
+
+    /// Part 2: `*`s next to exactly two numbers, multiplied.
+    pub fn gears() -> Self {
+        Self {
+            symbols: Symbols::Only(vec!['*']),
+            neighbours: Neighbours::Exactly(2),
+            aggregate: Aggregate::Product,
+            radius: 1,
+        }
+    }
+
+    pub fn symbols(mut self, symbols: Symbols) -> Self {
+        self.symbols = symbols;
+        self
+    }
+    pub fn neighbours(mut self, neighbours: Neighbours) -> Self {
+        self.neighbours = neighbours;
+        self
+    }
+    pub fn aggregate(mut self, aggregate: Aggregate) -> Self {
+        self.aggregate = aggregate;
+        self
+    }
+    pub fn radius(mut self, radius: usize) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn is_symbol(&self, c: char) -> bool {
+        match &self.symbols {
+            Symbols::Any => c != '.' && !c.is_ascii_digit(),
+            Symbols::Only(symbols) => symbols.contains(&c),
+        }
+    }
+
+    /// The columns of the symbols in `line`.
+    pub fn symbol_columns<'a>(&'a self, line: &'a str) -> impl Iterator<Item = usize> + 'a {
+        line.char_indices()
+            .filter(|&(_, c)| self.is_symbol(c))
+            .map(|(i, _)| i)
+    }
+
+    pub fn accepts(&self, neighbours: usize) -> bool {
+        match self.neighbours {
+            Neighbours::Exactly(n) => neighbours == n,
+            Neighbours::AtLeast(n) => neighbours >= n,
+        }
+    }
+
+    /// Whether a symbol in `column` neighbours the number in `span`, given
+    /// they're close enough in rows.
+    pub fn reaches(&self, column: usize, span: (usize, usize)) -> bool {
+        let (start, end) = span;
+        column + self.radius >= start && column < end + self.radius
+    }
+
+    /// The values combined, unless that overflows a `u64`.
+    pub fn combine(&self, values: impl IntoIterator<Item = u64>) -> Option<u64> {
+        let mut values = values.into_iter();
+        match self.aggregate {
+            Aggregate::Sum => values.try_fold(0, u64::checked_add),
+            Aggregate::Product => values.try_fold(1, u64::checked_mul),
+            Aggregate::Max => Some(values.max().unwrap_or(0)),
+        }
+    }
+}
+
+/// `any`, or the symbols themselves, like `*#`.
+impl FromStr for Symbols {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Err("expected some symbols".to_string()),
+            "any" => Ok(Self::Any),
+            symbols => Ok(Self::Only(symbols.chars().collect())),
+        }
+    }
+}
+
+/// `2` for exactly two, `2+` for at least two.
+impl FromStr for Neighbours {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let count = |n: &str| {
+            n.parse()
+                .map_err(|_| format!("expected N or N+, found {s:?}"))
+        };
+        match s.strip_suffix('+') {
+            Some(n) => Ok(Self::AtLeast(count(n)?)),
+            None => Ok(Self::Exactly(count(s)?)),
+        }
+    }
+}
+
+impl FromStr for Aggregate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sum" => Ok(Self::Sum),
+            "product" => Ok(Self::Product),
+            "max" => Ok(Self::Max),
+            _ => Err(format!("expected sum, product or max, found {s:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rules() {
+        let rule = Rule::part_numbers();
+        assert!(rule.is_symbol('#') && !rule.is_symbol('.') && !rule.is_symbol('7'));
+        assert!(!Rule::gears().is_symbol('#'));
+        assert!(rule.reaches(2, (3, 5)) && rule.reaches(5, (3, 5)));
+        assert!(!rule.reaches(1, (3, 5)) && !rule.reaches(6, (3, 5)));
+        assert!(rule.clone().radius(2).reaches(1, (3, 5)));
+        assert!(Rule::gears().accepts(2) && !Rule::gears().accepts(3));
+        assert_eq!(Rule::gears().combine([3, 4, 5]), Some(60));
+        assert_eq!(Rule::gears().combine([u64::MAX, 2]), None);
+        assert_eq!(rule.combine([u64::MAX, 1]), None);
+        assert_eq!(rule.aggregate(Aggregate::Max).combine([3, 5, 4]), Some(5));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("*#".parse(), Ok(Symbols::Only(vec!['*', '#'])));
+        assert_eq!("any".parse(), Ok(Symbols::Any));
+        assert_eq!("2".parse(), Ok(Neighbours::Exactly(2)));
+        assert_eq!("1+".parse(), Ok(Neighbours::AtLeast(1)));
+        assert!("+".parse::<Neighbours>().is_err());
+        assert_eq!("max".parse(), Ok(Aggregate::Max));
+    }
+}
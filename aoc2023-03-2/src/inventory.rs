@@ -0,0 +1,175 @@
+//! Every number and gear of a schematic, for looking at in something other
+//! than a terminal.
+//!
+//! Numbers and gears are found in one pass over the lines, holding no more
+//! of the schematic than the rules' radii need, and written out as they're
+//! found. Each comes out as soon as nothing further down can change it, so
+//! the two kinds are interleaved roughly in reading order.
+
+use std::io::{self, Write};
+
+use aoc2023_03_1::{stream, Number, NumberScanner, Rule, SchematicError};
+use serde::Serialize;
+
+use crate::{Gear, GearScanner};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Entry {
+    Number(Number),
+    Gear(Gear),
+}
+
+/// Every number, with the symbols `parts` counts as its neighbours, and
+/// every gear `gears` accepts.
+pub fn inventory<'a>(
+    it: impl Iterator<Item = String> + 'a,
+    parts: &'a Rule,
+    gears: &'a Rule,
+) -> impl Iterator<Item = Result<Entry, SchematicError>> + 'a {
+    let mut numbers = NumberScanner::new(parts);
+    let mut gear_scanner = GearScanner::new(gears);
+    // each scanner sees `None` once it's past the end of its own radius,
+    // which does it no harm
+    let flush = parts.radius.max(GearScanner::flush(gears));
+    stream(it, flush, move |next| {
+        let found = numbers.step(next.clone())?.into_iter().map(Entry::Number);
+        let gears = gear_scanner.step(next)?.into_iter().map(Entry::Gear);
+        Ok(found.chain(gears).collect())
+    })
+}
+
+/// A JSON array of `entries`, written one at a time.
+pub fn write_json(entries: impl Iterator<Item = Entry>, mut out: impl Write) -> io::Result<()> {
+    write!(out, "[")?;
+    for (i, entry) in entries.enumerate() {
+        if i > 0 {
+            write!(out, ",")?;
+        }
+        writeln!(out)?;
+        serde_json::to_writer(&mut out, &entry)?;
+    }
+    writeln!(out, "\n]")
+}
+
+/// `entries` as CSV, one row each.
+///
+/// A number's neighbours are its symbols, as `symbol@row:column`, and a
+/// gear's are its parts, as `value@row:start`, separated by spaces. Gears
+/// have a single column, given as both `start` and `end`.
+pub fn write_csv(entries: impl Iterator<Item = Entry>, mut out: impl Write) -> io::Result<()> {
+    writeln!(out, "kind,row,start,end,symbol,value,neighbours")?;
+    for entry in entries {
+        let (kind, row, start, end, symbol, value, neighbours) = match entry {
+            Entry::Number(number) => {
+                let symbols: Vec<String> = number
+                    .symbols
+                    .iter()
+                    .map(|s| format!("{}@{}:{}", s.symbol, s.row, s.column))
+                    .collect();
+                let (row, start, end) = (number.row, number.start, number.end);
+                (
+                    "number",
+                    row,
+                    start,
+                    end,
+                    String::new(),
+                    number.value,
+                    symbols,
+                )
+            }
+            Entry::Gear(gear) => {
+                let parts: Vec<String> = gear
+                    .parts
+                    .iter()
+                    .map(|p| format!("{}@{}:{}", p.value, p.row, p.start))
+                    .collect();
+                let (row, column) = (gear.row, gear.column);
+                let symbol = gear.symbol.to_string();
+                ("gear", row, column, column, symbol, gear.ratio, parts)
+            }
+        };
+        writeln!(
+            out,
+            "{kind},{row},{start},{end},{},{value},{}",
+            field(&symbol),
+            field(&neighbours.join(" "))
+        )?;
+    }
+    Ok(())
+}
+
+/// `s`, quoted if it would otherwise be read as more than one field.
+fn field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+
+    const EXAMPLE: &str = indoc! {"
+        467..114..
+        ...*......
+        ..35..633.
+        ......#...
+        617*......
+    "};
+
+    fn entries() -> Vec<Entry> {
+        let (parts, gears) = (Rule::part_numbers(), Rule::gears());
+        inventory(EXAMPLE.lines().map(String::from), &parts, &gears)
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn entries_in_order() {
+        let kinds: Vec<String> = entries()
+            .iter()
+            .map(|entry| match entry {
+                Entry::Number(n) => n.value.to_string(),
+                Entry::Gear(g) => format!("{}{}", g.symbol, g.ratio),
+            })
+            .collect();
+        assert_eq!(kinds, ["467", "114", "35", "633", "*16345", "617"]);
+    }
+
+    #[test]
+    fn csv() -> io::Result<()> {
+        let mut out = Vec::new();
+        write_csv(entries().into_iter(), &mut out)?;
+        let csv = String::from_utf8(out).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows[0], "kind,row,start,end,symbol,value,neighbours");
+        assert_eq!(rows[1], "number,0,0,3,,467,*@1:3");
+        assert_eq!(rows[2], "number,0,5,8,,114,");
+        assert_eq!(rows[5], "gear,1,3,3,*,16345,467@0:0 35@2:2");
+        assert_eq!(field("a,b"), "\"a,b\"");
+        assert_eq!(field("\""), "\"\"\"\"");
+        Ok(())
+    }
+
+    #[test]
+    fn json() -> io::Result<()> {
+        let mut out = Vec::new();
+        write_json(entries().into_iter(), &mut out)?;
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 6);
+        assert_eq!(json[0]["kind"], "number");
+        assert_eq!(json[0]["symbols"][0]["symbol"], "*");
+        assert_eq!(json[4]["kind"], "gear");
+        assert_eq!(json[4]["parts"][1]["value"], 35);
+        assert_eq!(json[4]["ratio"], 16345);
+
+        let mut empty = Vec::new();
+        write_json(std::iter::empty(), &mut empty)?;
+        assert_eq!(String::from_utf8(empty).unwrap(), "[\n]\n");
+        Ok(())
+    }
+}
@@ -1,137 +1,222 @@
-use array_macro::array;
-use bitvec::vec::BitVec;
+use aoc2023_03_1::{number_value, stream, Lookahead, Rule, SchematicError};
+use aoc2023_solution::{lines, Result, Solution};
 use regex::{Match, Matches, Regex};
+use serde::Serialize;
 use std::sync::OnceLock;
 
-#[derive(Debug)]
-struct Gear {
-    matrix: [BitVec; 3],
-    current: usize,
-    pub finished: bool,
-    hits: Vec<u32>,
+pub mod inventory;
+
+/// A number next to a gear, spanning columns `start..end`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Part {
+    pub row: usize,
+    pub start: usize,
+    pub end: usize,
+    pub value: u64,
 }
 
-fn make_row(size: usize, idx: usize) -> BitVec {
-    let mut v = BitVec::with_capacity(size);
-    v.resize(size, false);
-    if idx > 0 {
-        v.set(idx - 1, true);
-    }
-    v.set(idx, true);
-    if idx < size - 1 {
-        v.set(idx + 1, true);
-    }
-    v
+/// A symbol, collecting the numbers around it as the lines it can reach
+/// go by.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Gear {
+    pub row: usize,
+    pub column: usize,
+    pub symbol: char,
+    pub parts: Vec<Part>,
+    /// The parts combined, once they're all found.
+    pub ratio: u64,
+    /// Lines still to come within reach, this one included.
+    #[serde(skip)]
+    rows_left: usize,
+    #[serde(skip)]
+    pub finished: bool,
 }
 
 impl Gear {
-    fn prev_mut(&mut self) -> &mut BitVec {
-        &mut self.matrix[(self.current + 2) % 3]
-    }
-
-    fn new(size: usize, idx: usize) -> Self {
-        let matrix = array![_ => make_row(size, idx); 3];
+    fn new(rule: &Rule, row: usize, column: usize, symbol: char) -> Self {
         Self {
-            matrix,
-            current: 0,
-            hits: Vec::new(),
+            row,
+            column,
+            symbol,
+            parts: Vec::new(),
+            ratio: 0,
+            rows_left: 2 * rule.radius + 1,
             finished: false,
         }
     }
 
     pub fn inc(&mut self) {
-        if self.current == 2 {
-            self.finished = true
-        }
-        self.current = (self.current + 1) % 3;
-        self.prev_mut().fill(false);
+        self.rows_left -= 1;
+        self.finished = self.rows_left == 0;
     }
 
-    pub fn hit_test(&mut self, mtch: &Match) {
-        if self.matrix[self.current][mtch.start()..mtch.end()].any() {
-            if self.hits.len() == 2 {
-                self.finished = true;
-                self.hits.clear();
-            }
-            self.hits.push(mtch.as_str().parse::<u32>().unwrap());
+    pub fn hit_test(
+        &mut self,
+        rule: &Rule,
+        row: usize,
+        mtch: &Match,
+    ) -> Result<(), SchematicError> {
+        if rule.reaches(self.column, (mtch.start(), mtch.end())) {
+            self.parts.push(Part {
+                row,
+                start: mtch.start(),
+                end: mtch.end(),
+                value: number_value(row, mtch)?,
+            });
         }
+        Ok(())
     }
 
-    pub fn ratio(&self) -> Option<u32> {
-        if self.finished && self.hits.len() == 2 {
-            Some(self.hits[0] * self.hits[1])
-        } else {
-            None
+    /// The parts combined, if the gear is finished and `rule` accepts it.
+    pub fn ratio(&self, rule: &Rule) -> Result<Option<u64>, SchematicError> {
+        if !self.finished || !rule.accepts(self.parts.len()) {
+            return Ok(None);
         }
+        rule.combine(self.parts.iter().map(|part| part.value))
+            .map(Some)
+            .ok_or(SchematicError::Overflow)
     }
 }
 
-#[derive(Debug, Default)]
-struct GearScanner {
-    gear_ratio_sum: u32,
+/// Finds the gears `rule` asks for, a line at a time.
+#[derive(Debug)]
+pub struct GearScanner<'a> {
+    rule: &'a Rule,
     gears: Vec<Gear>,
+    lookahead: Lookahead,
+    /// The rows of the next line read, and the next whose numbers are.
+    rows: (usize, usize),
 }
 
-impl GearScanner {
-    pub fn read_gears(&mut self, line: &str) {
-        // find all occurrences of a symbol on the line
-        static SYMBOL: OnceLock<Regex> = OnceLock::new();
-        let gear_indices = SYMBOL
-            .get_or_init(|| Regex::new(r"\*").unwrap())
-            .find_iter(line)
-            .map(|m| m.start());
-        for idx in gear_indices {
-            self.gears.push(Gear::new(line.len(), idx));
+impl<'a> GearScanner<'a> {
+    pub fn new(rule: &'a Rule) -> Self {
+        Self {
+            rule,
+            gears: Vec::new(),
+            lookahead: Lookahead::new(rule.radius),
+            rows: (0, 0),
         }
     }
 
-    pub fn read_numbers(&mut self, line: &str) {
+    /// Start collecting numbers for the gears in `line`, which is `radius`
+    /// lines ahead of the one whose numbers are read next.
+    fn read_gears(&mut self, line: &str) {
+        for idx in self.rule.symbol_columns(line) {
+            let symbol = line[idx..].chars().next().unwrap();
+            self.gears
+                .push(Gear::new(self.rule, self.rows.0, idx, symbol));
+        }
+        self.rows.0 += 1;
+    }
+
+    fn read_numbers(&mut self, line: &str) -> Result<(), SchematicError> {
         for cap in number_captures(line) {
-            for matrix in self.gears.iter_mut() {
-                matrix.hit_test(&cap);
+            for gear in self.gears.iter_mut() {
+                gear.hit_test(self.rule, self.rows.1, &cap)?;
             }
         }
+        self.rows.1 += 1;
+        Ok(())
     }
 
-    pub fn inc(&mut self) {
+    /// The gears with nothing left in reach that `rule` accepts.
+    fn inc(&mut self) -> Result<Vec<Gear>, SchematicError> {
+        let mut found = Vec::new();
         for gear in self.gears.iter_mut() {
-            if let Some(ratio) = gear.ratio() {
-                self.gear_ratio_sum += ratio;
+            gear.inc();
+            if let Some(ratio) = gear.ratio(self.rule)? {
+                gear.ratio = ratio;
+                found.push(gear.clone());
             }
         }
         self.gears.retain(|g| !g.finished);
-        for gear in self.gears.iter_mut() {
-            gear.inc();
+        Ok(found)
+    }
+
+    /// How many `None`s to step past the end, so gears in the last lines
+    /// see the numbers `radius` lines below them.
+    pub fn flush(rule: &Rule) -> usize {
+        2 * rule.radius
+    }
+
+    /// Read the next line, or `None` past the end, giving any gears
+    /// completed by it.
+    pub fn step(&mut self, next: Option<String>) -> Result<Vec<Gear>, SchematicError> {
+        if let Some(next) = &next {
+            self.read_gears(next);
         }
+        if let Some(line) = self.lookahead.push(next) {
+            self.read_numbers(&line)?;
+        }
+        self.inc()
     }
 }
 
-fn number_captures(line: &str) -> Matches {
+fn number_captures(line: &str) -> Matches<'_, '_> {
     static NUMBER: OnceLock<Regex> = OnceLock::new();
     NUMBER
         .get_or_init(|| Regex::new(r"\d+").unwrap())
         .find_iter(line)
 }
 
-pub fn sum_gear_ratios(it: impl Iterator<Item = String>) -> u32 {
-    let mut scanner = GearScanner::default();
+/// Every gear `rule` accepts, in the order they're completed, without
+/// holding more than a few lines at once.
+pub fn gears<'a>(
+    it: impl Iterator<Item = String> + 'a,
+    rule: &'a Rule,
+) -> impl Iterator<Item = Result<Gear, SchematicError>> + 'a {
+    let mut scanner = GearScanner::new(rule);
+    stream(it, GearScanner::flush(rule), move |next| scanner.step(next))
+}
+
+/// Add up the combined numbers around each symbol with as many as `rule`
+/// asks for.
+pub fn gear_ratios(it: impl Iterator<Item = String>, rule: &Rule) -> Result<u64, SchematicError> {
+    gears(it, rule).try_fold(0u64, |sum, gear| {
+        sum.checked_add(gear?.ratio).ok_or(SchematicError::Overflow)
+    })
+}
+
+pub fn sum_gear_ratios(it: impl Iterator<Item = String>) -> Result<u64, SchematicError> {
+    gear_ratios(it, &Rule::gears())
+}
 
-    let mut it = it.peekable();
+/// The rule to find gears by.
+pub struct Params {
+    pub rule: Rule,
+}
 
-    while let Some(line) = it.next() {
-        if let Some(line) = it.peek() {
-            scanner.read_gears(&line);
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            rule: Rule::gears(),
         }
-        scanner.read_numbers(&line);
-        scanner.inc();
     }
-    scanner.inc();
-    scanner.gear_ratio_sum
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 3;
+    const PART: u8 = 2;
+
+    type Input = Vec<String>;
+    type Params = Params;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(lines(input))
+    }
+
+    fn solve(input: Self::Input, params: &Self::Params) -> Result<Self::Answer> {
+        Ok(gear_ratios(input.into_iter(), &params.rule)?)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use aoc2023_03_1::rule::{Aggregate, Neighbours};
     use indoc::indoc;
 
     #[test]
@@ -148,6 +233,107 @@ mod test {
             ...$.*....
             .664.598..
         "};
-        assert_eq!(sum_gear_ratios(example.lines().map(String::from)), 467835);
+        assert_eq!(
+            sum_gear_ratios(example.lines().map(String::from)),
+            Ok(467835)
+        );
+    }
+
+    #[test]
+    fn other_rules() {
+        let example = indoc! {"
+            467..114..
+            ...*......
+            ..35..633.
+            ......#...
+            617*......
+            .....+.58.
+            ..592.....
+            ......755.
+            ...$.*....
+            .664.598..
+        "};
+        let lines = || example.lines().map(String::from);
+        let gears = Rule::gears();
+        let sums = gears.clone().aggregate(Aggregate::Sum);
+        assert_eq!(gear_ratios(lines(), &sums), Ok(467 + 35 + 755 + 598));
+        // 617 is alone by its `*`, so counts with at least one
+        let lone = sums.neighbours(Neighbours::AtLeast(1));
+        assert_eq!(gear_ratios(lines(), &lone), Ok(467 + 35 + 617 + 755 + 598));
+        let any = Rule::part_numbers().aggregate(Aggregate::Max);
+        assert_eq!(
+            gear_ratios(lines(), &any),
+            Ok(467 + 633 + 617 + 592 + 664 + 755)
+        );
+        // from 2 away, the first two `*`s reach three numbers each
+        let far = gears.radius(2).neighbours(Neighbours::Exactly(3));
+        assert_eq!(
+            gear_ratios(lines(), &far),
+            Ok(467 * 114 * 35 + 35 * 617 * 592)
+        );
+    }
+
+    #[test]
+    fn gears_and_parts() {
+        let example = indoc! {"
+            467..114..
+            ...*......
+            ..35..633.
+            ......#...
+            617*......
+        "};
+        let rule = Rule::gears();
+        let found: Vec<Gear> = gears(example.lines().map(String::from), &rule)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        let gear = &found[0];
+        assert_eq!((gear.row, gear.column, gear.symbol), (1, 3, '*'));
+        assert_eq!(
+            gear.parts,
+            [
+                Part {
+                    row: 0,
+                    start: 0,
+                    end: 3,
+                    value: 467
+                },
+                Part {
+                    row: 2,
+                    start: 2,
+                    end: 4,
+                    value: 35
+                },
+            ]
+        );
+        assert_eq!(gear.ratio, 467 * 35);
+    }
+
+    #[test]
+    fn overflow() {
+        let example = indoc! {"
+            .123456789012345678901.
+            ..*....................
+            ..2....................
+        "};
+        let lines = || example.lines().map(String::from);
+        assert_eq!(
+            sum_gear_ratios(lines()),
+            Err(SchematicError::TooBig { line: 1, column: 2 })
+        );
+        let example = "4294967296*4294967296\n";
+        let lines = || example.lines().map(String::from);
+        assert_eq!(sum_gear_ratios(lines()), Err(SchematicError::Overflow));
+        let example = "4294967296*2\n";
+        let lines = || example.lines().map(String::from);
+        assert_eq!(sum_gear_ratios(lines()), Ok(1 << 33));
+        // each ratio fits, at 2^63, but not their sum
+        let example = indoc! {"
+            4294967296*2147483648
+            .....................
+            4294967296*2147483648
+        "};
+        let lines = || example.lines().map(String::from);
+        assert_eq!(sum_gear_ratios(lines()), Err(SchematicError::Overflow));
     }
 }
@@ -1,9 +1,72 @@
 use std::io;
 
-use aoc2023_03_2::sum_gear_ratios;
+use aoc2023_03_1::{
+    rule::{Aggregate, Neighbours, Symbols},
+    Rule,
+};
+use aoc2023_03_2::{
+    gear_ratios,
+    inventory::{inventory, write_csv, write_json},
+};
+use clap::{Parser, ValueEnum};
+use color_eyre::Result;
+use itertools::process_results;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = sum_gear_ratios(lines);
-    println!("{answer}");
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Csv,
+    Json,
+}
+
+#[derive(Parser)]
+#[command(about = "Add up the gear ratios of an engine schematic")]
+struct Cli {
+    /// The symbols to look for, or `any` for anything but digits and `.`
+    #[arg(long)]
+    symbols: Option<Symbols>,
+    /// Neighbours needed, `N` for exactly N or `N+` for at least N
+    #[arg(long)]
+    neighbours: Option<Neighbours>,
+    /// How to combine numbers: `sum`, `product` or `max`
+    #[arg(long)]
+    aggregate: Option<Aggregate>,
+    /// How many rows and columns away neighbours can be
+    #[arg(long)]
+    radius: Option<usize>,
+    /// List every number, with the symbols around it, and every gear, with
+    /// its parts, instead of adding up
+    #[arg(long)]
+    inventory: Option<Format>,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let cli = Cli::parse();
+    let mut rule = Rule::gears();
+    if let Some(symbols) = cli.symbols {
+        rule = rule.symbols(symbols);
+    }
+    if let Some(neighbours) = cli.neighbours {
+        rule = rule.neighbours(neighbours);
+    }
+    if let Some(aggregate) = cli.aggregate {
+        rule = rule.aggregate(aggregate);
+    }
+    if let Some(radius) = cli.radius {
+        rule = rule.radius(radius);
+    }
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let Some(format) = cli.inventory else {
+        println!("{}", gear_ratios(lines, &rule)?);
+        return Ok(());
+    };
+    // numbers are still part numbers by any symbol, as far as gears reach
+    let parts = Rule::part_numbers().radius(rule.radius);
+    let entries = inventory(lines, &parts, &rule);
+    let out = io::stdout().lock();
+    process_results(entries, |entries| match format {
+        Format::Csv => write_csv(entries, out),
+        Format::Json => write_json(entries, out),
+    })??;
+    Ok(())
 }
@@ -1,53 +1,85 @@
-use std::{collections::HashSet, sync::OnceLock};
+use std::{collections::HashSet, str::FromStr};
 
-use regex::Regex;
+use aoc2023_parse::{finish, parse_lines, ParseError};
+use aoc2023_solution::{Result, Solution};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, space1, u32},
+    multi::many1,
+    sequence::{delimited, pair, preceded, tuple},
+    IResult,
+};
 
 #[derive(Debug, PartialEq)]
-struct Card {
-    winning: Vec<u32>,
-    in_hand: Vec<u32>,
+pub struct Card {
+    pub id: u32,
+    pub winning: Vec<u32>,
+    pub in_hand: Vec<u32>,
 }
 
 impl Card {
-    pub fn score(&self) -> u32 {
-        static BASE: u32 = 2;
+    /// How many of the numbers in hand are winning numbers.
+    pub fn matches(&self) -> usize {
         let winning_set = HashSet::<&u32>::from_iter(self.winning.iter());
         let in_hand_set = HashSet::<&u32>::from_iter(self.in_hand.iter());
-        let num_winning_numbers = winning_set.intersection(&in_hand_set).count();
-        match num_winning_numbers {
+        winning_set.intersection(&in_hand_set).count()
+    }
+
+    pub fn score(&self) -> u32 {
+        static BASE: u32 = 2;
+        match self.matches() {
             0 => 0,
             n => BASE.pow((n - 1).try_into().unwrap()),
         }
     }
 }
 
-fn numbers(input: &str) -> Vec<u32> {
-    static NUMBER: OnceLock<Regex> = OnceLock::new();
-    NUMBER
-        .get_or_init(|| Regex::new(r"\d+").unwrap())
-        .find_iter(input)
-        .filter_map(|m| m.as_str().parse().ok())
-        .collect()
-}
+impl FromStr for Card {
+    type Err = ParseError;
 
-impl From<&str> for Card {
-    fn from(value: &str) -> Self {
-        static SPLIT: OnceLock<Regex> = OnceLock::new();
-        let captures = SPLIT
-            .get_or_init(|| Regex::new(r":(?<winning>.*)\|(?<hand>.*)$").unwrap())
-            .captures(value)
-            .unwrap();
-        let winning = captures.name("winning").unwrap().as_str();
-        let hand = captures.name("hand").unwrap().as_str();
-        Self {
-            winning: numbers(winning),
-            in_hand: numbers(hand),
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn numbers(input: &str) -> IResult<&str, Vec<u32>> {
+            many1(preceded(space1, u32))(input)
         }
+        let card = tuple((
+            delimited(pair(tag("Card"), space1), u32, char(':')),
+            numbers,
+            preceded(pair(space1, char('|')), numbers),
+        ))(s);
+        let (id, winning, in_hand) = finish(s, card)?;
+        Ok(Self {
+            id,
+            winning,
+            in_hand,
+        })
     }
 }
 
-pub fn sum_points(it: impl Iterator<Item = String>) -> u32 {
-    it.map(|line| Card::from(line.as_str()).score()).sum()
+pub fn total_points(cards: &[Card]) -> u32 {
+    cards.iter().map(Card::score).sum()
+}
+
+pub fn sum_points(it: impl Iterator<Item = String>) -> Result<u32, ParseError> {
+    Ok(total_points(&parse_lines(it)?))
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 4;
+    const PART: u8 = 1;
+
+    type Input = Vec<Card>;
+    type Params = ();
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input.lines())?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(total_points(&input))
+    }
 }
 
 #[cfg(test)]
@@ -56,19 +88,33 @@ mod test {
     use indoc::indoc;
 
     #[test]
-    fn game_deserialize() {
-        let card = Card::from("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53");
+    fn game_deserialize() -> Result<()> {
+        let card: Card = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53".parse()?;
         assert_eq!(
             card,
             Card {
+                id: 1,
                 winning: vec![41, 48, 83, 86, 17],
                 in_hand: vec![83, 86, 6, 31, 17, 9, 48, 53]
             }
         );
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_card() {
+        let err = parse_lines::<Card>(indoc! {"
+            Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+            Card 2: 13 32 20 16 61 / 61 30 68 82 17 32 24 19
+        "}
+        .lines())
+        .unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 24));
+        assert_eq!(err.expected(), "a fixed character");
     }
 
     #[test]
-    fn full_example() {
+    fn full_example() -> Result<()> {
         let example = indoc! {"
             Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
             Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
@@ -77,6 +123,7 @@ mod test {
             Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
             Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
         "};
-        assert_eq!(sum_points(example.lines().map(String::from)), 13);
+        assert_eq!(sum_points(example.lines().map(String::from))?, 13);
+        Ok(())
     }
 }
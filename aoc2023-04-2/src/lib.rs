@@ -1,77 +1,144 @@
-use std::{
-    cmp::min,
-    collections::{HashSet, VecDeque},
-    sync::OnceLock,
-};
-
-use regex::Regex;
-
-#[derive(Debug, PartialEq)]
-struct Card {
-    winning: Vec<u32>,
-    in_hand: Vec<u32>,
+use std::{collections::BTreeMap, num::NonZeroU64};
+
+use aoc2023_04_1::Card;
+use aoc2023_parse::{parse_lines, ParseError};
+use aoc2023_solution::{Result, Solution};
+use num_bigint::BigUint;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CascadeError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("Card {0} appears more than once")]
+    Duplicate(u32),
 }
 
-impl Card {
-    pub fn score(&self) -> usize {
-        let winning_set = HashSet::<&u32>::from_iter(self.winning.iter());
-        let in_hand_set = HashSet::<&u32>::from_iter(self.in_hand.iter());
-        winning_set.intersection(&in_hand_set).count()
-    }
+/// A card whose matches would win copies of cards that aren't in the pile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unwon {
+    pub card: u32,
+    pub missing: Vec<u32>,
 }
 
-fn numbers(input: &str) -> Vec<u32> {
-    static NUMBER: OnceLock<Regex> = OnceLock::new();
-    NUMBER
-        .get_or_init(|| Regex::new(r"\d+").unwrap())
-        .find_iter(input)
-        .filter_map(|m| m.as_str().parse().ok())
-        .collect()
+/// How many of each card end up in the pile, by card id.
+///
+/// Copies grow exponentially with the length of the pile, so they're
+/// counted exactly, or modulo some number if only a residue is wanted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cascade {
+    copies: BTreeMap<u32, BigUint>,
+    unwon: Vec<Unwon>,
+    modulus: Option<BigUint>,
 }
 
-impl From<&str> for Card {
-    fn from(value: &str) -> Self {
-        static SPLIT: OnceLock<Regex> = OnceLock::new();
-        let captures = SPLIT
-            .get_or_init(|| Regex::new(r":(?<winning>.*)\|(?<hand>.*)$").unwrap())
-            .captures(value)
-            .unwrap();
-        let winning = captures.name("winning").unwrap().as_str();
-        let hand = captures.name("hand").unwrap().as_str();
-        Self {
-            winning: numbers(winning),
-            in_hand: numbers(hand),
+impl Cascade {
+    /// Play out `cards`, which may come in any order and skip ids. A card
+    /// with `n` matches wins a copy of each of the `n` cards numbered after
+    /// it, for each copy of it there is.
+    pub fn new(
+        cards: impl IntoIterator<Item = Card>,
+        modulus: Option<NonZeroU64>,
+    ) -> Result<Self, CascadeError> {
+        let mut matches = BTreeMap::new();
+        for card in cards {
+            if matches.insert(card.id, card.matches()).is_some() {
+                return Err(CascadeError::Duplicate(card.id));
+            }
         }
+        let modulus = modulus.map(|m| BigUint::from(m.get()));
+        let reduce = |n: BigUint| match &modulus {
+            Some(m) => n % m,
+            None => n,
+        };
+        let mut copies: BTreeMap<u32, BigUint> = matches
+            .keys()
+            .map(|&id| (id, reduce(BigUint::from(1u32))))
+            .collect();
+        let mut unwon = Vec::new();
+        // every card is won only by those before it, so is done counting by
+        // the time it's reached
+        for (&id, &n) in &matches {
+            let won = copies[&id].clone();
+            let mut missing = Vec::new();
+            // ids past u32::MAX can't be in the pile, or be listed as missing
+            let after = id.checked_add(1).map(|next| next..=u32::MAX);
+            for other in after.into_iter().flatten().take(n) {
+                match copies.get_mut(&other) {
+                    Some(count) => *count = reduce(&*count + &won),
+                    None => missing.push(other),
+                }
+            }
+            if !missing.is_empty() {
+                unwon.push(Unwon { card: id, missing });
+            }
+        }
+        Ok(Self {
+            copies,
+            unwon,
+            modulus,
+        })
     }
-}
 
-#[derive(Default)]
-struct Accumulator(VecDeque<usize>);
+    /// The copies of card `id`, the original included.
+    pub fn copies(&self, id: u32) -> Option<&BigUint> {
+        self.copies.get(&id)
+    }
 
-impl Accumulator {
-    pub fn pop(&mut self) -> Option<usize> {
-        self.0.pop_front()
+    /// Each card id and its copies, in id order.
+    pub fn breakdown(&self) -> impl Iterator<Item = (u32, &BigUint)> {
+        self.copies.iter().map(|(&id, n)| (id, n))
     }
 
-    pub fn win(&mut self, n: usize, multiplier: usize) {
-        for el in self.0.iter_mut().take(n) {
-            *el += multiplier;
-        }
-        if n > self.0.len() {
-            self.0.resize(n, multiplier);
+    /// Cards that would have won copies of cards the pile doesn't have.
+    pub fn unwon(&self) -> &[Unwon] {
+        &self.unwon
+    }
+
+    pub fn total(&self) -> BigUint {
+        let total: BigUint = self.copies.values().sum();
+        match &self.modulus {
+            Some(m) => total % m,
+            None => total,
         }
     }
 }
 
-pub fn num_cards(it: impl Iterator<Item = String>) -> u32 {
-    let mut accumulator = Accumulator::default();
-    let mut count = 0;
-    for card_score in it.map(|line| Card::from(line.as_str()).score()) {
-        let copies_of_current_card = accumulator.pop().unwrap_or(0) + 1;
-        count += copies_of_current_card;
-        accumulator.win(card_score, copies_of_current_card);
+/// Play out the cards on each line.
+pub fn cascade(
+    it: impl Iterator<Item = String>,
+    modulus: Option<NonZeroU64>,
+) -> Result<Cascade, CascadeError> {
+    Cascade::new(parse_lines(it)?, modulus)
+}
+
+pub fn num_cards(it: impl Iterator<Item = String>) -> Result<BigUint, CascadeError> {
+    Ok(cascade(it, None)?.total())
+}
+
+/// Optionally count modulo some number.
+#[derive(Default)]
+pub struct Params {
+    pub modulus: Option<NonZeroU64>,
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 4;
+    const PART: u8 = 2;
+
+    type Input = Vec<Card>;
+    type Params = Params;
+    type Answer = BigUint;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input.lines())?)
+    }
+
+    fn solve(input: Self::Input, params: &Self::Params) -> Result<Self::Answer> {
+        Ok(Cascade::new(input, params.modulus)?.total())
     }
-    count as u32
 }
 
 #[cfg(test)]
@@ -79,17 +146,117 @@ mod test {
     use super::*;
     use indoc::indoc;
 
-    
+    const EXAMPLE: &str = indoc! {"
+        Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+        Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+        Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+        Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+        Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+        Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+    "};
+
+    fn counts(cascade: &Cascade) -> Vec<(u32, u64)> {
+        cascade
+            .breakdown()
+            .map(|(id, n)| (id, n.try_into().unwrap()))
+            .collect()
+    }
+
     #[test]
     fn example() {
-       let example = indoc!{"
-            Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-            Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-            Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-            Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-            Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-            Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
-       "};
-       assert_eq!(num_cards(example.lines().map(String::from)), 30);
+        assert_eq!(
+            num_cards(EXAMPLE.lines().map(String::from)),
+            Ok(BigUint::from(30u32))
+        );
+        let cascade = cascade(EXAMPLE.lines().map(String::from), None).unwrap();
+        assert_eq!(
+            counts(&cascade),
+            [(1, 1), (2, 2), (3, 4), (4, 8), (5, 14), (6, 1)]
+        );
+        assert!(cascade.unwon().is_empty());
+    }
+
+    #[test]
+    fn unordered_and_gapped() {
+        let shuffled = EXAMPLE.lines().rev().map(String::from);
+        assert_eq!(
+            cascade(shuffled, None).unwrap().total(),
+            BigUint::from(30u32)
+        );
+        // without card 3, card 1 wins only 2, 4 and 5
+        let gapped = EXAMPLE
+            .lines()
+            .filter(|line| !line.starts_with("Card 3"))
+            .map(String::from);
+        let cascade = cascade(gapped, None).unwrap();
+        assert_eq!(counts(&cascade), [(1, 1), (2, 2), (4, 4), (5, 6), (6, 1)]);
+        assert_eq!(
+            cascade.unwon(),
+            [
+                Unwon {
+                    card: 1,
+                    missing: vec![3]
+                },
+                Unwon {
+                    card: 2,
+                    missing: vec![3]
+                },
+            ]
+        );
+        assert_eq!(cascade.copies(3), None);
+
+        let twice = EXAMPLE.lines().chain(EXAMPLE.lines().take(1));
+        assert_eq!(
+            super::cascade(twice.map(String::from), None),
+            Err(CascadeError::Duplicate(1))
+        );
+    }
+
+    #[test]
+    fn past_the_end() {
+        let cascade = cascade(EXAMPLE.lines().take(2).map(String::from), None).unwrap();
+        assert_eq!(counts(&cascade), [(1, 1), (2, 2)]);
+        assert_eq!(cascade.unwon()[0].missing, [3, 4, 5]);
+        assert_eq!(cascade.unwon()[1].missing, [3, 4]);
+    }
+
+    #[test]
+    fn huge_piles() {
+        // each card wins the next two, so card n has one less than the
+        // (n + 2)th Fibonacci number of copies
+        let pile: Vec<String> = (1..=200)
+            .map(|id| format!("Card {id}: 1 2 | 1 2"))
+            .collect();
+        let exact = cascade(pile.iter().cloned(), None).unwrap();
+        let fib = |n: usize| {
+            let (mut a, mut b) = (BigUint::from(0u32), BigUint::from(1u32));
+            for _ in 0..n {
+                (a, b) = (b.clone(), a + b);
+            }
+            a
+        };
+        assert_eq!(exact.copies(200), Some(&(fib(202) - 1u32)));
+        assert!(exact.total() > BigUint::from(u128::MAX));
+
+        let modulo = cascade(pile.into_iter(), NonZeroU64::new(1_000_000_007)).unwrap();
+        let m = BigUint::from(1_000_000_007u32);
+        assert_eq!(modulo.total(), exact.total() % &m);
+        assert_eq!(modulo.copies(200), Some(&((fib(202) - 1u32) % &m)));
+    }
+
+    #[test]
+    fn smallest_modulus() {
+        let one = cascade(EXAMPLE.lines().map(String::from), NonZeroU64::new(1)).unwrap();
+        assert_eq!(one.total(), BigUint::from(0u32));
+        // so `--modulus 0` is turned away as the command line is read
+        assert!("0".parse::<NonZeroU64>().is_err());
+    }
+
+    #[test]
+    fn last_id() {
+        let lines = ["Card 4294967294: 1 2 | 1 2", "Card 4294967295: 1 | 1"].map(String::from);
+        let cascade = cascade(lines.into_iter(), None).unwrap();
+        assert_eq!(counts(&cascade), [(4294967294, 1), (4294967295, 2)]);
+        assert!(cascade.unwon().is_empty());
     }
 }
@@ -1,9 +1,38 @@
-use std::io;
+use std::{io, num::NonZeroU64};
 
-use aoc2023_04_2::num_cards;
+use aoc2023_04_2::cascade;
+use clap::Parser;
+use color_eyre::Result;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = num_cards(lines);
-    println!("{answer}");
+#[derive(Parser)]
+#[command(about = "Count the scratchcards won from a pile")]
+struct Cli {
+    /// Count modulo this, rather than exactly
+    #[arg(long)]
+    modulus: Option<NonZeroU64>,
+    /// List the copies of each card before the total
+    #[arg(long)]
+    breakdown: bool,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let cli = Cli::parse();
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let cascade = cascade(lines, cli.modulus)?;
+    for unwon in cascade.unwon() {
+        let missing: Vec<String> = unwon.missing.iter().map(u32::to_string).collect();
+        eprintln!(
+            "Card {} would win missing cards {}",
+            unwon.card,
+            missing.join(", ")
+        );
+    }
+    if cli.breakdown {
+        for (id, copies) in cascade.breakdown() {
+            println!("Card {id}: {copies}");
+        }
+    }
+    println!("{}", cascade.total());
+    Ok(())
 }
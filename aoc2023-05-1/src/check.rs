@@ -0,0 +1,262 @@
+//! Checks for almanacs whose maps don't say what they seem to.
+//!
+//! [`Map::get`] takes the first mapping holding a number, in sorted order,
+//! and leaves numbers no mapping holds where they are. Neither is an error,
+//! but an almanac relying on either is easy to misread: overlapping mappings
+//! silently shadow each other, and numbers can end up where others started
+//! or where another mapping sends something else.
+
+use std::fmt;
+
+use crate::{normalise, subtract, Map, Maps, Range};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// Two mappings share numbers, which go by the first.
+    Overlap {
+        first: Range,
+        second: Range,
+        shared: Range,
+    },
+    /// Numbers between mappings, left where they are.
+    Gap(Range),
+    /// Numbers reached from more than one place.
+    Collision {
+        destinations: Range,
+        sources: Vec<Range>,
+    },
+}
+
+impl Problem {
+    /// Whether the map is ambiguous or loses track of numbers, rather than
+    /// just leaving some alone.
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Self::Gap(_))
+    }
+}
+
+/// A problem with the map from `source` to `destination`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub source: String,
+    pub destination: String,
+    pub problem: Problem,
+}
+
+/// Every issue with the maps, map by map.
+pub fn check(maps: &Maps) -> Vec<Issue> {
+    maps.maps().iter().flat_map(check_map).collect()
+}
+
+/// The problems with a single map.
+pub fn check_map(map: &Map) -> Vec<Issue> {
+    let issue = |problem| Issue {
+        source: map.source.clone(),
+        destination: map.destination.clone(),
+        problem,
+    };
+    let mut problems = overlaps(map);
+    let covered = normalise(map.mappings().iter().map(|m| m.range).collect());
+    problems.extend(covered.windows(2).map(|pair| {
+        Problem::Gap(Range {
+            start: pair[0].end + 1,
+            end: pair[1].start - 1,
+        })
+    }));
+    problems.extend(collisions(map, &covered));
+    problems.into_iter().map(issue).collect()
+}
+
+fn overlaps(map: &Map) -> Vec<Problem> {
+    let mappings = map.mappings();
+    let mut problems = Vec::new();
+    // mappings are sorted, so only those starting before one ends can
+    // overlap it
+    for (i, first) in mappings.iter().enumerate() {
+        for second in mappings[i + 1..]
+            .iter()
+            .take_while(|m| m.range.start <= first.range.end)
+        {
+            let (_, shared, _) = first.range.split(&second.range);
+            if !shared.is_empty() {
+                problems.push(Problem::Overlap {
+                    first: first.range,
+                    second: second.range,
+                    shared,
+                });
+            }
+        }
+    }
+    problems
+}
+
+/// Where numbers moved by `map` land on each other, or on numbers `covered`
+/// doesn't hold, which stay put.
+fn collisions(map: &Map, covered: &[Range]) -> Vec<Problem> {
+    // not flattened, since that drops mappings with an offset of 0, which
+    // still send numbers somewhere
+    let mut images: Vec<(Range, i64)> = map
+        .resolved()
+        .iter()
+        .map(|m| (m.range.offset(m.offset), m.offset))
+        .collect();
+    images.sort();
+    let mut problems = Vec::new();
+    for (i, &(first, first_offset)) in images.iter().enumerate() {
+        for &(second, second_offset) in images[i + 1..]
+            .iter()
+            .take_while(|(image, _)| image.start <= first.end)
+        {
+            let (_, shared, _) = first.split(&second);
+            problems.push(Problem::Collision {
+                destinations: shared,
+                sources: vec![shared.offset(-first_offset), shared.offset(-second_offset)],
+            });
+        }
+        for alone in subtract(first, covered) {
+            problems.push(Problem::Collision {
+                destinations: alone,
+                sources: vec![alone.offset(-first_offset), alone],
+            });
+        }
+    }
+    problems
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overlap {
+                first,
+                second,
+                shared,
+            } => write!(f, "{first} and {second} overlap at {shared}"),
+            Self::Gap(range) => write!(f, "{range} isn't mapped"),
+            Self::Collision {
+                destinations,
+                sources,
+            } => {
+                let sources: Vec<String> = sources.iter().map(Range::to_string).collect();
+                write!(
+                    f,
+                    "{destinations} is reached from {}",
+                    sources.join(" and ")
+                )
+            }
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-to-{}: {}",
+            self.source, self.destination, self.problem
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::get_maps;
+    use indoc::indoc;
+
+    fn maps(almanac: &str) -> Maps {
+        get_maps(almanac.lines().map(String::from).enumerate()).unwrap()
+    }
+
+    #[test]
+    fn clean() {
+        let maps = maps(indoc! {"
+            seed-to-soil map:
+            50 98 2
+            52 50 48
+        "});
+        assert_eq!(check(&maps), []);
+    }
+
+    #[test]
+    fn problems() {
+        let maps = maps(indoc! {"
+            seed-to-soil map:
+            0 10 5
+            100 12 5
+            30 20 5
+        "});
+        let problems: Vec<Problem> = check(&maps).into_iter().map(|i| i.problem).collect();
+        assert_eq!(
+            problems,
+            [
+                Problem::Overlap {
+                    first: Range::new(10, 5),
+                    second: Range::new(12, 5),
+                    shared: Range::new(12, 3),
+                },
+                Problem::Gap(Range::new(17, 3)),
+                // 0 to 4 and 30 to 34 are both reached from where they
+                // start as well
+                Problem::Collision {
+                    destinations: Range::new(0, 5),
+                    sources: vec![Range::new(10, 5), Range::new(0, 5)],
+                },
+                Problem::Collision {
+                    destinations: Range::new(30, 5),
+                    sources: vec![Range::new(20, 5), Range::new(30, 5)],
+                },
+                Problem::Collision {
+                    destinations: Range::new(103, 2),
+                    sources: vec![Range::new(15, 2), Range::new(103, 2)],
+                },
+            ]
+        );
+        assert!(problems[0].is_error() && !problems[1].is_error());
+        assert_eq!(
+            check(&maps)[0].to_string(),
+            "seed-to-soil: 10..=14 and 12..=16 overlap at 12..=14"
+        );
+    }
+
+    #[test]
+    fn moved_onto_each_other() {
+        let moved = maps(indoc! {"
+            a-to-b map:
+            5 0 5
+            7 10 5
+            0 5 5
+            10 15 5
+        "});
+        let problems: Vec<Problem> = check(&moved).into_iter().map(|i| i.problem).collect();
+        assert_eq!(
+            problems,
+            [
+                Problem::Collision {
+                    destinations: Range::new(7, 3),
+                    sources: vec![Range::new(2, 3), Range::new(10, 3)],
+                },
+                Problem::Collision {
+                    destinations: Range::new(10, 2),
+                    sources: vec![Range::new(13, 2), Range::new(15, 2)],
+                },
+            ]
+        );
+        // a mapping that leaves numbers alone still collides
+        let alone = maps(indoc! {"
+            seed-to-location map:
+            50 50 10
+            50 0 10
+        "});
+        let problems: Vec<Problem> = check(&alone).into_iter().map(|i| i.problem).collect();
+        assert_eq!(
+            problems,
+            [
+                Problem::Gap(Range::new(10, 40)),
+                Problem::Collision {
+                    destinations: Range::new(50, 10),
+                    sources: vec![Range::new(50, 10), Range::new(0, 10)],
+                },
+            ]
+        );
+    }
+}
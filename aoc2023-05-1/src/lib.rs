@@ -1,23 +1,140 @@
-use std::{iter::Peekable, sync::OnceLock};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt, iter,
+    str::FromStr,
+};
 
-use regex::Regex;
+use aoc2023_parse::{finish, parse_line, ParseError};
+use aoc2023_solution::{Result, Solution};
+use color_eyre::eyre::eyre;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alphanumeric1, i64, space1},
+    multi::many1,
+    sequence::{delimited, preceded, tuple},
+};
+use thiserror::Error;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct SourceRange {
-    start: i64,
-    end: i64,
+pub mod check;
+
+/// The numbers `start` to `end`, inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Range {
+    pub start: i64,
+    pub end: i64,
 }
 
-impl SourceRange {
+impl Range {
+    /// The `length` numbers from `start`.
+    pub fn new(start: i64, length: i64) -> Self {
+        Self {
+            start,
+            end: start + length - 1,
+        }
+    }
+
+    /// The `length` numbers from `start`, unless some are past `i64::MAX`.
+    pub fn checked_new(start: i64, length: i64) -> Option<Self> {
+        let end = start.checked_add(length.checked_sub(1)?)?;
+        Some(Self { start, end })
+    }
+
     pub fn has(&self, source: i64) -> bool {
         self.start <= source && source <= self.end
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.start > self.end
+    }
+
+    pub fn len(&self) -> i64 {
+        (self.end - self.start + 1).max(0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = i64> {
+        self.start..=self.end
+    }
+
+    /// `self` shifted by `offset`.
+    pub fn offset(&self, offset: i64) -> Self {
+        Self {
+            start: self.start + offset,
+            end: self.end + offset,
+        }
+    }
+
+    /// The part of `self` inside `other`, and the parts before and after it,
+    /// any of which may be empty.
+    pub fn split(&self, other: &Range) -> (Range, Range, Range) {
+        let inside = Range {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        };
+        // nothing comes before `i64::MIN` or after `i64::MAX`
+        let empty = Range {
+            start: i64::MAX,
+            end: i64::MIN,
+        };
+        let before = match other.start.checked_sub(1) {
+            Some(end) => Range {
+                start: self.start,
+                end: self.end.min(end),
+            },
+            None => empty,
+        };
+        let after = match other.end.checked_add(1) {
+            Some(start) => Range {
+                start: self.start.max(start),
+                end: self.end,
+            },
+            None => empty,
+        };
+        (before, inside, after)
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Mapping {
-    range: SourceRange,
-    offset: i64,
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={}", self.start, self.end)
+    }
+}
+
+/// Sort `ranges`, dropping empty ones and merging those that overlap or
+/// touch.
+pub fn normalise(mut ranges: Vec<Range>) -> Vec<Range> {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort();
+    let mut merged: Vec<Range> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end.saturating_add(1) => {
+                last.end = last.end.max(range.end)
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// The parts of `range` outside all of `others`, which must be sorted and
+/// not overlap, as from [`normalise`].
+pub fn subtract(range: Range, others: &[Range]) -> Vec<Range> {
+    let mut rest = Vec::new();
+    let mut remaining = range;
+    for other in others {
+        let (before, _, after) = remaining.split(other);
+        rest.push(before);
+        remaining = after;
+    }
+    rest.push(remaining);
+    rest.retain(|range| !range.is_empty());
+    rest
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Mapping {
+    pub range: Range,
+    pub offset: i64,
 }
 
 impl Mapping {
@@ -29,59 +146,286 @@ impl Mapping {
     }
 }
 
-impl From<&str> for Mapping {
-    fn from(value: &str) -> Self {
-        static SPEC: OnceLock<Regex> = OnceLock::new();
-        let captures = SPEC
-            .get_or_init(|| Regex::new(r"^(?<dest>\d+) (?<source>\d+) (?<length>\d+)$").unwrap())
-            .captures(value)
-            .unwrap();
-        let start = captures.name("source").unwrap().as_str().parse().unwrap();
-        let end = start
-            + captures
-                .name("length")
-                .unwrap()
-                .as_str()
-                .parse::<i64>()
-                .unwrap()
-            - 1;
-        let offset = captures
-            .name("dest")
-            .unwrap()
-            .as_str()
-            .parse::<i64>()
-            .unwrap()
-            - start;
+impl FromStr for Mapping {
+    type Err = ParseError;
 
-        Self {
-            range: SourceRange { start, end },
-            offset,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spec = tuple((i64, preceded(space1, i64), preceded(space1, i64)))(s);
+        let (dest, start, length) = finish(s, spec)?;
+        let length_column = s.trim_end().rfind(' ').map_or(0, |i| i + 1);
+        if length < 1 {
+            return Err(ParseError::at(s, length_column, "a length of at least 1"));
         }
+        // both ends of the range, wherever it's moved to, must fit
+        let fits = |start| Range::checked_new(start, length);
+        let too_big = |column| Err(ParseError::at(s, column, "a range that fits in 64 bits"));
+        let (Some(range), Some(_)) = (fits(start), fits(dest)) else {
+            return too_big(length_column);
+        };
+        let Some(offset) = dest.checked_sub(start) else {
+            return too_big(0);
+        };
+        Ok(Self { range, offset })
     }
 }
 
-#[derive(Debug, Default)]
-/// ordered list of mappings
-struct Map(Vec<Mapping>);
+/// An ordered list of mappings from one category of numbers to another.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Map {
+    pub source: String,
+    pub destination: String,
+    mappings: Vec<Mapping>,
+}
+
+/// The mappings of `pieces`, which mustn't overlap, merging neighbours moved
+/// by the same offset and leaving out any that stay put.
+fn merged(mut pieces: Vec<Mapping>) -> Vec<Mapping> {
+    pieces.retain(|piece| piece.offset != 0 && !piece.range.is_empty());
+    pieces.sort();
+    let mut map: Vec<Mapping> = Vec::with_capacity(pieces.len());
+    for piece in pieces {
+        match map.last_mut() {
+            Some(last)
+                if last.offset == piece.offset
+                    && last.range.end.checked_add(1) == Some(piece.range.start) =>
+            {
+                last.range.end = piece.range.end
+            }
+            _ => map.push(piece),
+        }
+    }
+    map
+}
 
 impl Map {
+    pub fn new(source: impl Into<String>, destination: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            destination: destination.into(),
+            mappings: Vec::new(),
+        }
+    }
+
+    fn from_pieces(source: &str, destination: &str, pieces: Vec<Mapping>) -> Self {
+        Self {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            mappings: merged(pieces),
+        }
+    }
+
     pub fn push(&mut self, mapping: Mapping) {
-        self.0.push(mapping);
-        self.0.sort();
+        self.mappings.push(mapping);
+        self.mappings.sort();
     }
 
     pub fn get(&self, source: i64) -> i64 {
-        for mapping in self.0.iter() {
+        for mapping in self.mappings.iter() {
             if let Some(destination) = mapping.get(source) {
                 return destination;
             }
         }
-        return source;
+        source
+    }
+
+    /// Where all of `sources` end up, split wherever a mapping starts or
+    /// ends. As with [`Map::get`], a number two mappings share goes by the
+    /// first, and one no mapping has stays where it is.
+    pub fn transform(&self, sources: &[Range]) -> Vec<Range> {
+        // what's left unmapped, by start, never overlapping
+        let mut unmapped: BTreeMap<i64, i64> = normalise(sources.to_vec())
+            .into_iter()
+            .map(|range| (range.start, range.end))
+            .collect();
+        let mut mapped = Vec::new();
+        for mapping in self
+            .mappings
+            .iter()
+            .filter(|mapping| !mapping.range.is_empty())
+        {
+            let hits: Vec<Range> = unmapped
+                .range(..=mapping.range.end)
+                .rev()
+                .map(|(&start, &end)| Range { start, end })
+                .take_while(|range| range.end >= mapping.range.start)
+                .collect();
+            for range in hits {
+                unmapped.remove(&range.start);
+                let (before, inside, after) = range.split(&mapping.range);
+                mapped.push(inside.offset(mapping.offset));
+                for rest in [before, after].into_iter().filter(|r| !r.is_empty()) {
+                    unmapped.insert(rest.start, rest.end);
+                }
+            }
+        }
+        mapped.extend(
+            unmapped
+                .into_iter()
+                .map(|(start, end)| Range { start, end }),
+        );
+        normalise(mapped)
+    }
+
+    pub fn mappings(&self) -> &[Mapping] {
+        &self.mappings
+    }
+
+    /// The same map with overlaps resolved as [`Map::get`] resolves them,
+    /// so no two mappings share a number.
+    pub fn flatten(&self) -> Map {
+        Map::from_pieces(&self.source, &self.destination, self.resolved())
+    }
+
+    /// The parts of each mapping [`Map::get`] actually uses, including those
+    /// that leave numbers where they are, which [`Map::flatten`] drops.
+    pub(crate) fn resolved(&self) -> Vec<Mapping> {
+        let mut claimed: Vec<Range> = Vec::new();
+        let mut pieces = Vec::new();
+        for mapping in self.mappings.iter() {
+            for range in subtract(mapping.range, &claimed) {
+                pieces.push(Mapping {
+                    range,
+                    offset: mapping.offset,
+                });
+            }
+            claimed.push(mapping.range);
+            claimed = normalise(claimed);
+        }
+        pieces
     }
+
+    /// `range` cut up by the mappings of a flattened map, with an offset of
+    /// 0 for the parts none of them have.
+    fn pieces(&self, range: Range) -> Vec<Mapping> {
+        let mut pieces = Vec::new();
+        let mut remaining = range;
+        for mapping in self.mappings.iter() {
+            let (before, inside, after) = remaining.split(&mapping.range);
+            pieces.push(Mapping {
+                range: before,
+                offset: 0,
+            });
+            pieces.push(Mapping {
+                range: inside,
+                offset: mapping.offset,
+            });
+            remaining = after;
+        }
+        pieces.push(Mapping {
+            range: remaining,
+            offset: 0,
+        });
+        pieces.retain(|piece| !piece.range.is_empty());
+        pieces
+    }
+
+    /// A single map doing what `self` and then `next` do.
+    pub fn then(&self, next: &Map) -> Map {
+        let (first, next) = (self.flatten(), next.flatten());
+        let mut pieces = Vec::new();
+        for mapping in first.mappings.iter() {
+            let image = mapping.range.offset(mapping.offset);
+            for piece in next.pieces(image) {
+                pieces.push(Mapping {
+                    range: piece.range.offset(-mapping.offset),
+                    offset: mapping.offset + piece.offset,
+                });
+            }
+        }
+        // whatever `self` leaves alone goes straight to `next`
+        let moved = normalise(first.mappings.iter().map(|mapping| mapping.range).collect());
+        for mapping in next.mappings.iter() {
+            for range in subtract(mapping.range, &moved) {
+                pieces.push(Mapping {
+                    range,
+                    offset: mapping.offset,
+                });
+            }
+        }
+        Map::from_pieces(&self.source, &next.destination, pieces)
+    }
+
+    /// The map taking every number back to where it came from, if no two
+    /// numbers end up in the same place.
+    pub fn invert(&self) -> Option<Map> {
+        let flat = self.flatten();
+        let sources = normalise(flat.mappings.iter().map(|mapping| mapping.range).collect());
+        let images: Vec<Range> = flat
+            .mappings
+            .iter()
+            .map(|mapping| mapping.range.offset(mapping.offset))
+            .collect();
+        // in 128 bits, since together they can hold more than an i64
+        let length = |ranges: &[Range]| {
+            ranges
+                .iter()
+                .map(|range| i128::from(range.end) - i128::from(range.start) + 1)
+                .sum::<i128>()
+        };
+        let covered = normalise(images.clone());
+        // moved numbers landing on each other, or on ones left alone
+        if length(&covered) != length(&images) || covered != sources {
+            return None;
+        }
+        let inverse = flat.mappings.iter().map(|mapping| Mapping {
+            range: mapping.range.offset(mapping.offset),
+            offset: -mapping.offset,
+        });
+        Some(Map::from_pieces(
+            &self.destination,
+            &self.source,
+            inverse.collect(),
+        ))
+    }
+
+    /// Every number [`Map::get`] takes to `destination`, in order.
+    pub fn preimages(&self, destination: i64) -> Vec<i64> {
+        let flat = self.flatten();
+        let mut sources: Vec<i64> = flat
+            .mappings
+            .iter()
+            .filter(|mapping| mapping.range.offset(mapping.offset).has(destination))
+            .map(|mapping| destination - mapping.offset)
+            .collect();
+        if !flat
+            .mappings
+            .iter()
+            .any(|mapping| mapping.range.has(destination))
+        {
+            sources.push(destination);
+        }
+        sources.sort();
+        sources
+    }
+
+    /// The numbers moved by a different offset from the number before.
+    pub fn breakpoints(&self) -> Vec<i64> {
+        let mut points: Vec<i64> = self
+            .flatten()
+            .mappings
+            .iter()
+            // nothing comes after `i64::MAX` to be moved differently
+            .flat_map(|mapping| {
+                iter::once(mapping.range.start).chain(mapping.range.end.checked_add(1))
+            })
+            .collect();
+        points.dedup();
+        points
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum QueryError {
+    #[error("No map to or from {0}")]
+    Unknown(String),
+    #[error("The maps from {0} to {1} send several numbers to the same place")]
+    NotInvertible(String, String),
 }
 
-#[derive(Debug, Default)]
-struct Maps(Vec<Map>);
+/// The maps of an almanac, in order, each going from the category the one
+/// before it went to.
+#[derive(Debug, Clone, Default)]
+pub struct Maps(Vec<Map>);
 
 impl Maps {
     pub fn digest(&self, mut seed: i64) -> i64 {
@@ -90,63 +434,205 @@ impl Maps {
         }
         seed
     }
+
+    pub fn maps(&self) -> &[Map] {
+        &self.0
+    }
+
+    /// The categories, from first to last.
+    pub fn categories(&self) -> Vec<&str> {
+        let first = self.0.first().map(|map| map.source.as_str());
+        first
+            .into_iter()
+            .chain(self.0.iter().map(|map| map.destination.as_str()))
+            .collect()
+    }
+
+    fn position(&self, category: &str) -> Result<usize, QueryError> {
+        self.categories()
+            .iter()
+            .position(|&c| c == category)
+            .ok_or_else(|| QueryError::Unknown(category.to_string()))
+    }
+
+    /// The maps leading from `source` to `destination`, which must come
+    /// later in the chain. Going from a category to itself takes no maps.
+    pub fn path(&self, source: &str, destination: &str) -> Result<Maps, QueryError> {
+        let (from, to) = (self.position(source)?, self.position(destination)?);
+        Ok(Maps(self.0[from..to.max(from)].to_vec()))
+    }
+
+    /// One map from `source` to `destination`, going backwards if
+    /// `destination` comes first, which only works if no two numbers end up
+    /// in the same place on the way.
+    pub fn between(&self, source: &str, destination: &str) -> Result<Map, QueryError> {
+        if self.position(source)? <= self.position(destination)? {
+            let mut composite = self.path(source, destination)?.compose();
+            (composite.source, composite.destination) = (source.into(), destination.into());
+            return Ok(composite);
+        }
+        self.between(destination, source)?
+            .invert()
+            .ok_or_else(|| QueryError::NotInvertible(destination.into(), source.into()))
+    }
+
+    /// One map doing what the whole chain does, to look up many seeds
+    /// without going through every map each time.
+    pub fn compose(&self) -> Map {
+        let Some(first) = self.0.first() else {
+            return Map::default();
+        };
+        let identity = Map::new(&first.source, &first.source);
+        self.0
+            .iter()
+            .fold(identity, |composite, map| composite.then(map))
+    }
+
+    /// Where all of `seeds` end up, a map at a time.
+    pub fn transform(&self, seeds: &[Range]) -> Vec<Range> {
+        self.0
+            .iter()
+            .fold(normalise(seeds.to_vec()), |ranges, map| {
+                map.transform(&ranges)
+            })
+    }
 }
 
-fn get_seeds(line: &str) -> Vec<i64> {
-    static SEEDS: OnceLock<Regex> = OnceLock::new();
-    SEEDS
-        .get_or_init(|| Regex::new(r"\d+").unwrap())
-        .find_iter(line)
-        .filter_map(|s| s.as_str().parse().ok())
-        .collect()
+fn get_seeds(line: &str) -> Result<Vec<i64>, ParseError> {
+    let seeds = preceded(tag("seeds:"), many1(preceded(space1, i64)))(line);
+    finish(line, seeds)
 }
 
-/// get a single Map from input stream
-fn get_map<I>(it: &mut Peekable<I>) -> Map
-where
-    I: Iterator<Item = String>,
-{
-    static PREAMBLE: OnceLock<Regex> = OnceLock::new();
-    let preamble = PREAMBLE.get_or_init(|| Regex::new(r"^[^\d]*$").unwrap());
+/// The categories named by a header like `seed-to-soil map:`.
+fn get_header(line: &str) -> Result<Map, ParseError> {
+    let header = tuple((
+        alphanumeric1,
+        delimited(tag("-to-"), alphanumeric1, tag(" map:")),
+    ))(line);
+    let (source, destination) = finish(line, header)?;
+    Ok(Map::new(source, destination))
+}
 
-    // throw away preamble
-    while let Some(line) = it.peek() {
-        if !preamble.is_match(line.as_str()) {
-            break;
+/// Read every map, in whatever order they come, and chain them together.
+pub fn get_maps(it: impl Iterator<Item = (usize, String)>) -> Result<Maps, ParseError> {
+    let mut sections: Vec<(usize, String, Map)> = Vec::new();
+    for (i, line) in it {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !line.starts_with(|c: char| c.is_ascii_digit() || c == '-') {
+            let map = get_header(&line).map_err(|e| e.at_line(i + 1))?;
+            sections.push((i, line, map));
+            continue;
         }
-        it.next();
+        let Some((_, _, map)) = sections.last_mut() else {
+            return Err(ParseError::at(&line, 0, "a map header").at_line(i + 1));
+        };
+        map.push(parse_line(i, &line)?);
     }
+    chain(sections)
+}
 
-    let mut map = Map::default();
-    while let Some(line) = it.peek() {
-        if preamble.is_match(line.as_str()) {
-            break;
+/// Put `sections` in order, with each map going from where the one before
+/// went to. Every category may have at most one map from it and one to it,
+/// and all the maps must join up into a single chain.
+fn chain(sections: Vec<(usize, String, Map)>) -> Result<Maps, ParseError> {
+    let error = |n: usize, column: usize, expected: String| {
+        let (i, line, _) = &sections[n];
+        ParseError::at(line, column, expected).at_line(i + 1)
+    };
+    let mut from = HashMap::new();
+    let mut to = HashSet::new();
+    for (n, (_, line, map)) in sections.iter().enumerate() {
+        if from.insert(map.source.as_str(), n).is_some() {
+            return Err(error(n, 0, format!("the only map from {}", map.source)));
+        }
+        if !to.insert(map.destination.as_str()) {
+            let column = line.find("-to-").unwrap() + 4;
+            return Err(error(
+                n,
+                column,
+                format!("the only map to {}", map.destination),
+            ));
         }
-        map.push(Mapping::from(line.as_str()));
-        it.next();
     }
-    map
+    // start from seeds if they're a start at all
+    let starts = |n: &usize| !to.contains(sections[*n].2.source.as_str());
+    let seeds = (0..sections.len()).find(|n| starts(n) && sections[*n].2.source == "seed");
+    let mut order = Vec::new();
+    let mut next = seeds.or_else(|| (0..sections.len()).find(starts));
+    while let Some(n) = next {
+        order.push(n);
+        next = from.get(sections[n].2.destination.as_str()).copied();
+    }
+    if let Some(stray) = (0..sections.len()).find(|n| !order.contains(n)) {
+        let expected = match order.last() {
+            Some(&last) => format!("a map from {}", sections[last].2.destination),
+            None => "maps that don't go round in a loop".to_string(),
+        };
+        return Err(error(stray, 0, expected));
+    }
+    let mut maps: Vec<Option<Map>> = sections.into_iter().map(|(_, _, map)| Some(map)).collect();
+    Ok(Maps(
+        order.into_iter().filter_map(|n| maps[n].take()).collect(),
+    ))
 }
 
-fn get_maps(it: impl Iterator<Item = String>) -> Maps {
-    let mut maps = Vec::new();
-    let mut it = it.peekable();
+/// The seeds, and every map, checking that they lead from seeds to
+/// locations.
+pub fn parse_almanac(it: impl Iterator<Item = String>) -> Result<(Vec<i64>, Maps), ParseError> {
+    let mut it = it.enumerate();
+    let Some((i, line)) = it.next() else {
+        return Err(ParseError::at("", 0, "a seeds line"));
+    };
+    let seeds = get_seeds(&line).map_err(|e| e.at_line(i + 1))?;
+    let maps = get_maps(it)?;
+    check_seeds_to_locations(&maps, &line).map_err(|e| e.at_line(i + 1))?;
+    Ok((seeds, maps))
+}
 
-    while let Some(_) = it.peek() {
-        maps.push(get_map(&mut it));
+/// Whether `maps` go from seeds to locations, blaming the seeds line if
+/// not.
+pub fn check_seeds_to_locations(maps: &Maps, seeds_line: &str) -> Result<(), ParseError> {
+    let categories = maps.categories();
+    let seed = categories.iter().position(|&c| c == "seed");
+    let location = categories.iter().position(|&c| c == "location");
+    match (seed, location) {
+        (Some(seed), Some(location)) if seed < location => Ok(()),
+        _ => Err(ParseError::at(seeds_line, 0, "maps from seed to location")),
     }
+}
 
-    Maps(maps)
+/// The nearest location any of `seeds` is planted at, if there are any,
+/// and if `maps` lead from seeds to locations.
+pub fn nearest_location(seeds: &[i64], maps: &Maps) -> Option<i64> {
+    let maps = maps.path("seed", "location").ok()?;
+    seeds.iter().map(|seed| maps.digest(*seed)).min()
 }
 
-pub fn nearest_seed_location(mut it: impl Iterator<Item = String>) -> i64 {
-    let seeds = get_seeds(it.next().unwrap().as_str());
-    let maps = get_maps(it);
-    seeds
-        .iter()
-        .map(|seed| maps.digest(seed.clone()))
-        .min()
-        .unwrap()
+pub fn nearest_seed_location(it: impl Iterator<Item = String>) -> Result<i64, ParseError> {
+    let (seeds, maps) = parse_almanac(it)?;
+    Ok(nearest_location(&seeds, &maps).expect("parse_almanac checks for seeds and a path"))
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 5;
+    const PART: u8 = 1;
+
+    /// The seeds, and every map.
+    type Input = (Vec<i64>, Maps);
+    type Params = ();
+    type Answer = i64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_almanac(input.lines().map(String::from))?)
+    }
+
+    fn solve((seeds, maps): Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        nearest_location(&seeds, &maps).ok_or(eyre!("No seeds to plant"))
+    }
 }
 
 #[cfg(test)]
@@ -154,43 +640,251 @@ mod test {
     use super::*;
     use indoc::indoc;
 
+    const EXAMPLE: &str = indoc! {"
+        seeds: 79 14 55 13
+
+        seed-to-soil map:
+        50 98 2
+        52 50 48
+
+        soil-to-fertilizer map:
+        0 15 37
+        37 52 2
+        39 0 15
+
+        fertilizer-to-water map:
+        49 53 8
+        0 11 42
+        42 0 7
+        57 7 4
+
+        water-to-light map:
+        88 18 7
+        18 25 70
+
+        light-to-temperature map:
+        45 77 23
+        81 45 19
+        68 64 13
+
+        temperature-to-humidity map:
+        0 69 1
+        1 0 69
+
+        humidity-to-location map:
+        60 56 37
+        56 93 4
+        "};
+
     #[test]
     fn full_example() {
-        let example = indoc!{"
+        assert_eq!(
+            nearest_seed_location(EXAMPLE.lines().map(String::from)),
+            Ok(35)
+        )
+    }
+
+    #[test]
+    fn ranges() {
+        let range = Range::new(10, 5);
+        assert_eq!(range, Range { start: 10, end: 14 });
+        let (before, inside, after) = range.split(&Range::new(12, 10));
+        assert_eq!((before, inside), (Range::new(10, 2), Range::new(12, 3)));
+        assert!(after.is_empty());
+        assert_eq!(
+            normalise(vec![Range::new(5, 3), Range::new(0, 5), Range::new(20, 0)]),
+            [Range::new(0, 8)]
+        );
+        let everything = Range {
+            start: i64::MIN,
+            end: i64::MAX,
+        };
+        let (before, _, after) = everything.split(&everything);
+        assert!(before.is_empty() && after.is_empty());
+        assert_eq!(normalise(vec![everything, Range::new(0, 1)]), [everything]);
+        assert_eq!(Range::checked_new(i64::MAX, 2), None);
+    }
+
+    #[test]
+    fn transform() {
+        let mut map = Map::default();
+        map.push("50 98 2".parse().unwrap());
+        map.push("52 50 48".parse().unwrap());
+        // 40..=59 is partly left alone and partly moved up by 2
+        assert_eq!(
+            map.transform(&[Range::new(40, 20)]),
+            [Range::new(40, 10), Range::new(52, 10)]
+        );
+        assert_eq!(
+            map.transform(&[Range::new(96, 6)]),
+            [Range::new(50, 2), Range::new(98, 4)]
+        );
+        // overlaps go by the first mapping, as for single numbers
+        map.push("0 55 10".parse().unwrap());
+        let sources = Range::new(45, 30);
+        let expected: Vec<Range> =
+            normalise(sources.iter().map(|n| Range::new(map.get(n), 1)).collect());
+        assert_eq!(map.transform(&[sources]), expected);
+    }
+
+    #[test]
+    fn compose() {
+        let (_, maps) = parse_almanac(EXAMPLE.lines().map(String::from)).unwrap();
+        let composite = maps.compose();
+        for seed in -5..=110 {
+            assert_eq!(composite.get(seed), maps.digest(seed), "{seed}");
+        }
+        // seed 82 is the nearest of part 2
+        assert_eq!(composite.preimages(46), [82]);
+        let inverse = composite.invert().unwrap();
+        for seed in -5..=110 {
+            assert_eq!(inverse.get(composite.get(seed)), seed);
+        }
+        let breakpoints = composite.breakpoints();
+        assert_eq!(
+            (breakpoints[0], breakpoints[breakpoints.len() - 1]),
+            (0, 100)
+        );
+        for seed in -5..=110 {
+            let moved = |n: i64| composite.get(n) - n;
+            let at_breakpoint = breakpoints.contains(&seed);
+            assert_eq!(moved(seed) != moved(seed - 1), at_breakpoint, "{seed}");
+        }
+    }
+
+    #[test]
+    fn not_invertible() {
+        let mut map = Map::default();
+        map.push("10 0 5".parse().unwrap());
+        // 10 is where 0 goes, as well as where it started
+        assert_eq!(map.invert(), None);
+        assert_eq!(map.preimages(10), [0, 10]);
+        assert!(map.preimages(3).is_empty());
+        assert_eq!(map.breakpoints(), [0, 5]);
+        // overlaps go by the first mapping
+        map.push("20 2 1".parse().unwrap());
+        assert_eq!(map.flatten().mappings().len(), 1);
+        map.push("5 10 5".parse().unwrap());
+        map.push("0 5 5".parse().unwrap());
+        let inverse = map.invert().unwrap();
+        assert_eq!(
+            (0..15).map(|n| inverse.get(n)).collect::<Vec<_>>(),
+            [5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0, 1, 2, 3, 4]
+        );
+    }
+
+    /// `EXAMPLE` with its maps in the order of `order`.
+    fn reordered(order: [usize; 7]) -> String {
+        let sections: Vec<&str> = EXAMPLE.split("\n\n").collect();
+        let maps = order.iter().map(|&n| sections[n + 1].trim_end());
+        let mut almanac = vec![sections[0]];
+        almanac.extend(maps);
+        almanac.join("\n\n")
+    }
+
+    #[test]
+    fn categories() {
+        let almanac = reordered([6, 2, 0, 5, 1, 3, 4]);
+        let (_, maps) = parse_almanac(almanac.lines().map(String::from)).unwrap();
+        assert_eq!(
+            maps.categories(),
+            [
+                "seed",
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity",
+                "location"
+            ]
+        );
+        assert_eq!(
+            nearest_seed_location(almanac.lines().map(String::from)),
+            Ok(35)
+        );
+
+        let soil_to_humidity = maps.between("soil", "humidity").unwrap();
+        let path = maps.path("soil", "humidity").unwrap();
+        assert_eq!(path.categories().len(), 6);
+        for soil in 0..110 {
+            assert_eq!(soil_to_humidity.get(soil), path.digest(soil));
+        }
+        assert_eq!(
+            (&*soil_to_humidity.source, &*soil_to_humidity.destination),
+            ("soil", "humidity")
+        );
+        let location_to_seed = maps.between("location", "seed").unwrap();
+        assert_eq!(location_to_seed.get(46), 82);
+        assert_eq!(maps.between("soil", "soil").unwrap().get(7), 7);
+        assert_eq!(
+            maps.between("seed", "sand"),
+            Err(QueryError::Unknown("sand".to_string()))
+        );
+    }
+
+    #[test]
+    fn broken_chains() {
+        let error = |almanac: &str| {
+            let err = parse_almanac(almanac.lines().map(String::from)).unwrap_err();
+            (err.line(), err.column(), err.expected().to_string())
+        };
+        let twice = EXAMPLE.replace("soil-to-fertilizer", "seed-to-fertilizer");
+        assert_eq!(error(&twice), (7, 1, "the only map from seed".into()));
+        let twice = EXAMPLE.replace("light-to-temperature", "light-to-water");
+        assert_eq!(error(&twice), (22, 10, "the only map to water".into()));
+        let gap = EXAMPLE.replace("water-to-light", "waste-to-light");
+        assert_eq!(error(&gap), (18, 1, "a map from water".into()));
+        let short = EXAMPLE.replace("humidity-to-location", "humidity-to-place");
+        assert_eq!(error(&short), (1, 1, "maps from seed to location".into()));
+        let headless = "seeds: 1 2\n50 98 2\n";
+        assert_eq!(error(headless), (2, 1, "a map header".into()));
+        let header = EXAMPLE.replace("seed-to-soil map:", "seed to soil map:");
+        assert_eq!(error(&header).0, 3);
+    }
+
+    #[test]
+    fn malformed_mapping() {
+        let example = indoc! {"
             seeds: 79 14 55 13
 
             seed-to-soil map:
             50 98 2
-            52 50 48
-
-            soil-to-fertilizer map:
-            0 15 37
-            37 52 2
-            39 0 15
-
-            fertilizer-to-water map:
-            49 53 8
-            0 11 42
-            42 0 7
-            57 7 4
-
-            water-to-light map:
-            88 18 7
-            18 25 70
-
-            light-to-temperature map:
-            45 77 23
-            81 45 19
-            68 64 13
-
-            temperature-to-humidity map:
-            0 69 1
-            1 0 69
-
-            humidity-to-location map:
-            60 56 37
-            56 93 4
+            52 50
         "};
-        assert_eq!(nearest_seed_location(example.lines().map(String::from)), 35)
+        let err = parse_almanac(example.lines().map(String::from)).unwrap_err();
+        assert_eq!((err.line(), err.column()), (5, 6));
+
+        // past the end of an i64, where the numbers start or end up
+        for (mapping, column) in [
+            ("0 9223372036854775800 9", 23),
+            ("9223372036854775800 0 9", 23),
+            ("9223372036854775807 -1 1", 1),
+        ] {
+            let err = mapping.parse::<Mapping>().unwrap_err();
+            assert_eq!(err.column(), column, "{mapping}");
+            assert_eq!(err.expected(), "a range that fits in 64 bits");
+        }
+        assert!("0 9223372036854775800 8".parse::<Mapping>().is_ok());
+
+        for mapping in ["50 98 0", "50 98 -2"] {
+            let err = mapping.parse::<Mapping>().unwrap_err();
+            assert_eq!(err.column(), 7, "{mapping}");
+            assert_eq!(err.expected(), "a length of at least 1");
+        }
+    }
+
+    #[test]
+    fn edge_of_i64() {
+        let mut map = Map::default();
+        map.push("0 9223372036854775800 8".parse().unwrap());
+        assert_eq!(map.breakpoints(), [9223372036854775800]);
+        // 0 to 7 are where they started as well
+        assert_eq!(map.invert(), None);
+        map.push("9223372036854775800 0 8".parse().unwrap());
+        assert_eq!(map.breakpoints(), [0, 8, 9223372036854775800]);
+        let inverse = map.invert().unwrap();
+        assert_eq!(inverse.get(0), 9223372036854775800);
+        assert_eq!(inverse.get(i64::MAX), 7);
     }
 }
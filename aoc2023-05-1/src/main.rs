@@ -1,9 +1,68 @@
 use std::io;
 
-use aoc2023_05_1::nearest_seed_location;
+use aoc2023_05_1::{check::check, nearest_seed_location, parse_almanac};
+use clap::Parser;
+use color_eyre::{eyre::bail, Result};
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = nearest_seed_location(lines);
-    println!("Answer: {answer}");
+#[derive(Parser)]
+#[command(about = "Find the nearest location for any of the almanac's seeds")]
+struct Cli {
+    /// Instead, list the numbers of `--from` that end up at this number of
+    /// `--to`
+    #[arg(long)]
+    seeds_for: Vec<i64>,
+    /// Instead, list the numbers of `--from` moved differently from the
+    /// number before them
+    #[arg(long, conflicts_with = "seeds_for")]
+    breakpoints: bool,
+    /// Instead, report overlapping mappings, unmapped gaps and numbers
+    /// reached from more than one place, failing on all but gaps
+    #[arg(long, conflicts_with_all = ["seeds_for", "breakpoints"])]
+    check: bool,
+    /// The category to map from
+    #[arg(long, default_value = "seed")]
+    from: String,
+    /// The category to map to, which may come before `--from`
+    #[arg(long, default_value = "location")]
+    to: String,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let cli = Cli::parse();
+    let lines = io::stdin().lines().map_while(Result::ok);
+    if cli.check {
+        let (_, maps) = parse_almanac(lines)?;
+        let issues = check(&maps);
+        for issue in &issues {
+            println!("{issue}");
+        }
+        let errors = issues.iter().filter(|i| i.problem.is_error()).count();
+        if errors > 0 {
+            bail!("found {errors} overlaps or collisions");
+        }
+        return Ok(());
+    }
+    if cli.seeds_for.is_empty() && !cli.breakpoints {
+        let answer = nearest_seed_location(lines)?;
+        println!("Answer: {answer}");
+        return Ok(());
+    }
+    let (_, maps) = parse_almanac(lines)?;
+    // composed once, however many numbers are asked about
+    let composite = maps.between(&cli.from, &cli.to)?;
+    if cli.breakpoints {
+        for number in composite.breakpoints() {
+            println!("{number} -> {}", composite.get(number));
+        }
+    }
+    for number in cli.seeds_for {
+        let sources: Vec<String> = composite
+            .preimages(number)
+            .iter()
+            .map(i64::to_string)
+            .collect();
+        println!("{number}: {}", sources.join(" "));
+    }
+    Ok(())
 }
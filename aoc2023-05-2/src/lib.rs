@@ -1,189 +1,120 @@
-use std::{iter::Peekable, sync::OnceLock};
-
-use regex::Regex;
-
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Range {
-    start: i64,
-    end: i64,
+use std::str::FromStr;
+
+use aoc2023_05_1::{check_seeds_to_locations, get_maps, Maps, Range};
+use aoc2023_parse::{finish, parse_line, ParseError};
+use aoc2023_solution::{Result, Solution};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{i64, space1},
+    multi::many1,
+    sequence::{preceded, separated_pair},
+};
+
+/// At least one range of seeds, none of them empty.
+#[derive(Debug)]
+pub struct Seeds {
+    pub ranges: Vec<Range>,
 }
 
-impl Range {
-    pub fn has(&self, source: i64) -> bool {
-        self.start <= source && source <= self.end
+impl FromStr for Seeds {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pairs = preceded(
+            tag("seeds:"),
+            many1(preceded(space1, separated_pair(i64, space1, i64))),
+        )(s);
+        // every other number after `seeds:` is a length
+        let lengths = s
+            .split_whitespace()
+            .skip(2)
+            .step_by(2)
+            .map(|length| length.as_ptr() as usize - s.as_ptr() as usize);
+        let ranges = finish(s, pairs)?
+            .into_iter()
+            .zip(lengths)
+            .map(|((start, length), column)| {
+                if length < 1 {
+                    return Err(ParseError::at(s, column, "a length of at least 1"));
+                }
+                Range::checked_new(start, length)
+                    .ok_or_else(|| ParseError::at(s, column, "a range that fits in 64 bits"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { ranges })
     }
+}
 
-    pub fn iter(&self) -> impl Iterator<Item = i64> {
-        self.start..self.end
+impl Seeds {
+    fn iter(&self) -> impl Iterator<Item = i64> + '_ {
+        self.ranges.iter().flat_map(|range| range.iter())
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Mapping {
-    range: Range,
-    offset: i64,
+/// The seed ranges, and the maps from seeds to locations.
+pub fn parse_almanac(it: impl Iterator<Item = String>) -> Result<(Seeds, Maps), ParseError> {
+    let mut it = it.enumerate();
+    let Some((i, line)) = it.next() else {
+        return Err(ParseError::at("", 0, "a seeds line"));
+    };
+    let seeds = parse_line::<Seeds>(i, &line)?;
+    let maps = get_maps(it)?;
+    check_seeds_to_locations(&maps, &line).map_err(|e| e.at_line(i + 1))?;
+    Ok((seeds, maps.path("seed", "location").unwrap()))
 }
 
-impl Mapping {
-    pub fn get(&self, source: i64) -> Option<i64> {
-        if self.range.has(source) {
-            return Some(source + self.offset);
-        }
-        None
-    }
+/// The lowest location of any of `seeds`, following whole ranges of seeds
+/// through `maps` at once.
+pub fn nearest_location(seeds: &Seeds, maps: &Maps) -> i64 {
+    maps.transform(&seeds.ranges)[0].start
 }
 
-impl From<&str> for Mapping {
-    fn from(value: &str) -> Self {
-        static SPEC: OnceLock<Regex> = OnceLock::new();
-        let captures = SPEC
-            .get_or_init(|| Regex::new(r"^(?<dest>\d+) (?<source>\d+) (?<length>\d+)$").unwrap())
-            .captures(value)
-            .unwrap();
-        let start = captures.name("source").unwrap().as_str().parse().unwrap();
-        let end = start
-            + captures
-                .name("length")
-                .unwrap()
-                .as_str()
-                .parse::<i64>()
-                .unwrap()
-            - 1;
-        let offset = captures
-            .name("dest")
-            .unwrap()
-            .as_str()
-            .parse::<i64>()
-            .unwrap()
-            - start;
-
-        Self {
-            range: Range { start, end },
-            offset,
-        }
-    }
+/// The same as [`nearest_location`], but following every seed on its own,
+/// which is far too slow for real almanacs but simple enough to check
+/// against.
+pub fn nearest_location_per_seed(seeds: &Seeds, maps: &Maps) -> i64 {
+    seeds.iter().map(|seed| maps.digest(seed)).min().unwrap()
 }
 
-#[derive(Debug, Default)]
-/// ordered list of mappings
-struct Map(Vec<Mapping>);
-
-impl Map {
-    pub fn push(&mut self, mapping: Mapping) {
-        self.0.push(mapping);
-        self.0.sort();
-    }
-
-    pub fn get(&self, source: i64) -> i64 {
-        for mapping in self.0.iter() {
-            if let Some(destination) = mapping.get(source) {
-                return destination;
-            }
-        }
-        return source;
-    }
+/// The lowest location of any seed in the almanac.
+pub fn nearest_seed_location(it: impl Iterator<Item = String>) -> Result<i64, ParseError> {
+    let (seeds, maps) = parse_almanac(it)?;
+    Ok(nearest_location(&seeds, &maps))
 }
 
-#[derive(Debug, Default)]
-struct Maps(Vec<Map>);
-
-impl Maps {
-    pub fn digest(&self, mut seed: i64) -> i64 {
-        for map in self.0.iter() {
-            seed = map.get(seed);
-        }
-        seed
-    }
+/// As [`nearest_seed_location`], a seed at a time.
+pub fn nearest_seed_location_per_seed(it: impl Iterator<Item = String>) -> Result<i64, ParseError> {
+    let (seeds, maps) = parse_almanac(it)?;
+    Ok(nearest_location_per_seed(&seeds, &maps))
 }
 
-#[derive(Debug)]
-struct Seeds {
-    ranges: Vec<Range>,
+/// Whether to go a seed at a time.
+#[derive(Default)]
+pub struct Params {
+    pub per_seed: bool,
 }
 
-impl From<&str> for Seeds {
-    fn from(value: &str) -> Self {
-        static SEEDS: OnceLock<Regex> = OnceLock::new();
-        let ranges = SEEDS
-            .get_or_init(|| Regex::new(r"\b(?<from>\d+)\s+(?<length>\d+)\b").unwrap())
-            .captures_iter(value)
-            .map(|capture| {
-                let start = capture
-                    .name("from")
-                    .unwrap()
-                    .as_str()
-                    .parse::<i64>()
-                    .unwrap();
-                let length = capture
-                    .name("length")
-                    .unwrap()
-                    .as_str()
-                    .parse::<i64>()
-                    .unwrap();
-
-                Range {
-                    start,
-                    end: start + length - 1,
-                }
-            })
-            .collect();
+pub struct Solver;
 
-        Self { ranges }
-    }
-}
+impl Solution for Solver {
+    const DAY: u8 = 5;
+    const PART: u8 = 2;
 
-impl Seeds {
-    fn into_iter(self) -> impl Iterator<Item = i64> {
-        self.ranges.into_iter().map(|range| range.iter()).flatten()
-    }
-}
+    /// The seed ranges, and the maps from seeds to locations.
+    type Input = (Seeds, Maps);
+    type Params = Params;
+    type Answer = i64;
 
-/// get a single Map from input stream
-fn get_map<I>(it: &mut Peekable<I>) -> Map
-where
-    I: Iterator<Item = String>,
-{
-    static PREAMBLE: OnceLock<Regex> = OnceLock::new();
-    let preamble = PREAMBLE.get_or_init(|| Regex::new(r"^[^\d]*$").unwrap());
-
-    // throw away preamble
-    while let Some(line) = it.peek() {
-        if !preamble.is_match(line.as_str()) {
-            break;
-        }
-        it.next();
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_almanac(input.lines().map(String::from))?)
     }
 
-    let mut map = Map::default();
-    while let Some(line) = it.peek() {
-        if preamble.is_match(line.as_str()) {
-            break;
+    fn solve((seeds, maps): Self::Input, params: &Self::Params) -> Result<Self::Answer> {
+        match params.per_seed {
+            true => Ok(nearest_location_per_seed(&seeds, &maps)),
+            false => Ok(nearest_location(&seeds, &maps)),
         }
-        map.push(Mapping::from(line.as_str()));
-        it.next();
     }
-    map
-}
-
-fn get_maps(it: impl Iterator<Item = String>) -> Maps {
-    let mut maps = Vec::new();
-    let mut it = it.peekable();
-
-    while let Some(_) = it.peek() {
-        maps.push(get_map(&mut it));
-    }
-
-    Maps(maps)
-}
-
-pub fn nearest_seed_location(mut it: impl Iterator<Item = String>) -> i64 {
-    let seeds = Seeds::from(it.next().unwrap().as_str());
-    let maps = get_maps(it);
-    seeds
-        .into_iter()
-        .map(|seed| maps.digest(seed.clone()))
-        .min()
-        .unwrap()
 }
 
 #[cfg(test)]
@@ -191,43 +122,89 @@ mod test {
     use super::*;
     use indoc::indoc;
 
+    const EXAMPLE: &str = indoc! {"
+        seeds: 79 14 55 13
+
+        seed-to-soil map:
+        50 98 2
+        52 50 48
+
+        soil-to-fertilizer map:
+        0 15 37
+        37 52 2
+        39 0 15
+
+        fertilizer-to-water map:
+        49 53 8
+        0 11 42
+        42 0 7
+        57 7 4
+
+        water-to-light map:
+        88 18 7
+        18 25 70
+
+        light-to-temperature map:
+        45 77 23
+        81 45 19
+        68 64 13
+
+        temperature-to-humidity map:
+        0 69 1
+        1 0 69
+
+        humidity-to-location map:
+        60 56 37
+        56 93 4
+    "};
+
     #[test]
     fn full_example() {
-        let example = indoc! {"
-            seeds: 79 14 55 13
-
-            seed-to-soil map:
-            50 98 2
-            52 50 48
-
-            soil-to-fertilizer map:
-            0 15 37
-            37 52 2
-            39 0 15
-
-            fertilizer-to-water map:
-            49 53 8
-            0 11 42
-            42 0 7
-            57 7 4
-
-            water-to-light map:
-            88 18 7
-            18 25 70
-
-            light-to-temperature map:
-            45 77 23
-            81 45 19
-            68 64 13
-
-            temperature-to-humidity map:
-            0 69 1
-            1 0 69
-
-            humidity-to-location map:
-            60 56 37
-            56 93 4
-        "};
-        assert_eq!(nearest_seed_location(example.lines().map(String::from)), 46)
+        let lines = || EXAMPLE.lines().map(String::from);
+        assert_eq!(nearest_seed_location(lines()), Ok(46));
+        assert_eq!(nearest_seed_location_per_seed(lines()), Ok(46));
+    }
+
+    #[test]
+    fn same_as_per_seed() {
+        // seeds straddling the edges of the maps, and one past them all
+        for seeds in [
+            "seeds: 0 100",
+            "seeds: 97 3 49 2",
+            "seeds: 53 10 70 20",
+            "seeds: 100 5 1 1",
+        ] {
+            let almanac = EXAMPLE.replacen("seeds: 79 14 55 13", seeds, 1);
+            let lines = || almanac.lines().map(String::from);
+            assert_eq!(
+                nearest_seed_location(lines()),
+                nearest_seed_location_per_seed(lines()),
+                "{seeds}"
+            );
+        }
+    }
+
+    #[test]
+    fn too_many_seeds() {
+        let err = "seeds: 1 2 9223372036854775800 9"
+            .parse::<Seeds>()
+            .unwrap_err();
+        assert_eq!(err.column(), 32);
+        assert!("seeds: 9223372036854775800 8".parse::<Seeds>().is_ok());
+    }
+
+    #[test]
+    fn empty_ranges() {
+        let err = "seeds: 79 0".parse::<Seeds>().unwrap_err();
+        assert_eq!(
+            (err.column(), err.expected()),
+            (11, "a length of at least 1")
+        );
+        let err = "seeds: 79 14 55 -13".parse::<Seeds>().unwrap_err();
+        assert_eq!(err.column(), 17);
+        let almanac = EXAMPLE.replacen("seeds: 79 14 55 13", "seeds: 79 0", 1);
+        let lines = || almanac.lines().map(String::from);
+        assert!(nearest_seed_location(lines()).is_err());
+        assert!(nearest_seed_location_per_seed(lines()).is_err());
     }
 }
@@ -1,9 +1,28 @@
 use std::io;
 
-use aoc2023_05_2::nearest_seed_location;
+use aoc2023_05_2::{nearest_seed_location, nearest_seed_location_per_seed};
+use clap::Parser;
+use color_eyre::{eyre::bail, Result};
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = nearest_seed_location(lines);
+#[derive(Parser)]
+#[command(about = "Find the nearest location for any seed in the almanac's ranges")]
+struct Cli {
+    /// Also follow every seed on its own, failing if the answers differ
+    #[arg(long)]
+    cross_check: bool,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let cli = Cli::parse();
+    let lines: Vec<String> = io::stdin().lines().collect::<io::Result<_>>()?;
+    let answer = nearest_seed_location(lines.iter().cloned())?;
+    if cli.cross_check {
+        let per_seed = nearest_seed_location_per_seed(lines.into_iter())?;
+        if per_seed != answer {
+            bail!("ranges give {answer} but seeds one at a time give {per_seed}");
+        }
+    }
     println!("Answer: {answer}");
+    Ok(())
 }
@@ -1,45 +1,127 @@
 use std::{iter::zip, sync::OnceLock};
 
+use aoc2023_parse::ParseError;
+use aoc2023_solution::{Result, Solution};
+use num_bigint::BigUint;
+use num_integer::Roots;
+use num_traits::CheckedMul;
 use regex::Regex;
 
-fn get_numbers(line: &str) -> Vec<u64> {
+/// The numbers in `line`, with where each starts.
+fn get_numbers(line: &str) -> Result<Vec<(usize, u64)>, ParseError> {
     static NUMBERS: OnceLock<Regex> = OnceLock::new();
     NUMBERS
         .get_or_init(|| Regex::new(r"\d+").unwrap())
         .find_iter(line)
-        .filter_map(|s| s.as_str().parse().ok())
+        .map(|m| {
+            let number = m
+                .as_str()
+                .parse()
+                .map_err(|_| ParseError::at(line, m.start(), "a number that fits in 64 bits"))?;
+            Ok((m.start(), number))
+        })
         .collect()
 }
 
-fn distance(race_time: &u64, hold_time: &u64) -> u64 {
-    hold_time * (race_time - hold_time)
+/// Integers races can be solved in exactly: `u64`, `u128` or `BigUint`.
+pub trait RaceInt: Roots + Clone + From<u8> + CheckedMul {}
+
+impl<T: Roots + Clone + From<u8> + CheckedMul> RaceInt for T {}
+
+/// How many whole-millisecond hold times go further than `record` in a race
+/// lasting `time`, or `None` if `T` can't hold `time` squared.
+///
+/// Holding for `h` goes `h * (time - h)`, so the winners lie strictly
+/// between the roots of `h² - time * h + record`, `time / 2 ± √D / 2` with
+/// `D = time² - 4 * record`. The integer square root of `D` pins the first
+/// winner down to within a step or two, which are checked exactly.
+pub fn ways_to_beat<T: RaceInt>(time: &T, record: &T) -> Option<T> {
+    let two = T::from(2);
+    let squared = time.checked_mul(time)?;
+    let four_records = record.checked_mul(&T::from(4))?;
+    if squared <= four_records {
+        return Some(T::zero());
+    }
+    let root = (squared - four_records).sqrt();
+    let wins = |hold: &T| hold.clone() * (time.clone() - hold.clone()) > *record;
+    // no later than the first winner, since √D is at most 1 more than root
+    let mut hold = (time.clone() - root) / two.clone();
+    while hold.clone() * two.clone() <= *time {
+        if wins(&hold) {
+            // the winners are symmetric about time / 2
+            return Some(time.clone() - hold.clone() - hold + T::one());
+        }
+        hold = hold + T::one();
+    }
+    Some(T::zero())
 }
 
 fn num_ways_to_beat_record(race_time: &u64, record: &u64) -> u64 {
-    let t: f64 = *race_time as f64;
-    let r: f64 = *record as f64;
+    let ways = ways_to_beat(&u128::from(*race_time), &u128::from(*record))
+        .expect("u128 holds the square of any u64");
+    // never more than the race's length
+    ways as u64
+}
 
-    let midpoint = t / 2.0;
-    let delta = (t * t / 4.0 - r).sqrt();
+/// A race's length, and the furthest anyone's gone in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Race {
+    pub time: u64,
+    pub record: u64,
+}
 
-    let mut min_win = (midpoint - delta).ceil() as u64;
-    if distance(race_time, &min_win) == *record {
-        min_win += 1;
-    }
-    let mut max_win = (midpoint + delta).floor() as u64;
-    if distance(race_time, &max_win) == *record {
-        max_win -= 1;
+/// The races laid out in columns under `Time:` and `Distance:`.
+pub fn read_races(mut it: impl Iterator<Item = String>) -> Result<Vec<Race>, ParseError> {
+    let mut line = |number, expected| {
+        it.next()
+            .ok_or_else(|| ParseError::at("", 0, expected).at_line(number))
+    };
+    let times = get_numbers(&line(1, "a line of times")?).map_err(|e| e.at_line(1))?;
+    let distances = line(2, "a line of distances")?;
+    let records = get_numbers(&distances).map_err(|e| e.at_line(2))?;
+    if records.len() != times.len() {
+        // at the first distance too many, or the end of the line
+        let column = records.get(times.len()).map_or(distances.len(), |r| r.0);
+        let err = ParseError::at(&distances, column, "as many distances as times");
+        return Err(err.at_line(2));
     }
-    max_win - min_win + 1
+    Ok(zip(times, records)
+        .map(|((_, time), (_, record))| Race { time, record })
+        .collect())
+}
+
+/// The product of each race's ways to win, which can be far more than a
+/// `u64` holds.
+pub fn product_of_ways(races: &[Race]) -> BigUint {
+    races
+        .iter()
+        .map(|race| BigUint::from(num_ways_to_beat_record(&race.time, &race.record)))
+        .product()
+}
+
+pub fn product_of_record_breaking_strategies(
+    it: impl Iterator<Item = String>,
+) -> Result<BigUint, ParseError> {
+    Ok(product_of_ways(&read_races(it)?))
 }
 
-pub fn product_of_record_breaking_strategies(mut it: impl Iterator<Item = String>) -> u64 {
-    let times = get_numbers(it.next().unwrap().as_str());
-    let records = get_numbers(it.next().unwrap().as_str());
-    zip(times.iter(), records.iter())
-        .map(|(time, record)| num_ways_to_beat_record(time, record))
-        .reduce(|acc, v| acc * v)
-        .unwrap()
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 6;
+    const PART: u8 = 1;
+
+    type Input = Vec<Race>;
+    type Params = ();
+    type Answer = BigUint;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(read_races(input.lines().map(String::from))?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(product_of_ways(&input))
+    }
 }
 
 #[cfg(test)]
@@ -47,6 +129,57 @@ mod test {
     use super::*;
     use indoc::indoc;
 
+    /// Every hold time tried, one at a time.
+    fn brute_force(time: u64, record: u64) -> u64 {
+        (0..=time)
+            .filter(|hold| hold * (time - hold) > record)
+            .count() as u64
+    }
+
+    #[test]
+    fn small_races() {
+        for time in 0..40u64 {
+            for record in 0..=time * time / 4 + 1 {
+                let expected = brute_force(time, record);
+                assert_eq!(ways_to_beat(&time, &record), Some(expected));
+                let wide = ways_to_beat(&u128::from(time), &u128::from(record));
+                assert_eq!(wide, Some(u128::from(expected)));
+                let big = ways_to_beat(&BigUint::from(time), &BigUint::from(record));
+                assert_eq!(big, Some(BigUint::from(expected)));
+            }
+        }
+    }
+
+    #[test]
+    fn huge_races() {
+        // a record set by holding for `hold` is beaten by everything strictly
+        // between `hold` and `time - hold`
+        let time: u128 = (1 << 63) + 7;
+        let hold: u128 = (1 << 40) + 3;
+        let record = hold * (time - hold);
+        assert_eq!(ways_to_beat(&time, &record), Some(time - 2 * hold - 1));
+        assert_eq!(
+            ways_to_beat(&time, &(record - 1)),
+            Some(time - 2 * hold + 1)
+        );
+
+        // past 2^53, where floating point can't tell neighbouring holds apart
+        let time: u64 = (1 << 53) + 1;
+        let record = 3 * (time - 3);
+        assert_eq!(num_ways_to_beat_record(&time, &record), time - 7);
+        assert_eq!(ways_to_beat(&time, &record), None);
+
+        let time = BigUint::from(10u32).pow(60) + 1u32;
+        let hold = BigUint::from(10u32).pow(25);
+        let record = &hold * (&time - &hold);
+        let expected = &time - &hold * 2u32 - 1u32;
+        assert_eq!(ways_to_beat(&time, &record), Some(expected));
+        assert_eq!(
+            ways_to_beat(&time, &(&time * &time)),
+            Some(BigUint::from(0u32))
+        );
+    }
+
     #[test]
     fn full_example() {
         let example = indoc! {"
@@ -55,7 +188,44 @@ mod test {
         "};
         assert_eq!(
             product_of_record_breaking_strategies(example.lines().map(String::from)),
-            288
+            Ok(BigUint::from(288u32))
         )
     }
+
+    #[test]
+    fn huge_product() {
+        let races = [Race {
+            time: 4_000_000_000,
+            record: 0,
+        }; 3];
+        let expected = BigUint::from(3_999_999_999u64).pow(3);
+        assert_eq!(product_of_ways(&races), expected);
+    }
+
+    #[test]
+    fn missing_distances() {
+        let err = read_races(["Time: 7 15 30".to_string()].into_iter()).unwrap_err();
+        assert_eq!((err.line(), err.expected()), (2, "a line of distances"));
+    }
+
+    #[test]
+    fn malformed_races() {
+        let error = |times: &str, distances: &str| {
+            let lines = [times.to_string(), distances.to_string()];
+            let err = read_races(lines.into_iter()).unwrap_err();
+            (err.line(), err.column(), err.expected().to_string())
+        };
+        let too_big = "a number that fits in 64 bits".to_string();
+        assert_eq!(
+            error("Time: 99999999999999999999 7", "Distance: 5 9"),
+            (1, 7, too_big.clone())
+        );
+        assert_eq!(
+            error("Time: 7", "Distance: 99999999999999999999"),
+            (2, 11, too_big)
+        );
+        let count = "as many distances as times".to_string();
+        assert_eq!(error("Time: 7 15", "Distance: 9"), (2, 12, count.clone()));
+        assert_eq!(error("Time: 7", "Distance: 9 40"), (2, 13, count));
+    }
 }
@@ -1,9 +1,12 @@
 use std::io;
 
 use aoc2023_06_1::product_of_record_breaking_strategies;
+use color_eyre::Result;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = product_of_record_breaking_strategies(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = product_of_record_breaking_strategies(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
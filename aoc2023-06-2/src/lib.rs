@@ -1,5 +1,9 @@
 use std::sync::OnceLock;
 
+use aoc2023_06_1::ways_to_beat;
+use aoc2023_parse::ParseError;
+use aoc2023_solution::{Result, Solution};
+use num_bigint::BigUint;
 use regex::Regex;
 
 fn strip_whitespace(line: &str) -> String {
@@ -7,44 +11,62 @@ fn strip_whitespace(line: &str) -> String {
     line.replace(" ", "")
 }
 
-fn get_number(line: &str) -> u64 {
+/// The digits of `line` run together, however many there are.
+fn get_number(line: &str) -> Option<BigUint> {
     let stripped = strip_whitespace(line);
     static NUMBER: OnceLock<Regex> = OnceLock::new();
-    NUMBER
+    let digits = NUMBER
         .get_or_init(|| Regex::new(r"\d+").unwrap())
-        .find(stripped.as_str())
-        .unwrap()
-        .as_str()
-        .parse()
-        .unwrap()
+        .find(stripped.as_str())?;
+    Some(digits.as_str().parse().expect("digits make a number"))
 }
 
-fn distance(race_time: &u64, hold_time: &u64) -> u64 {
-    hold_time * (race_time - hold_time)
+/// The one long race, its length and record kerned across the lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Race {
+    pub time: BigUint,
+    pub record: BigUint,
 }
 
-fn num_ways_to_beat_record(race_time: &u64, record: &u64) -> u64 {
-    let t: f64 = *race_time as f64;
-    let r: f64 = *record as f64;
-
-    let midpoint = t / 2.0;
-    let delta = (t * t / 4.0 - r).sqrt();
-
-    let mut min_win = (midpoint - delta).ceil() as u64;
-    if distance(race_time, &min_win) == *record {
-        min_win += 1;
+impl Race {
+    pub fn read(mut it: impl Iterator<Item = String>) -> Result<Self, ParseError> {
+        let mut number = |line, expected| {
+            let missing = || ParseError::at("", 0, expected).at_line(line);
+            get_number(&it.next().ok_or_else(missing)?).ok_or_else(missing)
+        };
+        let time = number(1, "a line with the time")?;
+        let record = number(2, "a line with the distance")?;
+        Ok(Self { time, record })
     }
-    let mut max_win = (midpoint + delta).floor() as u64;
-    if distance(race_time, &max_win) == *record {
-        max_win -= 1;
+
+    pub fn ways_to_beat(&self) -> BigUint {
+        ways_to_beat(&self.time, &self.record).expect("big integers never overflow")
     }
-    max_win - min_win + 1
 }
 
-pub fn num_record_breaking_strategies(mut it: impl Iterator<Item = String>) -> u64 {
-    let time = get_number(it.next().unwrap().as_str());
-    let record = get_number(it.next().unwrap().as_str());
-    num_ways_to_beat_record(&time, &record)
+pub fn num_record_breaking_strategies(
+    it: impl Iterator<Item = String>,
+) -> Result<BigUint, ParseError> {
+    Ok(Race::read(it)?.ways_to_beat())
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 6;
+    const PART: u8 = 2;
+
+    type Input = Race;
+    type Params = ();
+    type Answer = BigUint;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Race::read(input.lines().map(String::from))?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(input.ways_to_beat())
+    }
 }
 
 #[cfg(test)]
@@ -60,7 +82,35 @@ mod test {
         "};
         assert_eq!(
             num_record_breaking_strategies(example.lines().map(String::from)),
-            71503
+            Ok(BigUint::from(71503u32))
         )
     }
+
+    #[test]
+    fn kerned_past_u128() {
+        // 10^40 + 1 and a record set by holding for 10^15
+        let example = indoc! {"
+            Time:      1 0000000000 0000000000 0000000000 0000000001
+            Distance:  9999999999 9999999999 9999900000 0000000001 0000000000 00000
+        "};
+        let time = BigUint::from(10u32).pow(40) + 1u32;
+        let hold = BigUint::from(10u32).pow(15);
+        assert_eq!(
+            get_number(example.lines().nth(1).unwrap()),
+            Some(&hold * (&time - &hold))
+        );
+        assert_eq!(
+            num_record_breaking_strategies(example.lines().map(String::from)),
+            Ok(time - hold * 2u32 - 1u32)
+        )
+    }
+
+    #[test]
+    fn missing_numbers() {
+        let lines = |s: &str| s.lines().map(String::from).collect::<Vec<_>>();
+        let err = Race::read(lines("Time: 7").into_iter()).unwrap_err();
+        assert_eq!(err.line(), 2);
+        let err = Race::read(lines("Time:\nDistance: 9").into_iter()).unwrap_err();
+        assert_eq!((err.line(), err.expected()), (1, "a line with the time"));
+    }
 }
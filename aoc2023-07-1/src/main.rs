@@ -1,9 +1,12 @@
 use std::io;
 
 use aoc2023_07_1::total_winnings;
+use color_eyre::Result;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = total_winnings(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = total_winnings(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
@@ -1,6 +1,14 @@
-use std::{collections::HashMap, sync::OnceLock};
+use std::{collections::HashMap, str::FromStr};
 
-use regex::Regex;
+use aoc2023_parse::{finish, parse_lines, ParseError};
+use aoc2023_solution::{Result, Solution};
+use nom::{
+    character::complete::{anychar, space1, u64},
+    combinator::map_res,
+    multi::count,
+    sequence::separated_pair,
+    IResult,
+};
 use thiserror::Error;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
@@ -78,7 +86,7 @@ impl From<&[Card; 5]> for Type {
                 joker_count += 1;
                 continue;
             }
-            if let Some(current) = counts.get_mut(&card) {
+            if let Some(current) = counts.get_mut(card) {
                 *current += 1;
             } else {
                 counts.insert(card.clone(), 1);
@@ -119,42 +127,43 @@ struct Hand {
     cards: [Card; 5],
 }
 
-impl From<&str> for Hand {
-    fn from(value: &str) -> Self {
-        let cards = value
-            .chars()
-            .take(5)
-            .filter_map(|c| c.try_into().ok())
-            .collect::<Vec<Card>>()
-            .try_into()
-            .unwrap();
+impl From<[Card; 5]> for Hand {
+    fn from(cards: [Card; 5]) -> Self {
         let t = Type::from(&cards);
         Self { cards, t }
     }
 }
 
+/// A hand and what's bid on it.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
-struct Prospect {
+pub struct Prospect {
     hand: Hand,
     bid: u64,
 }
 
-impl From<&str> for Prospect {
-    fn from(value: &str) -> Self {
-        static HAND_BID: OnceLock<Regex> = OnceLock::new();
-        let (_, [hand, bid]) = HAND_BID
-            .get_or_init(|| Regex::new(r"(\w+)\s+(\d+)").unwrap())
-            .captures(value)
-            .unwrap()
-            .extract();
-        let hand = Hand::from(hand);
-        let bid = bid.parse().unwrap();
-        Self { hand, bid }
+impl FromStr for Prospect {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn card(input: &str) -> IResult<&str, Card> {
+            map_res(anychar, Card::try_from)(input)
+        }
+        let prospect = separated_pair(count(card, 5), space1, u64)(s);
+        let (cards, bid) = finish(s, prospect)?;
+        let cards: [Card; 5] = cards.try_into().unwrap();
+        Ok(Self {
+            hand: cards.into(),
+            bid,
+        })
     }
 }
 
-pub fn total_winnings(it: impl Iterator<Item = String>) -> u64 {
-    let mut prospects: Vec<Prospect> = it.map(|line| Prospect::from(line.as_str())).collect();
+pub fn total_winnings(it: impl Iterator<Item = String>) -> Result<u64, ParseError> {
+    Ok(winnings(parse_lines(it)?))
+}
+
+/// Each bid times the rank of its hand, weakest first.
+pub fn winnings(mut prospects: Vec<Prospect>) -> u64 {
     prospects.sort();
     prospects
         .iter()
@@ -164,6 +173,25 @@ pub fn total_winnings(it: impl Iterator<Item = String>) -> u64 {
         .sum()
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 7;
+    const PART: u8 = 2;
+
+    type Input = Vec<Prospect>;
+    type Params = ();
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input.lines())?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(winnings(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -178,6 +206,13 @@ mod test {
             KTJJT 220
             QQQJA 483
         "};
-        assert_eq!(total_winnings(example.lines().map(String::from)), 5905);
+        assert_eq!(total_winnings(example.lines().map(String::from)), Ok(5905));
+    }
+
+    #[test]
+    fn malformed_hand() {
+        let err =
+            total_winnings(["32T3K 765", "T55X5 684"].map(String::from).into_iter()).unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 4));
     }
 }
@@ -1,11 +1,16 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
 
+use aoc2023_parse::{finish, parse_line, ParseError};
+use aoc2023_solution::{Result, Solution};
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{alpha1, char},
     combinator::value,
-    multi::many0,
+    multi::many1,
     sequence::{delimited, separated_pair},
     IResult,
 };
@@ -24,10 +29,15 @@ impl Direction {
 
 struct Instructions(Vec<Direction>);
 
-impl Instructions {
-    pub fn parse(input: &str) -> Self {
-        Self(many0(Direction::parse)(input).unwrap().1)
+impl FromStr for Instructions {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        finish(s, many1(Direction::parse)(s)).map(Self)
     }
+}
+
+impl Instructions {
     pub fn iter(&self) -> impl Iterator<Item = &Direction> {
         self.0.iter().cycle()
     }
@@ -39,9 +49,11 @@ struct NodeDef {
     right: String,
 }
 
-impl From<&str> for NodeDef {
-    fn from(input: &str) -> Self {
-        Self::parse(input)
+impl FromStr for NodeDef {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        finish(s, Self::parse(s))
     }
 }
 
@@ -51,13 +63,13 @@ impl NodeDef {
         delimited(char('('), neighbors, char(')'))(input)
     }
 
-    pub fn parse(input: &str) -> Self {
-        let parsed = separated_pair(alpha1, tag(" = "), Self::neighbors)(input).unwrap();
-        let (_, (id, (left, right))) = parsed;
+    pub fn parse(input: &str) -> IResult<&str, Self> {
+        let (rest, (id, (left, right))) =
+            separated_pair(alpha1, tag(" = "), Self::neighbors)(input)?;
         let id = id.to_string();
         let left = left.to_string();
         let right = right.to_string();
-        Self { id, left, right }
+        Ok((rest, Self { id, left, right }))
     }
 }
 
@@ -66,33 +78,93 @@ struct Neighbours {
     right: String,
 }
 
-fn read_graph(it: impl Iterator<Item = String>) -> HashMap<String, Neighbours> {
-    let mut map = HashMap::new();
+/// The turns to take, and where each node leads.
+pub struct Network {
+    instructions: Instructions,
+    graph: HashMap<String, Neighbours>,
+}
+
+impl Network {
+    /// Read the instructions and nodes, checking that every node leads to
+    /// one that's defined, and that there's a way in and out.
+    pub fn read(it: impl Iterator<Item = String>) -> Result<Self, ParseError> {
+        let mut it = it.enumerate();
+        let Some((i, line)) = it.next() else {
+            return Err(ParseError::at("", 0, "a line of instructions"));
+        };
+        let instructions = parse_line(i, &line)?;
+        it.next(); // skip a blank line
+        let mut nodes = Vec::new();
+        for (i, line) in it {
+            let node: NodeDef = parse_line(i, &line)?;
+            nodes.push((i, line, node));
+        }
+        let defined: HashSet<&str> = nodes.iter().map(|(_, _, node)| node.id.as_str()).collect();
+        for (i, line, node) in &nodes {
+            // `ID = (LEFT, RIGHT)`
+            let left = line.find('(').map_or(0, |column| column + 1);
+            let right = left + node.left.len() + 2;
+            for (name, column) in [(&node.left, left), (&node.right, right)] {
+                if !defined.contains(name.as_str()) {
+                    return Err(ParseError::at(line, column, "a node that's defined").at_line(i + 1));
+                }
+            }
+        }
+        let end = nodes.last().map_or(i + 1, |(i, _, _)| i + 1);
+        for name in ["AAA", "ZZZ"] {
+            if !defined.contains(name) {
+                return Err(ParseError::at("", 0, format!("a node {name}")).at_line(end + 1));
+            }
+        }
+        let graph = nodes
+            .into_iter()
+            .map(|(_, _, NodeDef { id, left, right })| (id, Neighbours { left, right }))
+            .collect();
+        Ok(Self {
+            instructions,
+            graph,
+        })
+    }
 
-    for line in it {
-        let NodeDef { id, left, right } = NodeDef::from(line.as_str());
-        map.insert(id, Neighbours { left, right });
+    /// Steps from `AAA` to `ZZZ`.
+    pub fn steps(&self) -> u64 {
+        let mut node = "AAA";
+        let mut steps = 0;
+        for turning in self.instructions.iter() {
+            if node == "ZZZ" {
+                break;
+            }
+            node = match turning {
+                Direction::Left => self.graph[node].left.as_str(),
+                Direction::Right => self.graph[node].right.as_str(),
+            };
+            steps += 1;
+        }
+        steps
     }
-    map
 }
 
-pub fn count_steps(mut it: impl Iterator<Item = String>) -> u64 {
-    let instructions = Instructions::parse(it.next().unwrap().as_str());
-    it.next(); // skip a blank line
-    let graph = read_graph(it);
-    let mut node = "AAA";
-    let mut steps = 0;
-    for turning in instructions.iter() {
-        if node == "ZZZ" {
-            break;
-        }
-        node = match turning {
-            Direction::Left => graph[node].left.as_str(),
-            Direction::Right => graph[node].right.as_str(),
-        };
-        steps += 1;
+pub fn count_steps(it: impl Iterator<Item = String>) -> Result<u64, ParseError> {
+    Ok(Network::read(it)?.steps())
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 8;
+    const PART: u8 = 1;
+
+    type Input = Network;
+    type Params = ();
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Network::read(input.lines().map(String::from))?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(input.steps())
     }
-    steps
 }
 
 #[cfg(test)]
@@ -113,7 +185,7 @@ mod test {
             GGG = (GGG, GGG)
             ZZZ = (ZZZ, ZZZ)
         "};
-        assert_eq!(count_steps(example.lines().map(String::from)), 2);
+        assert_eq!(count_steps(example.lines().map(String::from)), Ok(2));
     }
 
     #[test]
@@ -125,6 +197,27 @@ mod test {
             BBB = (AAA, ZZZ)
             ZZZ = (ZZZ, ZZZ)
         "};
-        assert_eq!(count_steps(example.lines().map(String::from)), 6);
+        assert_eq!(count_steps(example.lines().map(String::from)), Ok(6));
+    }
+
+    #[test]
+    fn undefined_nodes() {
+        let error = |network: &str| {
+            let err = count_steps(network.lines().map(String::from)).unwrap_err();
+            (err.line(), err.column(), err.expected().to_string())
+        };
+        let example = indoc! {"
+            LLR
+
+            AAA = (BBB, BBB)
+            BBB = (AAA, ZZZ)
+            ZZZ = (ZZZ, ZZZ)
+        "};
+        let missing = example.replace("BBB = (AAA", "BBB = (CCC");
+        assert_eq!(error(&missing), (4, 8, "a node that's defined".into()));
+        let missing = example.replace("(AAA, ZZZ)", "(AAA, CCC)");
+        assert_eq!(error(&missing), (4, 13, "a node that's defined".into()));
+        let no_way_out = "L\n\nAAA = (AAA, AAA)\n";
+        assert_eq!(error(no_way_out), (4, 1, "a node ZZZ".into()));
     }
 }
@@ -1,12 +1,18 @@
+use aoc2023_parse::{finish, parse_line, ParseError};
+use aoc2023_solution::{Result, Solution};
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+use tracing::{debug, info, instrument};
 
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{alphanumeric1, char},
     combinator::value,
-    multi::many0,
+    multi::many1,
     sequence::{delimited, separated_pair},
     IResult,
 };
@@ -26,10 +32,15 @@ impl Direction {
 #[derive(Debug)]
 struct Instructions(Vec<Direction>);
 
-impl Instructions {
-    pub fn parse(input: &str) -> Self {
-        Self(many0(Direction::parse)(input).unwrap().1)
+impl FromStr for Instructions {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        finish(s, many1(Direction::parse)(s)).map(Self)
     }
+}
+
+impl Instructions {
     pub fn iter(&self) -> impl Iterator<Item = (usize, &Direction)> {
         self.0.iter().enumerate().cycle()
     }
@@ -41,9 +52,11 @@ struct NodeDef {
     right: String,
 }
 
-impl From<&str> for NodeDef {
-    fn from(input: &str) -> Self {
-        Self::parse(input)
+impl FromStr for NodeDef {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        finish(s, Self::parse(s))
     }
 }
 
@@ -53,13 +66,13 @@ impl NodeDef {
         delimited(char('('), neighbors, char(')'))(input)
     }
 
-    pub fn parse(input: &str) -> Self {
-        let parsed = separated_pair(alphanumeric1, tag(" = "), Self::neighbors)(input).unwrap();
-        let (_, (id, (left, right))) = parsed;
+    pub fn parse(input: &str) -> IResult<&str, Self> {
+        let (rest, (id, (left, right))) =
+            separated_pair(alphanumeric1, tag(" = "), Self::neighbors)(input)?;
         let id = id.to_string();
         let left = left.to_string();
         let right = right.to_string();
-        Self { id, left, right }
+        Ok((rest, Self { id, left, right }))
     }
 }
 
@@ -68,18 +81,59 @@ struct Neighbours {
     right: String,
 }
 
-fn read_graph(it: impl Iterator<Item = String>) -> (HashMap<String, Neighbours>, Vec<String>) {
-    let mut map = HashMap::new();
-    let mut start_nodes = Vec::new();
+/// The turns to take, where each node leads, and the nodes ghosts start
+/// from.
+pub struct Network {
+    instructions: Instructions,
+    graph: HashMap<String, Neighbours>,
+    start_nodes: Vec<String>,
+}
 
-    for line in it {
-        let NodeDef { id, left, right } = NodeDef::from(line.as_str());
-        if id.ends_with("A") {
-            start_nodes.push(id.clone());
+impl Network {
+    /// Read the instructions and nodes, checking that every node leads to
+    /// one that's defined, and that there's somewhere to start.
+    pub fn read(it: impl Iterator<Item = String>) -> Result<Self, ParseError> {
+        let mut it = it.enumerate();
+        let Some((i, line)) = it.next() else {
+            return Err(ParseError::at("", 0, "a line of instructions"));
+        };
+        let instructions = parse_line(i, &line)?;
+        it.next(); // skip a blank line
+        let mut nodes = Vec::new();
+        for (i, line) in it {
+            let node: NodeDef = parse_line(i, &line)?;
+            nodes.push((i, line, node));
+        }
+        let defined: HashSet<&str> = nodes.iter().map(|(_, _, node)| node.id.as_str()).collect();
+        for (i, line, node) in &nodes {
+            // `ID = (LEFT, RIGHT)`
+            let left = line.find('(').map_or(0, |column| column + 1);
+            let right = left + node.left.len() + 2;
+            for (name, column) in [(&node.left, left), (&node.right, right)] {
+                if !defined.contains(name.as_str()) {
+                    return Err(ParseError::at(line, column, "a node that's defined").at_line(i + 1));
+                }
+            }
+        }
+        let start_nodes: Vec<String> = nodes
+            .iter()
+            .map(|(_, _, node)| node.id.clone())
+            .filter(|id| id.ends_with('A'))
+            .collect();
+        if start_nodes.is_empty() {
+            let end = nodes.last().map_or(i + 1, |(i, _, _)| i + 1);
+            return Err(ParseError::at("", 0, "a node ending in A").at_line(end + 1));
         }
-        map.insert(id, Neighbours { left, right });
+        let graph = nodes
+            .into_iter()
+            .map(|(_, _, NodeDef { id, left, right })| (id, Neighbours { left, right }))
+            .collect();
+        Ok(Self {
+            instructions,
+            graph,
+            start_nodes,
+        })
     }
-    (map, start_nodes)
 }
 
 #[derive(Debug)]
@@ -130,7 +184,7 @@ impl<'a> NodeFollower<'a> {
         if let Some(mu) = self.seen.get(&(node, step)) {
             // we have found cycle!
             self.mu_lambda = Some((*mu, self.counter - mu));
-            self.zs.retain(|i| i >= &mu);
+            self.zs.retain(|i| i >= mu);
             self.zs.iter_mut().for_each(|i| *i -= mu);
             self.seen.clear();
         } else {
@@ -177,13 +231,25 @@ impl Iterator for CycleIter {
     }
 }
 
-pub fn count_steps(mut it: impl Iterator<Item = String>) -> u64 {
-    let instructions = Instructions::parse(it.next().unwrap().as_str());
-    it.next(); // skip a blank line
-    let (graph, initial_nodes) = read_graph(it);
-    let mut nodes: Vec<NodeFollower> = initial_nodes.iter().map(NodeFollower::new).collect();
-    println!("Directions len: {}", &instructions.0.len());
-    println!("Nodes len: {}", &graph.keys().len());
+pub fn count_steps(it: impl Iterator<Item = String>) -> Result<u64, ParseError> {
+    Ok(steps(&Network::read(it)?))
+}
+
+/// Steps until every ghost is on a node ending in `Z` at once.
+#[instrument(skip_all)]
+pub fn steps(network: &Network) -> u64 {
+    let Network {
+        instructions,
+        graph,
+        start_nodes,
+    } = network;
+    let mut nodes: Vec<NodeFollower> = start_nodes.iter().map(NodeFollower::new).collect();
+    info!(
+        directions = instructions.0.len(),
+        nodes = graph.len(),
+        ghosts = nodes.len(),
+        "read network"
+    );
     let mut steps = 0;
     for (instruction_num, turning) in instructions.iter() {
         if nodes.iter().all(|n| n.is_z()) {
@@ -207,13 +273,13 @@ pub fn count_steps(mut it: impl Iterator<Item = String>) -> u64 {
     }
 
     // brute force the congruences, just about doable!
-    println!("Seeking {} hits...", nodes.len() - 1);
+    debug!("seeking {} simultaneous hits", nodes.len() - 1);
     let mut hit_count_record = 0;
     for idx in nodes.pop().unwrap() {
         let hit_count = nodes.iter().filter(|n| n.z_test(idx)).count();
         if hit_count > hit_count_record {
             hit_count_record = hit_count;
-            println!("{hit_count} hits at {idx}");
+            debug!(hit_count, idx, "new best");
         }
         if nodes.iter().all(|n| n.z_test(idx)) {
             return idx as u64;
@@ -222,6 +288,25 @@ pub fn count_steps(mut it: impl Iterator<Item = String>) -> u64 {
     steps
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 8;
+    const PART: u8 = 2;
+
+    type Input = Network;
+    type Params = ();
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Network::read(input.lines().map(String::from))?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(steps(&input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -241,6 +326,18 @@ mod test {
             22Z = (22B, 22B)
             XXX = (XXX, XXX)
         "};
-        assert_eq!(count_steps(example.lines().map(String::from)), 6);
+        assert_eq!(count_steps(example.lines().map(String::from)), Ok(6));
+    }
+
+    #[test]
+    fn malformed_network() {
+        let error = |network: &str| {
+            let err = count_steps(network.lines().map(String::from)).unwrap_err();
+            (err.line(), err.column(), err.expected().to_string())
+        };
+        let undefined = "LR\n\n11A = (11B, XXX)\nXXX = (XXX, XXX)\n";
+        assert_eq!(error(undefined), (3, 8, "a node that's defined".into()));
+        let nowhere = "LR\n\nXXX = (XXX, XXX)\n";
+        assert_eq!(error(nowhere), (4, 1, "a node ending in A".into()));
     }
 }
@@ -1,9 +1,14 @@
 use std::io;
 
 use aoc2023_08_2::count_steps;
+use aoc2023_solution::logging;
+use color_eyre::Result;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = count_steps(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    logging::init(None)?;
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = count_steps(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
@@ -1,3 +1,7 @@
+use std::str::FromStr;
+
+use aoc2023_parse::{finish, parse_lines, ParseError};
+use aoc2023_solution::{Result, Solution};
 use nom::{character::complete::space1, multi::separated_list1, IResult};
 
 fn parse_seq(line: &str) -> IResult<&str, Vec<i64>> {
@@ -7,9 +11,11 @@ fn parse_seq(line: &str) -> IResult<&str, Vec<i64>> {
 #[derive(Debug)]
 pub struct Seq(Vec<i64>);
 
-impl From<&str> for Seq {
-    fn from(value: &str) -> Self {
-        Self(parse_seq(value).unwrap().1)
+impl FromStr for Seq {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(finish(s, parse_seq(s))?))
     }
 }
 
@@ -44,8 +50,28 @@ impl Seq {
     }
 }
 
-pub fn extrapolated_sum(it: impl Iterator<Item = String>) -> i64 {
-    it.map(|line| Seq::from(line.as_str()).extrapolate()).sum()
+pub fn extrapolated_sum(it: impl Iterator<Item = String>) -> Result<i64, ParseError> {
+    let seqs: Vec<Seq> = parse_lines(it)?;
+    Ok(seqs.iter().map(Seq::extrapolate).sum())
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 9;
+    const PART: u8 = 1;
+
+    type Input = Vec<Seq>;
+    type Params = ();
+    type Answer = i64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input.lines())?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(input.iter().map(Seq::extrapolate).sum())
+    }
 }
 
 #[cfg(test)]
@@ -60,6 +86,12 @@ mod test {
             1 3 6 10 15 21
             10 13 16 21 30 45
         "};
-        assert_eq!(extrapolated_sum(example.lines().map(String::from)), 114);
+        assert_eq!(extrapolated_sum(example.lines().map(String::from)), Ok(114));
+    }
+
+    #[test]
+    fn malformed_sequence() {
+        let err = "0 3 x 9".parse::<Seq>().unwrap_err();
+        assert_eq!(err.column(), 4);
     }
 }
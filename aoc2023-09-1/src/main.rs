@@ -1,9 +1,12 @@
 use std::io;
 
 use aoc2023_09_1::extrapolated_sum;
+use color_eyre::Result;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = extrapolated_sum(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = extrapolated_sum(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
@@ -1,10 +1,37 @@
 use aoc2023_09_1::Seq;
+use aoc2023_parse::{parse_lines, ParseError};
+use aoc2023_solution::{Result, Solution};
 
-pub fn extrapolated_sum(it: impl Iterator<Item = String>) -> i64 {
-    it.map(|line| Seq::from(line.as_str()).reversed().extrapolate())
+pub fn extrapolated_sum(it: impl Iterator<Item = String>) -> Result<i64, ParseError> {
+    Ok(backwards_sum(parse_lines(it)?))
+}
+
+/// The sum of the values before each sequence.
+pub fn backwards_sum(seqs: Vec<Seq>) -> i64 {
+    seqs.into_iter()
+        .map(|seq| seq.reversed().extrapolate())
         .sum()
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 9;
+    const PART: u8 = 2;
+
+    type Input = Vec<Seq>;
+    type Params = ();
+    type Answer = i64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input.lines())?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(backwards_sum(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -17,6 +44,6 @@ mod test {
             1 3 6 10 15 21
             10 13 16 21 30 45
         "};
-        assert_eq!(extrapolated_sum(example.lines().map(String::from)), 2);
+        assert_eq!(extrapolated_sum(example.lines().map(String::from)), Ok(2));
     }
 }
@@ -1,27 +1,42 @@
-struct Network {
+use aoc2023_grid::Grid;
+use aoc2023_solution::{Result, Solution};
+use color_eyre::eyre::{bail, eyre};
+use tracing::{trace, warn};
+
+/// The field of pipes, and where the animal started.
+pub struct Network {
     start: (usize, usize),
-    pipes: Vec<Vec<char>>,
+    pipes: Grid<char>,
 }
 
 impl Network {
-    pub fn from(it: impl Iterator<Item = String>) -> Self {
-        let mut pipes = Vec::new();
-        let mut start = None;
-        for (row, line) in it.enumerate() {
-            let chars: Vec<char> = line.chars().collect();
-            if let Some(col) = chars.iter().position(|c| *c == 'S') {
-                start = Some((row, col));
-            }
-            pipes.push(chars);
-        }
-        Self {
-            start: start.unwrap(),
+    /// Read the pipes, checking there's a start with a pipe leading off it.
+    pub fn read(it: impl Iterator<Item = String>) -> Result<Self> {
+        let pipes = Grid::from_lines(it)?;
+        let network = Self {
+            start: pipes
+                .position(|c| *c == 'S')
+                .ok_or(eyre!("No start tile S"))?,
             pipes,
+        };
+        if network.walkers().is_empty() {
+            bail!("No pipe leads off the start tile");
         }
+        Ok(network)
+    }
+
+    /// Steps along the loop to the point farthest from the start.
+    pub fn farthest(&self) -> u64 {
+        let mut walkers = self.walkers();
+        let walker = walkers
+            .first_mut()
+            .expect("read checks for a way off the start");
+        while !self.next(walker) {}
+        walker.age as u64 / 2
     }
 
     pub fn get(&self, row: usize, col: usize) -> Option<&char> {
-        self.pipes.get(row).map(|row| row.get(col)).flatten()
+        self.pipes.get((row, col))
     }
 
     fn walkers(&self) -> Vec<NetWalker> {
@@ -67,10 +82,10 @@ impl Network {
     }
 
     // progress a walker, return true if we have hit the start
-    pub fn next(&self, walker: &mut NetWalker) -> bool {
+    fn next(&self, walker: &mut NetWalker) -> bool {
         walker.age += 1;
         let pipe = self.get(walker.row, walker.col).unwrap();
-        dbg!(pipe);
+        trace!(%pipe, ?walker);
         match walker.came_from {
             Direction::Left => match pipe {
                 '-' => {
@@ -88,7 +103,7 @@ impl Network {
                     return true;
                 }
                 _ => {
-                    dbg!(walker);
+                    warn!(%pipe, ?walker, "walked off the loop");
                 }
             },
             Direction::Down => match pipe {
@@ -107,7 +122,7 @@ impl Network {
                     return true;
                 }
                 _ => {
-                    dbg!(walker);
+                    warn!(%pipe, ?walker, "walked off the loop");
                 }
             },
             Direction::Right => match pipe {
@@ -126,7 +141,7 @@ impl Network {
                     return true;
                 }
                 _ => {
-                    dbg!(walker);
+                    warn!(%pipe, ?walker, "walked off the loop");
                 }
             },
             Direction::Up => match pipe {
@@ -145,7 +160,7 @@ impl Network {
                     return true;
                 }
                 _ => {
-                    dbg!(walker);
+                    warn!(%pipe, ?walker, "walked off the loop");
                 }
             },
         }
@@ -169,12 +184,27 @@ struct NetWalker {
     pub age: usize,
 }
 
-pub fn farthest_point(it: impl Iterator<Item = String>) -> u64 {
-    let network = Network::from(it);
-    let mut walkers = network.walkers();
-    let mut walker = walkers.first_mut().unwrap();
-    while !network.next(&mut walker) {}
-    walker.age as u64 / 2
+pub fn farthest_point(it: impl Iterator<Item = String>) -> Result<u64> {
+    Ok(Network::read(it)?.farthest())
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 10;
+    const PART: u8 = 1;
+
+    type Input = Network;
+    type Params = ();
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Network::read(input.lines().map(String::from))
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(input.farthest())
+    }
 }
 
 #[cfg(test)]
@@ -191,6 +221,17 @@ mod test {
             |F--J
             LJ...
         "};
-        assert_eq!(farthest_point(example.lines().map(String::from)), 8);
+        assert_eq!(
+            farthest_point(example.lines().map(String::from)).unwrap(),
+            8
+        );
+    }
+
+    #[test]
+    fn no_way_round() {
+        let lines = |s: &str| s.lines().map(String::from).collect::<Vec<_>>();
+        assert!(Network::read(lines(".F7\n.LJ").into_iter()).is_err());
+        assert!(Network::read(lines("S..\n...").into_iter()).is_err());
+        assert!(Network::read(lines("S-7\n|.").into_iter()).is_err());
     }
 }
@@ -1,9 +1,14 @@
 use std::io;
 
 use aoc2023_10_1::farthest_point;
+use aoc2023_solution::logging;
+use color_eyre::Result;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = farthest_point(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    logging::init(None).unwrap();
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = farthest_point(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
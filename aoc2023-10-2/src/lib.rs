@@ -1,58 +1,70 @@
-use std::{
-    collections::HashSet,
-    fmt,
-    ops::{Add, RangeBounds},
-};
+use std::{collections::HashSet, fmt, ops::Add};
 
-struct Network {
+use aoc2023_grid::Grid;
+use aoc2023_solution::{Result, Solution};
+use color_eyre::eyre::{bail, eyre};
+use tracing::{debug, instrument, trace, warn};
+
+/// The field of pipes, and what's learnt walking the loop through it.
+pub struct Network {
     start: (usize, usize),
-    pipes: Vec<Vec<char>>,
-    interiousity: Vec<Vec<i32>>,
+    pipes: Grid<char>,
+    interiousity: Grid<i32>,
     path: HashSet<(usize, usize)>,
 }
 
 impl fmt::Display for Network {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut ret = String::default();
-        for (i, row) in self.interiousity.iter().enumerate() {
+        for (i, row) in self.interiousity.rows().enumerate() {
             for (j, col) in row.iter().enumerate() {
                 if self.path.contains(&(i, j)) || *col == 0 {
-                    ret.push_str("0");
+                    write!(f, "0")?;
                 } else {
-                    ret.push_str("1");
+                    write!(f, "1")?;
                 }
             }
-            ret.push('\n');
+            writeln!(f)?;
         }
-        write!(f, "{ret}")
+        Ok(())
     }
 }
 
 impl Network {
-    pub fn from(it: impl Iterator<Item = String>) -> Self {
-        let mut pipes = Vec::new();
-        let mut interiousity = Vec::new();
-        let mut start = None;
-        let mut path = HashSet::new();
-        for (row, line) in it.enumerate() {
-            let chars: Vec<char> = line.chars().collect();
-            if let Some(col) = chars.iter().position(|c| *c == 'S') {
-                start = Some((row, col));
-                path.insert((row, col));
-            }
-            interiousity.push(vec![0; chars.len()]);
-            pipes.push(chars);
-        }
-        Self {
-            interiousity,
+    /// Read the pipes, checking there's a start with a pipe leading off it.
+    pub fn read(it: impl Iterator<Item = String>) -> Result<Self> {
+        let pipes = Grid::from_lines(it)?;
+        let start = pipes
+            .position(|c| *c == 'S')
+            .ok_or(eyre!("No start tile S"))?;
+        let network = Self {
+            interiousity: pipes.map(|_| 0),
             pipes,
-            start: start.unwrap(),
-            path,
+            start,
+            path: HashSet::from([start]),
+        };
+        if network.walkers().is_empty() {
+            bail!("No pipe leads off the start tile");
         }
+        Ok(network)
+    }
+
+    /// Walk the loop, and count the tiles it goes round.
+    #[instrument(skip_all)]
+    pub fn enclosed(mut self) -> usize {
+        let mut walkers = self.walkers();
+        let walker = walkers
+            .first_mut()
+            .expect("read checks for a way off the start");
+        self.path.insert(walker.coords());
+        while !self.next(walker) {}
+        let enclosed = self.sum();
+        debug!(loop_length = walker.age, enclosed);
+        trace!("\n{self}");
+        enclosed
     }
 
     pub fn get(&self, row: usize, col: usize) -> Option<&char> {
-        self.pipes.get(row).map(|row| row.get(col)).flatten()
+        self.pipes.get((row, col))
     }
 
     fn walkers(&self) -> Vec<NetWalker> {
@@ -98,16 +110,20 @@ impl Network {
     }
 
     fn tip_row(&mut self, walker: &NetWalker, multiplier: i32) {
-        for i in 0..walker.col {
-            self.interiousity[walker.row][i] -= multiplier;
+        let (left, right) = self
+            .interiousity
+            .row_mut(walker.row)
+            .split_at_mut(walker.col);
+        for cell in left {
+            *cell -= multiplier;
         }
-        for i in walker.col..self.interiousity[0].len() {
-            self.interiousity[walker.row][i] += multiplier;
+        for cell in right {
+            *cell += multiplier;
         }
     }
 
     // progress a walker, return true if we have hit the start
-    pub fn next(&mut self, walker: &mut NetWalker) -> bool {
+    fn next(&mut self, walker: &mut NetWalker) -> bool {
         walker.age += 1;
         let pipe = self.get(walker.row, walker.col).unwrap();
         match walker.came_from {
@@ -116,25 +132,25 @@ impl Network {
                     walker.col += 1;
                 }
                 'J' => {
-                    self.tip_row(&walker, 1);
+                    self.tip_row(walker, 1);
                     walker.row -= 1;
                     walker.came_from = Direction::Down;
                 }
                 '7' => {
                     walker.row += 1;
-                    self.tip_row(&walker, -1);
+                    self.tip_row(walker, -1);
                     walker.came_from = Direction::Up;
                 }
                 'S' => {
                     return true;
                 }
                 _ => {
-                    dbg!(&walker);
+                    warn!(%pipe, ?walker, "walked off the loop");
                 }
             },
             Direction::Down => match pipe {
                 '|' => {
-                    self.tip_row(&walker, 1);
+                    self.tip_row(walker, 1);
                     walker.row -= 1;
                 }
                 'F' => {
@@ -149,7 +165,7 @@ impl Network {
                     return true;
                 }
                 _ => {
-                    dbg!(&walker);
+                    warn!(%pipe, ?walker, "walked off the loop");
                 }
             },
             Direction::Right => match pipe {
@@ -157,26 +173,26 @@ impl Network {
                     walker.col -= 1;
                 }
                 'L' => {
-                    self.tip_row(&walker, 1);
+                    self.tip_row(walker, 1);
                     walker.row -= 1;
                     walker.came_from = Direction::Down;
                 }
                 'F' => {
                     walker.row += 1;
-                    self.tip_row(&walker, -1);
+                    self.tip_row(walker, -1);
                     walker.came_from = Direction::Up;
                 }
                 'S' => {
                     return true;
                 }
                 _ => {
-                    dbg!(&walker);
+                    warn!(%pipe, ?walker, "walked off the loop");
                 }
             },
             Direction::Up => match pipe {
                 '|' => {
                     walker.row += 1;
-                    self.tip_row(&walker, -1);
+                    self.tip_row(walker, -1);
                 }
                 'J' => {
                     walker.col -= 1;
@@ -187,11 +203,11 @@ impl Network {
                     walker.came_from = Direction::Left;
                 }
                 'S' => {
-                    self.tip_row(&walker, -1);
+                    self.tip_row(walker, -1);
                     return true;
                 }
                 _ => {
-                    dbg!(&walker);
+                    warn!(%pipe, ?walker, "walked off the loop");
                 }
             },
         }
@@ -200,15 +216,10 @@ impl Network {
     }
 
     pub fn sum(&self) -> usize {
-        let mut count = 0;
-        for (i, row) in self.interiousity.iter().enumerate() {
-            for (j, col) in row.iter().enumerate() {
-                if !self.path.contains(&(i, j)) && *col != 0 {
-                    count += 1;
-                }
-            }
-        }
-        count
+        self.interiousity
+            .cells()
+            .filter(|(coords, col)| !self.path.contains(coords) && **col != 0)
+            .count()
     }
 }
 
@@ -245,15 +256,27 @@ impl NetWalker {
     }
 }
 
-pub fn num_enclosed_tiles(it: impl Iterator<Item = String>) -> usize {
-    let mut network = Network::from(it);
-    let mut walkers = network.walkers();
-    let mut walker = walkers.first_mut().unwrap();
-    while !network.next(&mut walker) {}
-    dbg!(walker.age);
-    dbg!(network.sum());
-    println!("{network}");
-    network.sum()
+pub fn num_enclosed_tiles(it: impl Iterator<Item = String>) -> Result<usize> {
+    Ok(Network::read(it)?.enclosed())
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 10;
+    const PART: u8 = 2;
+
+    type Input = Network;
+    type Params = ();
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Network::read(input.lines().map(String::from))
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(input.enclosed())
+    }
 }
 
 #[cfg(test)]
@@ -274,7 +297,10 @@ mod test {
             .L--J.L--J.
             ...........
         "};
-        assert_eq!(num_enclosed_tiles(example.lines().map(String::from)), 4);
+        assert_eq!(
+            num_enclosed_tiles(example.lines().map(String::from)).unwrap(),
+            4
+        );
     }
 
     #[test]
@@ -291,7 +317,10 @@ mod test {
             ....FJL-7.||.||||...
             ....L---J.LJ.LJLJ...
         "};
-        assert_eq!(num_enclosed_tiles(example.lines().map(String::from)), 8);
+        assert_eq!(
+            num_enclosed_tiles(example.lines().map(String::from)).unwrap(),
+            8
+        );
     }
 
     #[test]
@@ -308,6 +337,9 @@ mod test {
             L.L7LFJ|||||FJL7||LJ
             L7JLJL-JLJLJL--JLJ.L
         "};
-        assert_eq!(num_enclosed_tiles(example.lines().map(String::from)), 10);
+        assert_eq!(
+            num_enclosed_tiles(example.lines().map(String::from)).unwrap(),
+            10
+        );
     }
 }
@@ -1,49 +1,47 @@
-use std::collections::HashSet;
+use aoc2023_grid::{Grid, GridError};
+use aoc2023_solution::{Result, Solution};
 
+/// Where the galaxies are, and the rows and columns without any.
 #[derive(Default)]
-struct Universe {
-    size: (usize, usize),
+pub struct Universe {
+    empty_rows: Vec<usize>,
+    empty_cols: Vec<usize>,
     galaxies: Vec<(usize, usize)>,
 }
 
 impl Universe {
-    pub fn from(it: impl Iterator<Item = String>) -> Self {
-        let mut me = Self::default();
-        for (row, line) in it.enumerate() {
-            me.size.0 += 1;
-            me.size.1 = line.len();
-            for (col, char) in line.chars().enumerate() {
-                if char == '#' {
-                    me.galaxies.push((row, col));
-                }
-            }
-        }
-        me
+    pub fn read(it: impl Iterator<Item = String>) -> Result<Self, GridError> {
+        let image = Grid::from_lines(it)?;
+        let galaxies = image
+            .cells()
+            .filter(|(_, c)| **c == '#')
+            .map(|(coords, _)| coords)
+            .collect();
+        let empty_rows = image
+            .rows()
+            .enumerate()
+            .filter(|(_, row)| !row.contains(&'#'))
+            .map(|(i, _)| i)
+            .collect();
+        let empty_cols = image
+            .cols()
+            .enumerate()
+            .filter_map(|(j, mut col)| (!col.any(|c| *c == '#')).then_some(j))
+            .collect();
+        Ok(Self {
+            empty_rows,
+            empty_cols,
+            galaxies,
+        })
     }
 
     pub fn expand(&mut self) {
-        let rows = HashSet::<usize>::from_iter(self.galaxies.iter().map(|(row, _)| *row));
-        let cols = HashSet::<usize>::from_iter(self.galaxies.iter().map(|(_, col)| *col));
-        let mut row_expansions = Vec::new();
-        let mut col_expansions = Vec::new();
-        for i in 0..self.size.0 {
-            if rows.get(&i).is_none() {
-                row_expansions.push(i);
-            }
-        }
-        for j in 0..self.size.1 {
-            if cols.get(&j).is_none() {
-                col_expansions.push(j);
-            }
-        }
-        row_expansions.sort();
-        col_expansions.sort();
-        for to_insert in row_expansions.iter().rev() {
+        for to_insert in self.empty_rows.iter().rev() {
             for (row, _) in self.galaxies.iter_mut().filter(|(row, _)| row > to_insert) {
                 *row += 1;
             }
         }
-        for to_insert in col_expansions.iter().rev() {
+        for to_insert in self.empty_cols.iter().rev() {
             for (_, col) in self.galaxies.iter_mut().filter(|(_, col)| col > to_insert) {
                 *col += 1;
             }
@@ -61,10 +59,30 @@ impl Universe {
     }
 }
 
-pub fn sum_of_shortest_paths(it: impl Iterator<Item = String>) -> u64 {
-    let mut universe = Universe::from(it);
+pub fn sum_of_shortest_paths(it: impl Iterator<Item = String>) -> Result<u64, GridError> {
+    let mut universe = Universe::read(it)?;
     universe.expand();
-    universe.sum_shortest_paths()
+    Ok(universe.sum_shortest_paths())
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 11;
+    const PART: u8 = 1;
+
+    type Input = Universe;
+    type Params = ();
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Universe::read(input.lines().map(String::from))?)
+    }
+
+    fn solve(mut input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        input.expand();
+        Ok(input.sum_shortest_paths())
+    }
 }
 
 #[cfg(test)]
@@ -88,7 +106,20 @@ mod test {
         "};
         assert_eq!(
             sum_of_shortest_paths(example.lines().map(String::from)),
-            374
+            Ok(374)
+        );
+    }
+
+    #[test]
+    fn ragged_image() {
+        let lines = ["#..", ".#"].map(String::from);
+        assert_eq!(
+            sum_of_shortest_paths(lines.into_iter()),
+            Err(GridError::Ragged {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
         );
     }
 }
@@ -1,9 +1,12 @@
 use std::io;
 
 use aoc2023_11_1::sum_of_shortest_paths;
+use color_eyre::Result;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = sum_of_shortest_paths(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = sum_of_shortest_paths(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
@@ -1,6 +1,7 @@
-use itertools::Itertools;
-use std::ops::Add;
+use std::{ops::Add, str::FromStr};
 
+use aoc2023_parse::{finish, parse_lines, ParseError};
+use aoc2023_solution::{Result, Solution};
 use nom::{
     bytes::complete::{tag, take_while1},
     character::complete::{space1, u64},
@@ -9,30 +10,33 @@ use nom::{
     IResult,
 };
 
-struct ConditionRecord {
+/// A row of springs, some unknown, and the sizes of its damaged groups.
+pub struct ConditionRecord {
     known: String,
     damage_sizes: Vec<u64>,
 }
 
-impl From<String> for ConditionRecord {
-    fn from(line: String) -> Self {
-        let (known, damage_sizes) = parse_condition_record(line.as_str()).unwrap().1;
-        ConditionRecord {
+impl FromStr for ConditionRecord {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (known, damage_sizes) = finish(s, parse_condition_record(s))?;
+        Ok(ConditionRecord {
             known: known.to_string(),
             damage_sizes,
-        }
+        })
     }
 }
 
-fn vec_decrement(vec: &Vec<u64>) -> Vec<u64> {
-    if vec.len() == 0 {
+fn vec_decrement(vec: &[u64]) -> Vec<u64> {
+    if vec.is_empty() {
         return Vec::new();
     }
     let (first, rest) = vec.split_first().unwrap();
     if *first == 1 {
         rest.to_vec()
     } else {
-        let mut ret = vec.clone();
+        let mut ret = vec.to_vec();
         let first = ret.first_mut().unwrap();
         *first -= 1;
         ret
@@ -41,7 +45,7 @@ fn vec_decrement(vec: &Vec<u64>) -> Vec<u64> {
 
 impl ConditionRecord {
     fn possible_arrangements(&self) -> PossibleArrangements {
-        if self.known.len() < 1 {
+        if self.known.is_empty() {
             return PossibleArrangements::default();
         }
         // println!("{} {}", &self.known, self.damage_sizes.iter().join(","));
@@ -71,12 +75,10 @@ impl ConditionRecord {
         }
         let (firs2, rest) = self.known.split_at(2);
         match firs2 {
-            "#." => {
-                if self.damage_sizes.first() == Some(&1) {
-                    return Self::new(".".to_owned() + rest, vec_decrement(&self.damage_sizes))
-                        .possible_arrangements()
-                        .prepend("#");
-                }
+            "#." if self.damage_sizes.first() == Some(&1) => {
+                return Self::new(".".to_owned() + rest, vec_decrement(&self.damage_sizes))
+                    .possible_arrangements()
+                    .prepend("#");
             }
             "##" => {
                 if let Some(n) = self.damage_sizes.first() {
@@ -148,14 +150,37 @@ impl PossibleArrangements {
     }
 }
 
-pub fn sum_possible_arrangements(it: impl Iterator<Item = String>) -> u64 {
-    let records: Vec<ConditionRecord> = it.map(ConditionRecord::from).collect();
+pub fn sum_possible_arrangements(it: impl Iterator<Item = String>) -> Result<u64, ParseError> {
+    let records: Vec<ConditionRecord> = parse_lines(it)?;
+    Ok(total_arrangements(&records))
+}
+
+pub fn total_arrangements(records: &[ConditionRecord]) -> u64 {
     records
         .iter()
         .map(|r| r.possible_arrangements().len())
         .sum()
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 12;
+    const PART: u8 = 1;
+
+    type Input = Vec<ConditionRecord>;
+    type Params = ();
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input.lines())?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(total_arrangements(&input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -169,13 +194,13 @@ mod test {
 
     #[test]
     fn example_1() {
-        let record = ConditionRecord::from("???.### 1,1,3".to_owned());
+        let record = "???.### 1,1,3".parse::<ConditionRecord>().unwrap();
         assert_eq!(record.possible_arrangements(), vec!["#.#.###"].into());
     }
 
     #[test]
     fn example_2() {
-        let record = ConditionRecord::from(".??..??...?##. 1,1,3".to_owned());
+        let record = ".??..??...?##. 1,1,3".parse::<ConditionRecord>().unwrap();
         assert_eq!(
             record.possible_arrangements(),
             vec![
@@ -190,7 +215,9 @@ mod test {
 
     #[test]
     fn example_3() {
-        let record = ConditionRecord::from("?#?#?#?#?#?#?#? 1,3,1,6".to_owned());
+        let record = "?#?#?#?#?#?#?#? 1,3,1,6"
+            .parse::<ConditionRecord>()
+            .unwrap();
         assert_eq!(
             record.possible_arrangements(),
             vec![".#.###.#.######"].into()
@@ -198,12 +225,14 @@ mod test {
     }
     #[test]
     fn example_4() {
-        let record = ConditionRecord::from("????.#...#... 4,1,1".to_owned());
+        let record = "????.#...#... 4,1,1".parse::<ConditionRecord>().unwrap();
         assert_eq!(record.possible_arrangements(), vec!["####.#...#..."].into());
     }
     #[test]
     fn example_5() {
-        let record = ConditionRecord::from("????.######..#####. 1,6,5".to_owned());
+        let record = "????.######..#####. 1,6,5"
+            .parse::<ConditionRecord>()
+            .unwrap();
         assert_eq!(
             record.possible_arrangements(),
             vec![
@@ -217,7 +246,7 @@ mod test {
     }
     #[test]
     fn example_6() {
-        let record = ConditionRecord::from("?###???????? 3,2,1".to_owned());
+        let record = "?###???????? 3,2,1".parse::<ConditionRecord>().unwrap();
         assert_eq!(
             record.possible_arrangements(),
             vec![
@@ -248,7 +277,13 @@ mod test {
         "};
         assert_eq!(
             sum_possible_arrangements(example.lines().map(String::from)),
-            21
+            Ok(21)
         );
     }
+
+    #[test]
+    fn malformed_record() {
+        let err = "??.x 1,1".parse::<ConditionRecord>().err().unwrap();
+        assert_eq!(err.column(), 4);
+    }
 }
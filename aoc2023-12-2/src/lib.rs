@@ -1,11 +1,13 @@
+use aoc2023_parse::{finish, parse_lines, ParseError};
+use aoc2023_solution::{Result, Solution};
 use itertools::Itertools;
 use num_integer::binomial;
 use rayon::prelude::*;
 use std::{
-    iter::{repeat, zip},
     ops::{Add, AddAssign, Mul},
+    str::FromStr,
 };
-use tracing::{info, trace};
+use tracing::{debug, debug_span, instrument, trace};
 
 use nom::{
     bytes::complete::{tag, take_while1},
@@ -168,19 +170,22 @@ fn possible_arrangements(damage_sizes: &[usize], filter: &str) -> usize {
     unreachable!("How did we get here?");
 }
 
+/// A row of springs, some unknown, and the sizes of its damaged groups.
 #[derive(Debug)]
-struct ConditionRecord {
+pub struct ConditionRecord {
     known: String,
     damage_sizes: Vec<usize>,
 }
 
-impl From<String> for ConditionRecord {
-    fn from(line: String) -> Self {
-        let (known, damage_sizes) = parse_condition_record(line.as_str()).unwrap().1;
-        ConditionRecord {
+impl FromStr for ConditionRecord {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (known, damage_sizes) = finish(s, parse_condition_record(s))?;
+        Ok(ConditionRecord {
             known: known.to_string(),
             damage_sizes: damage_sizes.iter().map(|s| *s as usize).collect(),
-        }
+        })
     }
 }
 
@@ -192,7 +197,7 @@ impl Mul<usize> for ConditionRecord {
             known,
             damage_sizes,
         } = self;
-        let known = repeat(known).take(n).join("?").to_string();
+        let known = std::iter::repeat_n(known, n).join("?").to_string();
         let damage_sizes = damage_sizes
             .iter()
             .cloned()
@@ -214,6 +219,7 @@ fn parse_condition_record(input: &str) -> IResult<&str, (&str, Vec<u64>)> {
     )(input)
 }
 
+#[allow(dead_code)]
 #[derive(PartialEq, Eq, Debug, Default)]
 struct PossibleArrangements(Vec<String>);
 
@@ -243,20 +249,45 @@ impl AddAssign for PossibleArrangements {
     }
 }
 
-pub fn sum_possible_arrangements(it: impl Iterator<Item = String>) -> usize {
-    let records: Vec<ConditionRecord> = it.map(|line| ConditionRecord::from(line) * 5).collect();
+pub fn sum_possible_arrangements(it: impl Iterator<Item = String>) -> Result<usize, ParseError> {
+    Ok(unfolded_arrangements(parse_lines(it)?))
+}
+
+/// The arrangements of each record, unfolded five times over.
+#[instrument(skip_all)]
+pub fn unfolded_arrangements(records: Vec<ConditionRecord>) -> usize {
+    let records: Vec<ConditionRecord> = records.into_iter().map(|r| r * 5).collect();
     records
         .par_iter()
         .enumerate()
         .map(|(i, r)| {
-            info!("{i}: {:?}", &r);
+            let _record = debug_span!("record", i).entered();
             let ret = possible_arrangements(&r.damage_sizes, r.known.as_str());
-            println!("{:?} = {}", &r, &ret);
+            debug!(record = ?r, arrangements = ret);
             ret
         })
         .sum()
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 12;
+    const PART: u8 = 2;
+
+    type Input = Vec<ConditionRecord>;
+    type Params = ();
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input.lines())?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(unfolded_arrangements(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -268,7 +299,7 @@ mod test {
             ???.### 1,1,3
         "};
         assert_eq!(
-            sum_possible_arrangements(example.lines().map(String::from)),
+            sum_possible_arrangements(example.lines().map(String::from)).unwrap(),
             1
         );
     }
@@ -279,7 +310,7 @@ mod test {
             .??..??...?##. 1,1,3
         "};
         assert_eq!(
-            sum_possible_arrangements(example.lines().map(String::from)),
+            sum_possible_arrangements(example.lines().map(String::from)).unwrap(),
             16384
         );
     }
@@ -289,7 +320,7 @@ mod test {
             ?#?#?#?#?#?#?#? 1,3,1,6
         "};
         assert_eq!(
-            sum_possible_arrangements(example.lines().map(String::from)),
+            sum_possible_arrangements(example.lines().map(String::from)).unwrap(),
             1
         );
     }
@@ -299,7 +330,7 @@ mod test {
             ????.#...#... 4,1,1
         "};
         assert_eq!(
-            sum_possible_arrangements(example.lines().map(String::from)),
+            sum_possible_arrangements(example.lines().map(String::from)).unwrap(),
             16
         );
     }
@@ -309,7 +340,7 @@ mod test {
             ????.######..#####. 1,6,5
         "};
         assert_eq!(
-            sum_possible_arrangements(example.lines().map(String::from)),
+            sum_possible_arrangements(example.lines().map(String::from)).unwrap(),
             2500
         );
     }
@@ -319,7 +350,7 @@ mod test {
             ?###???????? 3,2,1
         "};
         assert_eq!(
-            sum_possible_arrangements(example.lines().map(String::from)),
+            sum_possible_arrangements(example.lines().map(String::from)).unwrap(),
             506250
         );
     }
@@ -330,7 +361,7 @@ mod test {
             ?.###??????#???.?.?? 11,1
         "};
         assert_eq!(
-            sum_possible_arrangements(example.lines().map(String::from)),
+            sum_possible_arrangements(example.lines().map(String::from)).unwrap(),
             // 4 // 1 round
             5184
         );
@@ -347,7 +378,7 @@ mod test {
             ?###???????? 3,2,1
         "};
         assert_eq!(
-            sum_possible_arrangements(example.lines().map(String::from)),
+            sum_possible_arrangements(example.lines().map(String::from)).unwrap(),
             525152
         );
     }
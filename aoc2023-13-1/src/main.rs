@@ -1,9 +1,12 @@
 use std::io;
 
 use aoc2023_13_1::reflection_summary;
+use color_eyre::Result;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = reflection_summary(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = reflection_summary(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
@@ -1,27 +1,21 @@
 use std::iter::zip;
 use std::mem;
 
+use aoc2023_grid::{Grid, GridError};
+use aoc2023_solution::{Result, Solution};
+
+/// A pattern of ash and rocks, and the same turned on its side.
 #[derive(Default)]
-struct Pattern {
-    pixels: Vec<Vec<char>>,
-    pixels_t: Vec<Vec<char>>,
+pub struct Pattern {
+    pixels: Grid<char>,
+    pixels_t: Grid<char>,
 }
 
-impl From<Vec<Vec<char>>> for Pattern {
-    fn from(value: Vec<Vec<char>>) -> Self {
-        let rows = value.len();
-        let cols = value.first().unwrap().len();
-        let mut transpose = Vec::new();
-        for col in 0..cols {
-            let mut new_col = Vec::new();
-            for row in 0..rows {
-                new_col.push(value[row][col]);
-            }
-            transpose.push(new_col);
-        }
+impl From<Grid<char>> for Pattern {
+    fn from(value: Grid<char>) -> Self {
         Pattern {
+            pixels_t: value.transpose(),
             pixels: value,
-            pixels_t: transpose,
         }
     }
 }
@@ -37,24 +31,24 @@ impl Default for Reflection {
     }
 }
 
-impl Into<usize> for Reflection {
-    fn into(self) -> usize {
-        match self {
+impl From<Reflection> for usize {
+    fn from(val: Reflection) -> Self {
+        match val {
             Reflection::Horizontal(h) => 100 * h,
             Reflection::Vertical(v) => v,
         }
     }
 }
 
-fn find_reflection(input: &Vec<Vec<char>>) -> Option<usize> {
-    for i in 1..input.len() {
+fn find_reflection(input: &Grid<char>) -> Option<usize> {
+    for i in 1..input.height() {
         let diff = (0..i)
             .map(|j| {
-                if i + j >= input.len() {
+                if i + j >= input.height() {
                     return 0;
                 }
-                let a = &input[i - j - 1];
-                let b = &input[i + j];
+                let a = input.row(i - j - 1);
+                let b = input.row(i + j);
                 zip(a.iter(), b.iter())
                     .map(|(x, y)| if x == y { 0 } else { 1 })
                     .sum()
@@ -70,10 +64,10 @@ fn find_reflection(input: &Vec<Vec<char>>) -> Option<usize> {
 impl Pattern {
     fn find_reflection(&self) -> Reflection {
         find_reflection(&self.pixels)
-            .map(|i| Reflection::Horizontal(i))
+            .map(Reflection::Horizontal)
             .unwrap_or_else(|| {
                 find_reflection(&self.pixels_t)
-                    .map(|i| Reflection::Vertical(i))
+                    .map(Reflection::Vertical)
                     .unwrap()
             })
     }
@@ -83,29 +77,52 @@ impl Pattern {
     }
 }
 
-fn parse_input(it: impl Iterator<Item = String>) -> Vec<Pattern> {
+/// The patterns, separated by blank lines.
+pub fn read_patterns(it: impl Iterator<Item = String>) -> Result<Vec<Pattern>, GridError> {
     let mut patterns = Vec::<Pattern>::new();
     let mut pixels = Vec::new();
     for line in it {
-        if line == "" {
+        if line.is_empty() {
             let pixels = mem::take(&mut pixels);
-            patterns.push(pixels.into());
+            patterns.push(Grid::from_lines(pixels)?.into());
             continue;
         }
-        pixels.push(line.chars().collect());
+        pixels.push(line);
     }
-    patterns.push(pixels.into());
-    patterns
+    patterns.push(Grid::from_lines(pixels)?.into());
+    Ok(patterns)
 }
 
-pub fn reflection_summary(it: impl Iterator<Item = String>) -> usize {
-    let patterns = parse_input(it);
+pub fn summary(patterns: &[Pattern]) -> usize {
     patterns
         .iter()
         .map(|pattern| pattern.reflection_score())
         .sum()
 }
 
+pub fn reflection_summary(it: impl Iterator<Item = String>) -> Result<usize, GridError> {
+    Ok(summary(&read_patterns(it)?))
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 13;
+    const PART: u8 = 2;
+
+    type Input = Vec<Pattern>;
+    type Params = ();
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(read_patterns(input.lines().map(String::from))?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(summary(&input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -130,6 +147,9 @@ mod test {
             ..##..###
             #....#..#
         "};
-        assert_eq!(reflection_summary(example.lines().map(String::from)), 400);
+        assert_eq!(
+            reflection_summary(example.lines().map(String::from)),
+            Ok(400)
+        );
     }
 }
@@ -1,5 +1,8 @@
 use std::{iter::zip, ops::AddAssign};
 
+use aoc2023_grid::Grid;
+use aoc2023_solution::{Result, Solution};
+
 #[derive(Debug, Default)]
 struct Count {
     val: usize,
@@ -40,10 +43,10 @@ impl LoadCalculator {
         }
     }
 
-    pub fn push_row(&mut self, line: &str) {
+    pub fn push_row(&mut self, row: impl IntoIterator<Item = char>) {
         self.num_rows += 1;
         let mut finished_counts = Vec::new();
-        for (ch, cnt) in zip(line.chars(), self.counts.iter_mut()) {
+        for (ch, cnt) in zip(row, self.counts.iter_mut()) {
             match ch {
                 '#' => {
                     finished_counts.push(std::mem::replace(cnt, Count::new(self.num_rows)));
@@ -64,22 +67,51 @@ impl LoadCalculator {
     }
 }
 
+/// The load on the north beams, a line at a time.
 pub fn total_load(it: impl Iterator<Item = String>) -> usize {
     let mut it = it.peekable();
-    let mut load_calculator = LoadCalculator::new(it.peek().unwrap().len());
+    let mut load_calculator = LoadCalculator::new(it.peek().map_or(0, String::len));
     for line in it {
-        load_calculator.push_row(line.as_str());
+        load_calculator.push_row(line.chars());
     }
     load_calculator.total()
 }
 
+/// The same, for a platform already read.
+pub fn platform_load(platform: &Grid<char>) -> usize {
+    let mut load_calculator = LoadCalculator::new(platform.width());
+    for row in platform.rows() {
+        load_calculator.push_row(row.iter().copied());
+    }
+    load_calculator.total()
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 14;
+    const PART: u8 = 1;
+
+    type Input = Grid<char>;
+    type Params = ();
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Grid::from_lines(input.lines())?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(platform_load(&input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use indoc::indoc;
 
     #[test]
-    fn full_example() {
+    fn full_example() -> Result<()> {
         let example = indoc! {"
             O....#....
             O.OO#....#
@@ -93,5 +125,9 @@ mod test {
             #OO..#....
         "};
         assert_eq!(total_load(example.lines().map(String::from)), 136);
+        assert_eq!(Solver::run(example)?, 136);
+        assert!(Solver::parse("O.#\n.O").is_err());
+        assert_eq!(total_load(std::iter::empty()), 0);
+        Ok(())
     }
 }
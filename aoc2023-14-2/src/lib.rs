@@ -1,14 +1,11 @@
 use std::{
     collections::{HashMap, VecDeque},
-    ops::Range,
+    fmt,
 };
 
-enum Direction {
-    North,
-    West,
-    South,
-    East,
-}
+use aoc2023_grid::{Grid, GridError};
+use aoc2023_solution::{Result, Solution};
+use tracing::{debug, instrument};
 
 #[derive(Debug, Hash, Eq, PartialEq, Clone)]
 enum Rock {
@@ -17,99 +14,64 @@ enum Rock {
     Cube,
 }
 
+impl fmt::Display for Rock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Rock::None => ".",
+                Rock::Rounded => "O",
+                Rock::Cube => "#",
+            }
+        )
+    }
+}
+
+/// The platform, with its rocks wherever they've rolled to.
 #[derive(Debug, Hash, Eq, PartialEq, Clone)]
-struct LoadCalculator {
-    cols: usize,
-    rocks: Vec<Rock>,
+pub struct LoadCalculator {
+    rocks: Grid<Rock>,
+}
+
+impl fmt::Display for LoadCalculator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.rocks)
+    }
 }
 
 impl LoadCalculator {
-    pub fn debug(&self) {
-        for (i, rock) in self.rocks.iter().enumerate() {
-            print!(
-                "{}",
-                match rock {
-                    Rock::None => ".",
-                    Rock::Rounded => "O",
-                    Rock::Cube => "#",
-                }
-            );
-            if (i + 1) % self.cols == 0 {
-                println!("");
-            }
-        }
-        println!("");
+    pub fn read(lines: impl Iterator<Item = String>) -> Result<Self, GridError> {
+        let rocks = Grid::parse_lines(lines, |c| match c {
+            'O' => Rock::Rounded,
+            '#' => Rock::Cube,
+            _ => Rock::None,
+        })?;
+        Ok(Self { rocks })
     }
 
     pub fn cycle(&mut self) {
-        self.tilt(Direction::North);
-        self.tilt(Direction::West);
-        self.tilt(Direction::South);
-        self.tilt(Direction::East);
+        // north, west, south, east: each rotation brings the next edge round to the north
+        for _ in 0..4 {
+            self.tilt_north();
+            self.rocks = self.rocks.rotate_cw();
+        }
     }
 
     pub fn load(&self) -> usize {
-        let rows = self.rocks.len() / self.cols;
-        (0..self.cols)
-            .map(|col| {
-                (col..col + rows * self.cols)
-                    .step_by(self.cols)
-                    .rev()
-                    .enumerate()
-                    .map(|(row, rock)| {
-                        if let Rock::Rounded = self.rocks[rock] {
-                            row + 1
-                        } else {
-                            0
-                        }
-                    })
-                    .sum::<usize>()
-            })
+        let rows = self.rocks.height();
+        self.rocks
+            .cells()
+            .filter(|(_, rock)| **rock == Rock::Rounded)
+            .map(|((row, _), _)| rows - row)
             .sum()
     }
 
-    pub fn new(cols: usize) -> Self {
-        let rocks = Vec::new();
-        Self { cols, rocks }
-    }
-
-    pub fn push_row(&mut self, line: &str) {
-        for c in line.chars() {
-            self.rocks.push(match c {
-                'O' => Rock::Rounded,
-                '#' => Rock::Cube,
-                _ => Rock::None,
-            });
-        }
-    }
-
-    fn tilt(&mut self, direction: Direction) {
-        let rows = self.rocks.len() / self.cols;
-        let (outer, inner): (
-            Range<usize>,
-            Box<dyn Fn(usize) -> Box<dyn Iterator<Item = usize>>>,
-        ) = match direction {
-            Direction::North => (
-                0..self.cols,
-                Box::new(|col| Box::new((col..col + rows * self.cols).step_by(self.cols))),
-            ),
-            Direction::West => (
-                0..rows,
-                Box::new(|row| Box::new(row * self.cols..(row + 1) * self.cols)),
-            ),
-            Direction::South => (
-                0..self.cols,
-                Box::new(|col| Box::new((col..col + rows * self.cols).step_by(self.cols).rev())),
-            ),
-            Direction::East => (
-                0..rows,
-                Box::new(|row| Box::new((row * self.cols..(row + 1) * self.cols).rev())),
-            ),
-        };
-
-        for o in outer {
+    fn tilt_north(&mut self) {
+        let cols = self.rocks.width();
+        for col in 0..cols {
             let mut queue = VecDeque::new();
-            for i in inner(o) {
+            for i in (col..self.rocks.len()).step_by(cols) {
                 match self.rocks[i] {
                     Rock::None => {
                         queue.push_back(i);
@@ -129,27 +91,25 @@ impl LoadCalculator {
     }
 }
 
-pub fn total_load(it: impl Iterator<Item = String>) -> usize {
-    let mut it = it.peekable();
-    let mut load_calculator = LoadCalculator::new(it.peek().unwrap().len());
-    for line in it {
-        load_calculator.push_row(line.as_str());
-    }
+pub fn total_load(it: impl Iterator<Item = String>) -> Result<usize, GridError> {
+    Ok(load_after_cycles(LoadCalculator::read(it)?))
+}
 
+/// The load on the north beams after a billion spin cycles.
+#[instrument(skip_all)]
+pub fn load_after_cycles(mut load_calculator: LoadCalculator) -> usize {
     let mut n = 1000000000;
 
     let mut first_seen_at = HashMap::<LoadCalculator, usize>::new();
     let mut i = 0;
     while i < n {
         load_calculator.cycle();
-        // load_calculator.debug();
         if let Some(first) = first_seen_at.get(&load_calculator) {
-            println!("cylce hit! {} -> {}", i, first);
             let modulus = i - first;
-            println!("{} == {} (mod {modulus})", n - 1 - i, (n - 1 - i) % modulus);
+            debug!(cycle = i, repeats = first, modulus, "cycle hit");
             n = (n - i - 1) % modulus;
             i = 0;
-            println!("Trimming down to {i}..{n}");
+            debug!("trimming down to {i}..{n}");
             break;
         } else {
             first_seen_at.insert(load_calculator.clone(), i);
@@ -163,11 +123,32 @@ pub fn total_load(it: impl Iterator<Item = String>) -> usize {
     load_calculator.load()
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 14;
+    const PART: u8 = 2;
+
+    type Input = LoadCalculator;
+    type Params = ();
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(LoadCalculator::read(input.lines().map(String::from))?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(load_after_cycles(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use indoc::indoc;
+    use tracing_test::traced_test;
 
+    #[traced_test]
     #[test]
     fn full_example() {
         let example = indoc! {"
@@ -182,6 +163,7 @@ mod test {
             #....###..
             #OO..#....
         "};
-        assert_eq!(total_load(example.lines().map(String::from)), 64);
+        assert_eq!(total_load(example.lines().map(String::from)), Ok(64));
+        assert!(logs_contain("cycle hit"));
     }
 }
@@ -1,9 +1,14 @@
 use std::io;
 
 use aoc2023_14_2::total_load;
+use aoc2023_solution::logging;
+use color_eyre::Result;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = total_load(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    logging::init(None).unwrap();
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = total_load(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
@@ -1,3 +1,6 @@
+use aoc2023_parse::ParseError;
+use aoc2023_solution::{Result, Solution};
+
 fn ascii_value(c: char) -> u32 {
     c as u32
 }
@@ -8,8 +11,36 @@ fn hash(input: &str) -> u32 {
         .fold(0, |acc, v| ((acc + ascii_value(v)) * 17) % 256)
 }
 
-pub fn sum_hashes(mut it: impl Iterator<Item = String>) -> u32 {
-    it.next().unwrap().as_str().split(",").map(hash).sum()
+/// The comma-separated steps of the initialization sequence.
+pub fn read_steps(mut it: impl Iterator<Item = String>) -> Result<Vec<String>, ParseError> {
+    let line = it
+        .next()
+        .ok_or_else(|| ParseError::at("", 0, "a line of steps"))?;
+    Ok(line.split(",").map(String::from).collect())
+}
+
+pub fn sum_hashes(it: impl Iterator<Item = String>) -> Result<u32, ParseError> {
+    Ok(read_steps(it)?.iter().map(|step| hash(step)).sum())
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 15;
+    const PART: u8 = 1;
+
+    /// The steps.
+    type Input = Vec<String>;
+    type Params = ();
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(read_steps(input.lines().map(String::from))?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(input.iter().map(|step| hash(step)).sum())
+    }
 }
 
 #[cfg(test)]
@@ -22,6 +53,7 @@ mod test {
         let example = indoc! {"
              rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
         "};
-        assert_eq!(sum_hashes(example.lines().map(String::from)), 1320);
+        assert_eq!(sum_hashes(example.lines().map(String::from)), Ok(1320));
+        assert!(sum_hashes(std::iter::empty()).is_err());
     }
 }
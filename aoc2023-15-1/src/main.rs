@@ -1,9 +1,12 @@
 use std::io;
 
 use aoc2023_15_1::sum_hashes;
+use color_eyre::Result;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = sum_hashes(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = sum_hashes(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
@@ -1,10 +1,14 @@
+use aoc2023_parse::{finish, ParseError};
+use aoc2023_solution::{Result, Solution};
 use arr_macro::arr;
 use indexmap::IndexMap;
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{alpha1, digit0},
-    sequence::tuple,
+    character::complete::{alpha1, u32},
+    combinator::{cut, map, value},
+    multi::separated_list1,
+    sequence::{pair, preceded},
     IResult,
 };
 
@@ -78,28 +82,42 @@ impl Boxes {
     }
 }
 
-enum Operation {
+/// A step of the initialization sequence.
+pub enum Operation {
     Remove(String),
     Insert(String, u32),
 }
 
-fn parse_entry(input: &str) -> IResult<&str, (&str, &str, &str)> {
-    tuple((alpha1, alt((tag("-"), tag("="))), digit0))(input)
+fn operation(input: &str) -> IResult<&str, Operation> {
+    let (input, (label, focal_length)) = pair(
+        alpha1,
+        alt((
+            value(None, tag("-")),
+            map(preceded(tag("="), cut(u32)), Some),
+        )),
+    )(input)?;
+    let operation = match focal_length {
+        None => Operation::Remove(label.into()),
+        Some(focal_length) => Operation::Insert(label.into(), focal_length),
+    };
+    Ok((input, operation))
 }
 
-impl From<&str> for Operation {
-    fn from(value: &str) -> Self {
-        let (label, op, focal_length) = parse_entry(value).unwrap().1;
-        match op {
-            "-" => Operation::Remove(label.into()),
-            "=" => Operation::Insert(label.into(), focal_length.parse().unwrap()),
-            _ => unreachable!("op must be \"-\" or \"=\""),
-        }
-    }
+/// The comma-separated steps of the initialization sequence.
+pub fn read_operations(mut it: impl Iterator<Item = String>) -> Result<Vec<Operation>, ParseError> {
+    let line = it
+        .next()
+        .ok_or_else(|| ParseError::at("", 0, "a line of steps"))?;
+    let operations = separated_list1(tag(","), operation)(&line);
+    finish(&line, operations)
+}
+
+pub fn focusing_power(it: impl Iterator<Item = String>) -> Result<u32, ParseError> {
+    Ok(arrange(read_operations(it)?))
 }
 
-pub fn focusing_power(mut it: impl Iterator<Item = String>) -> u32 {
-    let operations: Vec<Operation> = it.next().unwrap().split(",").map(Operation::from).collect();
+/// The focusing power once every lens is in place.
+pub fn arrange(operations: Vec<Operation>) -> u32 {
     let mut boxes = Boxes::default();
     for operation in operations {
         boxes.proceed(operation);
@@ -107,6 +125,25 @@ pub fn focusing_power(mut it: impl Iterator<Item = String>) -> u32 {
     boxes.focusing_power()
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 15;
+    const PART: u8 = 2;
+
+    type Input = Vec<Operation>;
+    type Params = ();
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(read_operations(input.lines().map(String::from))?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(arrange(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -117,6 +154,12 @@ mod test {
         let example = indoc! {"
             rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
         "};
-        assert_eq!(focusing_power(example.lines().map(String::from)), 145);
+        assert_eq!(focusing_power(example.lines().map(String::from)), Ok(145));
+    }
+
+    #[test]
+    fn malformed_step() {
+        let err = focusing_power(["rn=1,cm=".to_string()].into_iter()).unwrap_err();
+        assert_eq!(err.column(), 9);
     }
 }
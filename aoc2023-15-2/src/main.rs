@@ -1,9 +1,12 @@
 use std::io;
 
 use aoc2023_15_2::focusing_power;
+use color_eyre::Result;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = focusing_power(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = focusing_power(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
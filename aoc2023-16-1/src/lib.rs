@@ -1,3 +1,6 @@
+use aoc2023_grid::{Grid, GridError};
+use aoc2023_solution::{Result, Solution};
+
 enum Direction {
     East,
     North,
@@ -100,60 +103,52 @@ impl Ray {
     }
 }
 
-struct Contraption {
-    layout: Vec<Vec<char>>,
-    visited_from_east: Vec<Vec<bool>>,
-    visited_from_north: Vec<Vec<bool>>,
-    visited_from_west: Vec<Vec<bool>>,
-    visited_from_south: Vec<Vec<bool>>,
+/// The layout of mirrors and splitters, and where the beams have been.
+pub struct Contraption {
+    layout: Grid<char>,
+    visited_from_east: Grid<bool>,
+    visited_from_north: Grid<bool>,
+    visited_from_west: Grid<bool>,
+    visited_from_south: Grid<bool>,
     rays: Vec<Ray>,
 }
 
-impl<I: Iterator<Item = String>> From<I> for Contraption {
-    fn from(value: I) -> Self {
-        let mut layout = Vec::<Vec<char>>::new();
-        let mut visited = Vec::new();
-        for line in value {
-            layout.push(line.chars().into_iter().collect());
-            visited.push(vec![false; line.len()]);
-        }
+impl Contraption {
+    pub fn read(it: impl Iterator<Item = String>) -> Result<Self, GridError> {
+        let layout = Grid::from_lines(it)?;
+        let visited = layout.map(|_| false);
 
         let mut visited_from_west = visited.clone();
-        visited_from_west[0][0] = true;
+        visited_from_west[(0, 0)] = true;
 
         let mut init_ray = Ray::new(0, 0, Direction::East);
         let mut rays = Vec::new();
-        if let Some(ray_2) = init_ray.redirect(layout[0][0]) {
+        if let Some(ray_2) = init_ray.redirect(layout[(0, 0)]) {
             rays.push(ray_2);
         }
         rays.push(init_ray);
 
-        Self {
+        Ok(Self {
             layout,
             visited_from_east: visited.clone(),
             visited_from_north: visited.clone(),
             visited_from_west,
             visited_from_south: visited,
             rays,
-        }
+        })
     }
 }
 
 impl Contraption {
     pub fn num_energized_tiles(&self) -> usize {
-        let mut count = 0;
-        for i in 0..self.height() {
-            for j in 0..self.width() {
-                if self.visited_from_east[i][j]
-                    || self.visited_from_north[i][j]
-                    || self.visited_from_west[i][j]
-                    || self.visited_from_south[i][j]
-                {
-                    count += 1;
-                }
-            }
-        }
-        count
+        (0..self.layout.len())
+            .filter(|&i| {
+                self.visited_from_east[i]
+                    || self.visited_from_north[i]
+                    || self.visited_from_west[i]
+                    || self.visited_from_south[i]
+            })
+            .count()
     }
 
     pub fn trace(&mut self) -> bool {
@@ -168,12 +163,12 @@ impl Contraption {
                         continue;
                     }
                     ray.col += 1;
-                    if self.visited_from_west[ray.row][ray.col] {
+                    if self.visited_from_west[(ray.row, ray.col)] {
                         // Some ray has already played this out
                         ray.kill();
                         continue;
                     }
-                    self.visited_from_west[ray.row][ray.col] = true;
+                    self.visited_from_west[(ray.row, ray.col)] = true;
                 }
                 Direction::North => {
                     if ray.row == 0 {
@@ -181,12 +176,12 @@ impl Contraption {
                         continue;
                     }
                     ray.row -= 1;
-                    if self.visited_from_south[ray.row][ray.col] {
+                    if self.visited_from_south[(ray.row, ray.col)] {
                         // Some ray has already played this out
                         ray.kill();
                         continue;
                     }
-                    self.visited_from_south[ray.row][ray.col] = true;
+                    self.visited_from_south[(ray.row, ray.col)] = true;
                 }
                 Direction::West => {
                     if ray.col == 0 {
@@ -194,12 +189,12 @@ impl Contraption {
                         continue;
                     }
                     ray.col -= 1;
-                    if self.visited_from_east[ray.row][ray.col] {
+                    if self.visited_from_east[(ray.row, ray.col)] {
                         // Some ray has already played this out
                         ray.kill();
                         continue;
                     }
-                    self.visited_from_east[ray.row][ray.col] = true;
+                    self.visited_from_east[(ray.row, ray.col)] = true;
                 }
                 Direction::South => {
                     if ray.row == height - 1 {
@@ -207,16 +202,16 @@ impl Contraption {
                         continue;
                     }
                     ray.row += 1;
-                    if self.visited_from_north[ray.row][ray.col] {
+                    if self.visited_from_north[(ray.row, ray.col)] {
                         // Some ray has already played this out
                         ray.kill();
                         continue;
                     }
-                    self.visited_from_north[ray.row][ray.col] = true;
+                    self.visited_from_north[(ray.row, ray.col)] = true;
                 }
             }
 
-            if let Some(new_ray) = ray.redirect(self.layout[ray.row][ray.col]) {
+            if let Some(new_ray) = ray.redirect(self.layout[(ray.row, ray.col)]) {
                 new_rays.push(new_ray);
             }
         }
@@ -227,18 +222,43 @@ impl Contraption {
     }
 
     fn height(&self) -> usize {
-        self.layout.len()
+        self.layout.height()
     }
 
     fn width(&self) -> usize {
-        self.layout[0].len()
+        self.layout.width()
+    }
+}
+
+impl Contraption {
+    /// Trace the beam until it settles and count the tiles it energized.
+    pub fn energize(mut self) -> usize {
+        while self.trace() {}
+        self.num_energized_tiles()
     }
 }
 
-pub fn num_energized_tiles(it: impl Iterator<Item = String>) -> usize {
-    let mut contraption = Contraption::from(it);
-    while contraption.trace() {}
-    contraption.num_energized_tiles()
+pub fn num_energized_tiles(it: impl Iterator<Item = String>) -> Result<usize, GridError> {
+    Ok(Contraption::read(it)?.energize())
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 16;
+    const PART: u8 = 1;
+
+    type Input = Contraption;
+    type Params = ();
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Contraption::read(input.lines().map(String::from))?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(input.energize())
+    }
 }
 
 #[cfg(test)]
@@ -260,6 +280,22 @@ mod test {
             .|....-|.\
             ..//.|....
         "#};
-        assert_eq!(num_energized_tiles(example.lines().map(String::from)), 46);
+        assert_eq!(
+            num_energized_tiles(example.lines().map(String::from)),
+            Ok(46)
+        );
+    }
+
+    #[test]
+    fn ragged_layout() {
+        let lines = [".|.", "-."].map(String::from);
+        assert_eq!(
+            num_energized_tiles(lines.into_iter()),
+            Err(GridError::Ragged {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
     }
 }
@@ -1,9 +1,12 @@
 use std::io;
 
 use aoc2023_16_1::num_energized_tiles;
+use color_eyre::Result;
 
-fn main() {
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = num_energized_tiles(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = num_energized_tiles(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
@@ -1,3 +1,6 @@
+use aoc2023_grid::{Grid, GridError};
+use aoc2023_solution::{Result, Solution};
+
 enum Direction {
     East,
     North,
@@ -100,40 +103,37 @@ impl Ray {
     }
 }
 
-struct Contraption {
-    layout: Vec<Vec<char>>,
-    visited_from_east: Vec<Vec<bool>>,
-    visited_from_north: Vec<Vec<bool>>,
-    visited_from_west: Vec<Vec<bool>>,
-    visited_from_south: Vec<Vec<bool>>,
+/// The layout of mirrors and splitters, and where the beams have been.
+pub struct Contraption {
+    layout: Grid<char>,
+    visited_from_east: Grid<bool>,
+    visited_from_north: Grid<bool>,
+    visited_from_west: Grid<bool>,
+    visited_from_south: Grid<bool>,
     rays: Vec<Ray>,
 }
 
-impl<I: Iterator<Item = String>> From<I> for Contraption {
-    fn from(value: I) -> Self {
-        let mut layout = Vec::<Vec<char>>::new();
-        let mut visited = Vec::new();
-        for line in value {
-            layout.push(line.chars().into_iter().collect());
-            visited.push(vec![false; line.len()]);
-        }
+impl Contraption {
+    pub fn read(it: impl Iterator<Item = String>) -> Result<Self, GridError> {
+        let layout = Grid::from_lines(it)?;
+        let visited = layout.map(|_| false);
 
         let rays = Vec::new();
 
-        Self {
+        Ok(Self {
             layout,
             visited_from_east: visited.clone(),
             visited_from_north: visited.clone(),
             visited_from_west: visited.clone(),
             visited_from_south: visited,
             rays,
-        }
+        })
     }
 }
 
 impl Contraption {
-    pub fn init(&mut self, row: usize, col: usize, direction: Direction) -> &mut Self {
-        let visited = vec![vec![false; self.width()]; self.height()];
+    fn init(&mut self, row: usize, col: usize, direction: Direction) -> &mut Self {
+        let visited = Grid::new(self.width(), self.height(), false);
         self.visited_from_east = visited.clone();
         self.visited_from_north = visited.clone();
         self.visited_from_west = visited.clone();
@@ -141,22 +141,22 @@ impl Contraption {
 
         match direction {
             Direction::East => {
-                self.visited_from_west[row][col] = true;
+                self.visited_from_west[(row, col)] = true;
             }
             Direction::North => {
-                self.visited_from_south[row][col] = true;
+                self.visited_from_south[(row, col)] = true;
             }
             Direction::West => {
-                self.visited_from_east[row][col] = true;
+                self.visited_from_east[(row, col)] = true;
             }
             Direction::South => {
-                self.visited_from_north[row][col] = true;
+                self.visited_from_north[(row, col)] = true;
             }
         }
 
         let mut init_ray = Ray::new(row, col, direction);
         let mut rays = Vec::new();
-        if let Some(ray_2) = init_ray.redirect(self.layout[row][col]) {
+        if let Some(ray_2) = init_ray.redirect(self.layout[(row, col)]) {
             rays.push(ray_2);
         }
         rays.push(init_ray);
@@ -165,22 +165,17 @@ impl Contraption {
         self
     }
     pub fn num_energized_tiles(&self) -> usize {
-        let mut count = 0;
-        for i in 0..self.height() {
-            for j in 0..self.width() {
-                if self.visited_from_east[i][j]
-                    || self.visited_from_north[i][j]
-                    || self.visited_from_west[i][j]
-                    || self.visited_from_south[i][j]
-                {
-                    count += 1;
-                }
-            }
-        }
-        count
+        (0..self.layout.len())
+            .filter(|&i| {
+                self.visited_from_east[i]
+                    || self.visited_from_north[i]
+                    || self.visited_from_west[i]
+                    || self.visited_from_south[i]
+            })
+            .count()
     }
 
-    pub fn run(&mut self) -> &mut Self {
+    fn run(&mut self) -> &mut Self {
         while self.trace() {}
         self
     }
@@ -197,12 +192,12 @@ impl Contraption {
                         continue;
                     }
                     ray.col += 1;
-                    if self.visited_from_west[ray.row][ray.col] {
+                    if self.visited_from_west[(ray.row, ray.col)] {
                         // Some ray has already played this out
                         ray.kill();
                         continue;
                     }
-                    self.visited_from_west[ray.row][ray.col] = true;
+                    self.visited_from_west[(ray.row, ray.col)] = true;
                 }
                 Direction::North => {
                     if ray.row == 0 {
@@ -210,12 +205,12 @@ impl Contraption {
                         continue;
                     }
                     ray.row -= 1;
-                    if self.visited_from_south[ray.row][ray.col] {
+                    if self.visited_from_south[(ray.row, ray.col)] {
                         // Some ray has already played this out
                         ray.kill();
                         continue;
                     }
-                    self.visited_from_south[ray.row][ray.col] = true;
+                    self.visited_from_south[(ray.row, ray.col)] = true;
                 }
                 Direction::West => {
                     if ray.col == 0 {
@@ -223,12 +218,12 @@ impl Contraption {
                         continue;
                     }
                     ray.col -= 1;
-                    if self.visited_from_east[ray.row][ray.col] {
+                    if self.visited_from_east[(ray.row, ray.col)] {
                         // Some ray has already played this out
                         ray.kill();
                         continue;
                     }
-                    self.visited_from_east[ray.row][ray.col] = true;
+                    self.visited_from_east[(ray.row, ray.col)] = true;
                 }
                 Direction::South => {
                     if ray.row == height - 1 {
@@ -236,16 +231,16 @@ impl Contraption {
                         continue;
                     }
                     ray.row += 1;
-                    if self.visited_from_north[ray.row][ray.col] {
+                    if self.visited_from_north[(ray.row, ray.col)] {
                         // Some ray has already played this out
                         ray.kill();
                         continue;
                     }
-                    self.visited_from_north[ray.row][ray.col] = true;
+                    self.visited_from_north[(ray.row, ray.col)] = true;
                 }
             }
 
-            if let Some(new_ray) = ray.redirect(self.layout[ray.row][ray.col]) {
+            if let Some(new_ray) = ray.redirect(self.layout[(ray.row, ray.col)]) {
                 new_rays.push(new_ray);
             }
         }
@@ -256,28 +251,52 @@ impl Contraption {
     }
 
     fn height(&self) -> usize {
-        self.layout.len()
+        self.layout.height()
     }
 
     fn width(&self) -> usize {
-        self.layout[0].len()
+        self.layout.width()
     }
 }
 
-pub fn max_num_energized_tiles(it: impl Iterator<Item = String>) -> usize {
-    let mut contraption = Contraption::from(it);
+impl Contraption {
+    /// Fire the beam in from every edge tile and count the most tiles it energizes.
+    pub fn max_energized(mut self) -> usize {
+        let width = self.width();
+        let height = self.height();
 
-    let width = contraption.width();
-    let height = contraption.height();
+        (0..width)
+            .map(|j| (0, j, Direction::South))
+            .chain((0..width).map(|j| (height - 1, j, Direction::North)))
+            .chain((0..height).map(|i| (i, 0, Direction::East)))
+            .chain((0..height).map(|i| (i, width - 1, Direction::West)))
+            .map(|(i, j, d)| self.init(i, j, d).run().num_energized_tiles())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+pub fn max_num_energized_tiles(it: impl Iterator<Item = String>) -> Result<usize, GridError> {
+    Ok(Contraption::read(it)?.max_energized())
+}
+
+pub struct Solver;
 
-    (0..width)
-        .map(|j| (0, j, Direction::South))
-        .chain((0..width).map(|j| (height - 1, j, Direction::North)))
-        .chain((0..height).map(|i| (i, 0, Direction::East)))
-        .chain((0..height).map(|i| (i, width - 1, Direction::West)))
-        .map(|(i, j, d)| contraption.init(i, j, d).run().num_energized_tiles())
-        .max()
-        .unwrap()
+impl Solution for Solver {
+    const DAY: u8 = 16;
+    const PART: u8 = 2;
+
+    type Input = Contraption;
+    type Params = ();
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Contraption::read(input.lines().map(String::from))?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(input.max_energized())
+    }
 }
 
 #[cfg(test)]
@@ -301,7 +320,7 @@ mod test {
         "#};
         assert_eq!(
             max_num_energized_tiles(example.lines().map(String::from)),
-            51
+            Ok(51)
         );
     }
 }
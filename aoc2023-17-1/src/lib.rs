@@ -1,3 +1,6 @@
+use aoc2023_grid::{Direction, Grid};
+use aoc2023_solution::{Result, Solution};
+use color_eyre::eyre::bail;
 use std::{
     collections::{BinaryHeap, HashMap},
     ops::{Add, AddAssign},
@@ -56,7 +59,7 @@ impl History {
                 3 => vec![History(format!("{ch}{ch}")), History(format!("{ch}"))],
                 _ => Vec::new(),
             })
-            .unwrap_or_else(Vec::new)
+            .unwrap_or_default()
     }
 
     pub fn subsies(&self) -> Vec<History> {
@@ -210,27 +213,27 @@ impl PartialOrd for State {
     }
 }
 
-struct Map {
-    blocks: Vec<usize>,
-    width: usize,
+/// The city blocks, by how much heat is lost entering each one.
+pub struct Map {
+    blocks: Grid<usize>,
 }
 
-impl<I: Iterator<Item = String>> From<I> for Map {
-    fn from(lines: I) -> Self {
-        let mut blocks = Vec::new();
-        let lines = lines.peekable();
-        let mut width = 0;
-        for line in lines {
-            width = line.len();
-            blocks.append(
-                &mut line
-                    .chars()
-                    .map(|digit| digit.to_string().parse::<usize>().unwrap())
-                    .collect(),
+impl Map {
+    /// Read the heat loss digits, checking there's at least one block.
+    pub fn read(it: impl Iterator<Item = String>) -> Result<Self> {
+        let digits = Grid::parse_lines(it, |c| c.to_digit(10))?;
+        if let Some((row, col)) = digits.position(Option::is_none) {
+            bail!(
+                "Block at line {}, column {} isn't a digit",
+                row + 1,
+                col + 1
             );
         }
-
-        Self { blocks, width }
+        if digits.is_empty() {
+            bail!("No city blocks");
+        }
+        let blocks = digits.map(|digit| digit.expect("checked above") as usize);
+        Ok(Self { blocks })
     }
 }
 
@@ -239,7 +242,7 @@ impl Map {
         // let's be cowboys and assume end > start
         let diff = end - start;
         // manhattan distance
-        diff / self.width + diff % self.width
+        diff / self.blocks.width() + diff % self.blocks.width()
     }
 }
 
@@ -296,26 +299,43 @@ impl Map {
     }
 
     fn neighbours(&self, node: usize) -> Vec<(char, usize)> {
-        let mut neighbours = Vec::new();
-        if node >= self.width {
-            neighbours.push(('^', node - self.width));
-        }
-        if node % self.width > 0 {
-            neighbours.push(('<', node - 1));
-        }
-        if node < self.blocks.len() - self.width {
-            neighbours.push(('v', node + self.width));
-        }
-        if node % self.width < self.width - 1 {
-            neighbours.push(('>', node + 1));
-        }
-        neighbours
+        let coords = self.blocks.coords_of(node).unwrap();
+        self.blocks
+            .neighbours4(coords)
+            .map(|(direction, neighbour)| {
+                let ch = match direction {
+                    Direction::North => '^',
+                    Direction::East => '>',
+                    Direction::South => 'v',
+                    Direction::West => '<',
+                };
+                (ch, self.blocks.index_of(neighbour).unwrap())
+            })
+            .collect()
     }
 }
 
-pub fn minimum_heat_loss(it: impl Iterator<Item = String>) -> usize {
-    let map = Map::from(it);
-    map.minimum_heat_loss()
+pub fn minimum_heat_loss(it: impl Iterator<Item = String>) -> Result<usize> {
+    Ok(Map::read(it)?.minimum_heat_loss())
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 17;
+    const PART: u8 = 1;
+
+    type Input = Map;
+    type Params = ();
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Map::read(input.lines().map(String::from))
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(input.minimum_heat_loss())
+    }
 }
 
 #[cfg(test)]
@@ -334,7 +354,7 @@ mod test {
     fn empty_cost_ord() {
         let empty = Cost::default();
         let non_emtpy = Cost {
-            pathwise: HashMap::from([(History::default(), 1 as usize)]),
+            pathwise: HashMap::from([(History::default(), 1_usize)]),
             heuristic: 0,
         };
         // good because we are using max heap and None represents infinite cost
@@ -344,11 +364,11 @@ mod test {
     #[test]
     fn cost_ord() {
         let a = Cost {
-            pathwise: HashMap::from([(History::default(), 1 as usize), (History::default(), 2)]),
+            pathwise: HashMap::from([(History::default(), 1_usize), (History::default(), 2)]),
             heuristic: 0,
         };
         let b = Cost {
-            pathwise: HashMap::from([(History::default(), 2 as usize), (History::default(), 3)]),
+            pathwise: HashMap::from([(History::default(), 2_usize), (History::default(), 3)]),
             heuristic: 0,
         };
         // good because we are using max heap so small is preferred
@@ -379,6 +399,16 @@ mod test {
             2546548887735
             4322674655533
         "};
-        assert_eq!(minimum_heat_loss(example.lines().map(String::from)), 102);
+        assert_eq!(
+            minimum_heat_loss(example.lines().map(String::from)).unwrap(),
+            102
+        );
+    }
+
+    #[test]
+    fn not_a_digit() {
+        let lines = ["12", "3x"].map(String::from);
+        let err = minimum_heat_loss(lines.into_iter()).unwrap_err();
+        assert_eq!(err.to_string(), "Block at line 2, column 2 isn't a digit");
     }
 }
@@ -1,3 +1,6 @@
+use aoc2023_grid::{Direction, Grid};
+use aoc2023_solution::{Result, Solution};
+use color_eyre::eyre::bail;
 use std::{
     collections::{BinaryHeap, HashMap},
     ops::{Add, AddAssign},
@@ -11,25 +14,17 @@ impl History {
     fn add(&self, rhs: char) -> Option<Self> {
         let last_char = self.0.chars().last().unwrap();
         match last_char {
-            '>' => {
-                if rhs == '<' {
-                    return None;
-                }
+            '>' if rhs == '<' => {
+                return None;
             }
-            '^' => {
-                if rhs == 'v' {
-                    return None;
-                }
+            '^' if rhs == 'v' => {
+                return None;
             }
-            '<' => {
-                if rhs == '>' {
-                    return None;
-                }
+            '<' if rhs == '>' => {
+                return None;
             }
-            'v' => {
-                if rhs == '^' {
-                    return None;
-                }
+            'v' if rhs == '^' => {
+                return None;
             }
             _ => {}
         }
@@ -187,27 +182,27 @@ impl PartialOrd for State {
     }
 }
 
-struct Map {
-    blocks: Vec<usize>,
-    width: usize,
+/// The city blocks, by how much heat is lost entering each one.
+pub struct Map {
+    blocks: Grid<usize>,
 }
 
-impl<I: Iterator<Item = String>> From<I> for Map {
-    fn from(lines: I) -> Self {
-        let mut blocks = Vec::new();
-        let lines = lines.peekable();
-        let mut width = 0;
-        for line in lines {
-            width = line.len();
-            blocks.append(
-                &mut line
-                    .chars()
-                    .map(|digit| digit.to_string().parse::<usize>().unwrap())
-                    .collect(),
+impl Map {
+    /// Read the heat loss digits, checking there's at least one block.
+    pub fn read(it: impl Iterator<Item = String>) -> Result<Self> {
+        let digits = Grid::parse_lines(it, |c| c.to_digit(10))?;
+        if let Some((row, col)) = digits.position(Option::is_none) {
+            bail!(
+                "Block at line {}, column {} isn't a digit",
+                row + 1,
+                col + 1
             );
         }
-
-        Self { blocks, width }
+        if digits.is_empty() {
+            bail!("No city blocks");
+        }
+        let blocks = digits.map(|digit| digit.expect("checked above") as usize);
+        Ok(Self { blocks })
     }
 }
 
@@ -216,7 +211,7 @@ impl Map {
         // let's be cowboys and assume end > start
         let diff = end - start;
         // manhattan distance
-        diff / self.width + diff % self.width
+        diff / self.blocks.width() + diff % self.blocks.width()
     }
 }
 
@@ -275,26 +270,43 @@ impl Map {
     }
 
     fn neighbours(&self, node: usize) -> Vec<(char, usize)> {
-        let mut neighbours = Vec::new();
-        if node >= self.width {
-            neighbours.push(('^', node - self.width));
-        }
-        if node % self.width > 0 {
-            neighbours.push(('<', node - 1));
-        }
-        if node < self.blocks.len() - self.width {
-            neighbours.push(('v', node + self.width));
-        }
-        if node % self.width < self.width - 1 {
-            neighbours.push(('>', node + 1));
-        }
-        neighbours
+        let coords = self.blocks.coords_of(node).unwrap();
+        self.blocks
+            .neighbours4(coords)
+            .map(|(direction, neighbour)| {
+                let ch = match direction {
+                    Direction::North => '^',
+                    Direction::East => '>',
+                    Direction::South => 'v',
+                    Direction::West => '<',
+                };
+                (ch, self.blocks.index_of(neighbour).unwrap())
+            })
+            .collect()
     }
 }
 
-pub fn minimum_heat_loss(it: impl Iterator<Item = String>) -> usize {
-    let map = Map::from(it);
-    map.minimum_heat_loss()
+pub fn minimum_heat_loss(it: impl Iterator<Item = String>) -> Result<usize> {
+    Ok(Map::read(it)?.minimum_heat_loss())
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 17;
+    const PART: u8 = 2;
+
+    type Input = Map;
+    type Params = ();
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Map::read(input.lines().map(String::from))
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(input.minimum_heat_loss())
+    }
 }
 
 #[cfg(test)]
@@ -321,7 +333,10 @@ mod test {
             2546548887735
             4322674655533
         "};
-        assert_eq!(minimum_heat_loss(example.lines().map(String::from)), 94);
+        assert_eq!(
+            minimum_heat_loss(example.lines().map(String::from)).unwrap(),
+            94
+        );
     }
 
     #[traced_test]
@@ -334,6 +349,9 @@ mod test {
             999999999991
             999999999991
         "};
-        assert_eq!(minimum_heat_loss(example.lines().map(String::from)), 71);
+        assert_eq!(
+            minimum_heat_loss(example.lines().map(String::from)).unwrap(),
+            71
+        );
     }
 }
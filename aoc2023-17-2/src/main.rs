@@ -1,10 +1,14 @@
 use std::io;
 
 use aoc2023_17_2::minimum_heat_loss;
+use aoc2023_solution::logging;
+use color_eyre::Result;
 
-fn main() {
-    tracing_subscriber::fmt::init();
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = minimum_heat_loss(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    logging::init(None).unwrap();
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = minimum_heat_loss(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
@@ -1,16 +1,21 @@
+use aoc2023_parse::{finish, parse_lines, ParseError};
+use aoc2023_solution::{Result, Solution};
 use core::fmt;
 use std::{
     collections::{HashMap, HashSet},
     ops::{RangeBounds, RangeFrom, RangeInclusive, RangeTo},
+    str::FromStr,
 };
 
 use nom::{
+    branch::alt,
     bytes::complete::tag,
-    character::complete::{alpha1, digit1},
-    character::complete::{hex_digit1, multispace1},
+    character::complete::{char, hex_digit1, i64, space1},
+    combinator::{map_res, value},
     sequence::{delimited, separated_pair},
     IResult,
 };
+use tracing::{instrument, trace};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum Direction {
@@ -18,18 +23,19 @@ enum Direction {
     V,
 }
 
-impl From<&str> for Direction {
-    fn from(value: &str) -> Self {
-        match value {
-            "R" => Direction::H,
-            "U" => Direction::V,
-            "L" => Direction::H,
-            "D" => Direction::V,
-            _ => unreachable!(),
-        }
+impl Direction {
+    /// Parse a heading, returning the axis it lies on and the sign of travel.
+    fn parse(input: &str) -> IResult<&str, (Self, i64)> {
+        alt((
+            value((Direction::H, 1), char('R')),
+            value((Direction::V, -1), char('U')),
+            value((Direction::H, -1), char('L')),
+            value((Direction::V, 1), char('D')),
+        ))(input)
     }
 }
 
+#[allow(dead_code)]
 #[derive(Debug)]
 struct Colour {
     r: u8,
@@ -37,45 +43,49 @@ struct Colour {
     b: u8,
 }
 
-impl From<&str> for Colour {
-    fn from(value: &str) -> Self {
-        let r = u8::from_str_radix(&value[..2], 16).unwrap();
-        let g = u8::from_str_radix(&value[2..4], 16).unwrap();
-        let b = u8::from_str_radix(&value[4..6], 16).unwrap();
-        Self { r, g, b }
+impl Colour {
+    fn parse(input: &str) -> IResult<&str, Self> {
+        map_res(hex_digit1, |hex: &str| {
+            if hex.len() != 6 {
+                return Err(hex.len());
+            }
+            let rgb = u32::from_str_radix(hex, 16).unwrap();
+            let [_, r, g, b] = rgb.to_be_bytes();
+            Ok(Self { r, g, b })
+        })(input)
     }
 }
 
+/// One dig instruction: which way, how far, and the colour to paint the trench.
 #[derive(Debug)]
-struct Instruction {
+pub struct Instruction {
     direction: Direction,
     count: i64,
+    #[allow(dead_code)]
     colour: Colour,
 }
 
-fn parse_instruciton(input: &str) -> IResult<&str, ((&str, &str), &str)> {
-    separated_pair(
-        separated_pair(alpha1, multispace1, digit1),
-        multispace1,
-        delimited(tag("(#"), hex_digit1, tag(")")),
-    )(input)
+fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
+    let (rest, (((direction, sign), count), colour)) = separated_pair(
+        separated_pair(Direction::parse, space1, i64),
+        space1,
+        delimited(tag("(#"), Colour::parse, char(')')),
+    )(input)?;
+    Ok((
+        rest,
+        Instruction {
+            direction,
+            count: sign * count,
+            colour,
+        },
+    ))
 }
 
-impl From<String> for Instruction {
-    fn from(value: String) -> Self {
-        let ((direction, count), colour) = parse_instruciton(value.as_str()).unwrap().1;
-        let mut count = count.parse().unwrap();
-        match direction {
-            "L" | "U" => {
-                count *= -1;
-            }
-            _ => {}
-        }
-        Self {
-            direction: Direction::from(direction),
-            count,
-            colour: Colour::from(colour),
-        }
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        finish(s, parse_instruction(s))
     }
 }
 
@@ -151,9 +161,9 @@ impl fmt::Display for Rover {
         for y in self.rows() {
             for x in self.cols() {
                 if self.path.contains(&(x, y).into()) {
-                    ret.push_str("#");
+                    ret.push('#');
                 } else {
-                    ret.push_str(".");
+                    ret.push('.');
                 }
             }
             ret.push_str(&format!("{y}"));
@@ -165,9 +175,9 @@ impl fmt::Display for Rover {
         for y in self.rows() {
             for x in self.cols() {
                 if self.is_exterior((x, y).into()) {
-                    ret.push_str(".");
+                    ret.push('.');
                 } else {
-                    ret.push_str("#");
+                    ret.push('#');
                 }
             }
             ret.push('\n');
@@ -189,6 +199,9 @@ impl Default for Rover {
 
 impl Rover {
     fn area(&self) -> usize {
+        if self.path.is_empty() {
+            return 0;
+        }
         self.rows()
             .map(|y| {
                 self.cols()
@@ -229,28 +242,16 @@ impl Rover {
                 for _ in 0..instruction.count.abs() {
                     self.path.insert(self.location);
                     if instruction.count > 0 {
-                        let plus = self
-                            .exterior_plus
-                            .entry(self.location.y)
-                            .or_insert_with(Vec::default);
-                        let minus = self
-                            .exterior_minus
-                            .entry(self.location.y)
-                            .or_insert_with(Vec::default);
+                        let plus = self.exterior_plus.entry(self.location.y).or_default();
+                        let minus = self.exterior_minus.entry(self.location.y).or_default();
                         plus.push((self.location.x..).into());
                         minus.push((..self.location.x).into());
                         self.location.y += increment;
                     }
                     if instruction.count < 0 {
                         self.location.y += increment;
-                        let plus = self
-                            .exterior_plus
-                            .entry(self.location.y)
-                            .or_insert_with(Vec::default);
-                        let minus = self
-                            .exterior_minus
-                            .entry(self.location.y)
-                            .or_insert_with(Vec::default);
+                        let plus = self.exterior_plus.entry(self.location.y).or_default();
+                        let minus = self.exterior_minus.entry(self.location.y).or_default();
                         plus.push((..self.location.x).into());
                         minus.push((self.location.x..).into());
                     }
@@ -272,15 +273,40 @@ impl Rover {
     }
 }
 
-pub fn cubic_meters_of_lava(it: impl Iterator<Item = String>) -> usize {
+/// Dig the trench and count the cubic metres of the lagoon it encloses.
+#[instrument(skip_all)]
+pub fn lagoon_volume(instructions: Vec<Instruction>) -> usize {
     let mut rover = Rover::default();
-    for instruction in it.map(Instruction::from) {
+    for instruction in instructions {
         rover.rove(instruction);
     }
-    println!("{rover}");
+    trace!("\n{rover}");
     rover.area()
 }
 
+pub fn cubic_meters_of_lava(it: impl Iterator<Item = String>) -> Result<usize, ParseError> {
+    Ok(lagoon_volume(parse_lines(it)?))
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 18;
+    const PART: u8 = 1;
+
+    type Input = Vec<Instruction>;
+    type Params = ();
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input.lines())?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(lagoon_volume(input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -304,6 +330,16 @@ mod test {
             L 2 (#015232)
             U 2 (#7a21e3)
         "};
-        assert_eq!(cubic_meters_of_lava(example.lines().map(String::from)), 62);
+        assert_eq!(
+            cubic_meters_of_lava(example.lines().map(String::from)),
+            Ok(62)
+        );
+    }
+
+    #[test]
+    fn malformed_instruction() {
+        let lines = ["R 6 (#70c710)", "D 5 (#0dc57)"].map(String::from);
+        let err = cubic_meters_of_lava(lines.into_iter()).unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 7));
     }
 }
@@ -1,25 +1,31 @@
 use std::{
     cmp::Ordering,
     ops::{AddAssign, Mul},
+    str::FromStr,
 };
 
+use aoc2023_parse::{finish, parse_lines, ParseError};
+use aoc2023_solution::{Result, Solution};
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
-    character::complete::{alpha1, digit1, hex_digit1, multispace1},
-    sequence::{delimited, separated_pair},
+    character::complete::{char, digit1, hex_digit1, one_of, space1},
+    combinator::map_opt,
+    sequence::{delimited, preceded, tuple},
     IResult,
 };
 
+/// Which way the trench turns from one instruction to the next.
 #[derive(Clone, PartialEq)]
-enum Rotation {
+pub enum Rotation {
     Clockwise,
     CounterClockwise,
     Colinear,
 }
 
+/// One dig instruction, as decoded from the colour code.
 #[derive(Debug, Clone, Copy)]
-enum Instruction {
+pub enum Instruction {
     R(usize),
     U(usize),
     L(usize),
@@ -68,28 +74,30 @@ impl Mul for &Instruction {
     }
 }
 
-fn parse_instruction(input: &str) -> IResult<&str, ((&str, &str), &str)> {
-    separated_pair(
-        separated_pair(alpha1, multispace1, digit1),
-        multispace1,
-        delimited(tag("(#"), hex_digit1, tag(")")),
-    )(input)
+fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
+    fn hex(input: &str) -> IResult<&str, Instruction> {
+        map_opt(hex_digit1, |hex: &str| {
+            let (length, direction) = hex.split_at(hex.len() - 1);
+            let length = usize::from_str_radix(length, 16).ok()?;
+            match direction {
+                "0" => Some(Instruction::R(length)),
+                "1" => Some(Instruction::D(length)),
+                "2" => Some(Instruction::L(length)),
+                "3" => Some(Instruction::U(length)),
+                _ => None,
+            }
+        })(input)
+    }
+    // the part 1 heading and count are decoys, the real ones are in the hex
+    let decoy = tuple((one_of("RULD"), space1, digit1, space1));
+    preceded(decoy, delimited(tag("(#"), hex, char(')')))(input)
 }
 
-impl From<&str> for Instruction {
-    fn from(input: &str) -> Self {
-        let ((_, _), hex) = parse_instruction(input).unwrap().1;
-        let (length, direction) = hex.split_at(hex.len() - 1);
-        let length = usize::from_str_radix(length, 16).unwrap();
-        match direction {
-            "0" => Self::R(length),
-            "1" => Self::D(length),
-            "2" => Self::L(length),
-            "3" => Self::U(length),
-            _ => {
-                unimplemented!();
-            }
-        }
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        finish(s, parse_instruction(s))
     }
 }
 
@@ -137,8 +145,12 @@ impl RotationCounter {
     }
 }
 
-pub fn cubic_metres_of_lava(it: impl Iterator<Item = String>) -> usize {
-    let instructions: Vec<_> = it.map(|s| Instruction::from(s.as_str())).collect();
+pub fn cubic_metres_of_lava(it: impl Iterator<Item = String>) -> Result<usize, ParseError> {
+    Ok(lagoon_volume(&parse_lines::<Instruction>(it)?))
+}
+
+/// Count the cubic metres of the lagoon the instructions dig out.
+pub fn lagoon_volume(instructions: &[Instruction]) -> usize {
     let rotations = instructions
         .iter()
         .circular_tuple_windows()
@@ -193,6 +205,25 @@ pub fn cubic_metres_of_lava(it: impl Iterator<Item = String>) -> usize {
     total_score / 4
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 18;
+    const PART: u8 = 2;
+
+    type Input = Vec<Instruction>;
+    type Params = ();
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input.lines())?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(lagoon_volume(&input))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -209,7 +240,7 @@ mod test {
             "};
             assert_eq!(
                 cubic_metres_of_lava(example.lines().map(String::from)),
-                (i + 1) * (i + 1),
+                Ok((i + 1) * (i + 1)),
                 "({i}+1) * ({i}+1)"
             );
         }
@@ -228,7 +259,7 @@ mod test {
             ", 2*i, 2*i};
             assert_eq!(
                 cubic_metres_of_lava(example.lines().map(String::from)),
-                3 * i * i + 4 * i + 1,
+                Ok(3 * i * i + 4 * i + 1),
                 "(2({i})+1)^2 - {i}^2"
             );
         }
@@ -254,7 +285,7 @@ mod test {
         "};
         assert_eq!(
             cubic_metres_of_lava(example.lines().map(String::from)),
-            952408144115
+            Ok(952408144115)
         );
     }
 }
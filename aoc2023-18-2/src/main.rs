@@ -1,10 +1,14 @@
 use std::io;
 
 use aoc2023_18_2::cubic_metres_of_lava;
+use aoc2023_solution::logging;
+use color_eyre::Result;
 
-fn main() {
-    tracing_subscriber::fmt::init();
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = cubic_metres_of_lava(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    logging::init(None).unwrap();
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = cubic_metres_of_lava(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
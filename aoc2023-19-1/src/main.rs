@@ -2,11 +2,12 @@ use color_eyre::Result;
 use std::io::{self};
 
 use aoc2023_19_1::accepted_part_rating_sum;
+use aoc2023_solution::logging;
 
 fn main() -> Result<()> {
-    tracing_subscriber::fmt::init();
+    logging::init(None).unwrap();
     let lines = io::read_to_string(io::stdin())?;
-    let answer = accepted_part_rating_sum(lines);
+    let answer = accepted_part_rating_sum(lines)?;
     println!("Answer: {answer}");
     Ok(())
 }
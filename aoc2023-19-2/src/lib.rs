@@ -1,18 +1,17 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
+use aoc2023_parse::{finish, Parse, ParseError};
+use aoc2023_solution::{Result, Solution};
 use nom::{
-    branch::alt,
     bytes::complete::tag,
-    character::complete::{alpha1, char, newline},
+    character::complete::{alpha1, newline},
     multi::separated_list0,
-    sequence::{delimited, pair, separated_pair, tuple},
+    sequence::separated_pair,
     IResult,
 };
 
-pub fn acceptable_parts_sum<S: AsRef<str>>(input: S) -> u64 {
-    let Input { workflows, .. } = Input::parse(input.as_ref());
-
-    workflows.sum_acceptable(PartSpace::default(), Target::default())
+pub fn acceptable_parts_sum<S: AsRef<str>>(input: S) -> Result<u64, ParseError> {
+    Ok(input.as_ref().parse::<System>()?.acceptable_parts())
 }
 
 #[derive(Clone, Copy)]
@@ -62,76 +61,35 @@ impl PartSpace {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Parse)]
 enum Category {
+    #[parse(tag = "x")]
     X,
+    #[parse(tag = "m")]
     M,
+    #[parse(tag = "a")]
     A,
+    #[parse(tag = "s")]
     S,
 }
 
-impl Parse for Category {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        let (rest, id) = alt((char('x'), char('m'), char('a'), char('s')))(input)?;
-        Ok((
-            rest,
-            match id {
-                'x' => Category::X,
-                'm' => Category::M,
-                'a' => Category::A,
-                's' => Category::S,
-                _ => unreachable!(),
-            },
-        ))
-    }
-}
-
 #[allow(dead_code)]
-#[derive(Debug)]
+// once we're inside the braces, any mismatch is a hard error
+#[derive(Debug, Parse)]
+#[parse(prefix = "{", separator = ",", suffix = "}", cut)]
 struct Part {
+    #[parse(prefix = "x=")]
     x: u64,
+    #[parse(prefix = "m=")]
     m: u64,
+    #[parse(prefix = "a=")]
     a: u64,
+    #[parse(prefix = "s=")]
     s: u64,
 }
 
-impl Parse for Part {
-    fn parse(value: &str) -> IResult<&str, Self> {
-        fn inards(input: &str) -> IResult<&str, Part> {
-            use nom::character::complete::u64;
-            let (remaining, parsed) = tuple((
-                tag("x="),
-                u64,
-                tag(",m="),
-                u64,
-                tag(",a="),
-                u64,
-                tag(",s="),
-                u64,
-            ))(input)?;
-            Ok((
-                remaining,
-                Part {
-                    x: parsed.1,
-                    m: parsed.3,
-                    a: parsed.5,
-                    s: parsed.7,
-                },
-            ))
-        }
-        delimited(char('{'), inards, char('}'))(value)
-    }
-}
-
-#[derive(Debug)]
-struct Parts(Vec<Part>);
-
-impl Parse for Parts {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        let (rest, parts) = separated_list0(newline, Part::parse)(input)?;
-        Ok((rest, Self(parts)))
-    }
-}
+#[derive(Debug, Parse)]
+struct Parts(#[parse(separator = "\n")] Vec<Part>);
 
 impl<'a> IntoIterator for &'a Parts {
     type Item = &'a Part;
@@ -141,16 +99,11 @@ impl<'a> IntoIterator for &'a Parts {
     }
 }
 
-trait Parse
-where
-    Self: Sized,
-{
-    fn parse(input: &str) -> IResult<&str, Self>;
-}
-
-#[derive(Debug)]
+#[derive(Debug, Parse)]
 enum Comparator {
+    #[parse(tag = ">")]
     Gt(u64),
+    #[parse(tag = "<")]
     Lt(u64),
 }
 
@@ -197,20 +150,7 @@ impl Comparator {
     }
 }
 
-impl Parse for Comparator {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        use nom::character::complete::u64;
-        let (rest, (operator, operand)) = pair(alt((char('>'), char('<'))), u64)(input)?;
-        let comparator = match operator {
-            '>' => Comparator::Gt(operand),
-            '<' => Comparator::Lt(operand),
-            _ => unreachable!(),
-        };
-        Ok((rest, comparator))
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Parse)]
 struct Condition {
     category: Category,
     comparator: Comparator,
@@ -237,19 +177,6 @@ impl Condition {
     }
 }
 
-impl Parse for Condition {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        let (rest, (category, comparator)) = pair(Category::parse, Comparator::parse)(input)?;
-        Ok((
-            rest,
-            Self {
-                category,
-                comparator,
-            },
-        ))
-    }
-}
-
 #[derive(Debug, Clone)]
 enum Target {
     Accept,
@@ -292,8 +219,9 @@ impl IntoIterator for SpaceTargets {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Parse)]
 struct Rule {
+    #[parse(suffix = ":")]
     condition: Option<Condition>,
     target: Target,
 }
@@ -321,32 +249,8 @@ impl Rule {
     }
 }
 
-impl Parse for Rule {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        if let Ok((rest, (condition, target))) =
-            separated_pair(Condition::parse, char(':'), Target::parse)(input)
-        {
-            return Ok((
-                rest,
-                Self {
-                    condition: Some(condition),
-                    target,
-                },
-            ));
-        }
-        let (rest, target) = Target::parse(input)?;
-        Ok((
-            rest,
-            Self {
-                condition: None,
-                target,
-            },
-        ))
-    }
-}
-
-#[derive(Debug)]
-struct Rules(Vec<Rule>);
+#[derive(Debug, Parse)]
+struct Rules(#[parse(separator = ",")] Vec<Rule>);
 
 impl<'a> IntoIterator for &'a Rules {
     type Item = &'a Rule;
@@ -356,23 +260,10 @@ impl<'a> IntoIterator for &'a Rules {
     }
 }
 
-impl Parse for Rules {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        let (rest, rules) = separated_list0(char(','), Rule::parse)(input)?;
-        Ok((rest, Self(rules)))
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Parse)]
+#[parse(prefix = "{", suffix = "}", cut)]
 struct Workflow(Rules);
 
-impl Parse for Workflow {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        let (rest, rules) = delimited(char('{'), Rules::parse, char('}'))(input)?;
-        Ok((rest, Self(rules)))
-    }
-}
-
 impl Workflow {
     fn sum_acceptable(&self, space: PartSpace) -> SpaceTargets {
         // what is this spaghetti???
@@ -412,39 +303,99 @@ impl Workflows {
     }
 }
 
-#[derive(Debug)]
-struct NamedWorkflow<'a>(&'a str, Workflow);
-
-// TODO this with Parse trait?
-impl<'a> NamedWorkflow<'a> {
-    fn parse(input: &'a str) -> IResult<&'a str, Self> {
-        let (rest, (name, workflow)) = tuple((alpha1, Workflow::parse))(input)?;
-        Ok((rest, Self(name, workflow)))
+impl Workflows {
+    /// The offset into `s` of the first use of a workflow that isn't
+    /// defined.
+    fn undefined(&self, s: &str) -> Option<usize> {
+        self.0
+            .values()
+            .flat_map(|Workflow(rules)| rules)
+            .filter_map(|rule| match &rule.target {
+                Target::Workflow(name) if !self.0.contains_key(name) => {
+                    Some(target_offset(s, name))
+                }
+                _ => None,
+            })
+            .min()
     }
 }
 
+/// Where `name` is first sent to by a rule in `s`.
+fn target_offset(s: &str, name: &str) -> usize {
+    s.match_indices(name)
+        .map(|(i, _)| i)
+        .find(|&i| s[..i].ends_with([':', ',']) && s[i + name.len()..].starts_with([',', '}']))
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Parse)]
+struct NamedWorkflow(#[parse(with = alpha1)] String, Workflow);
+
 impl Parse for Workflows {
     fn parse(input: &str) -> IResult<&str, Self> {
         let (rest, named_workflows) = separated_list0(newline, NamedWorkflow::parse)(input)?;
         Ok((
             rest,
-            Self(HashMap::from_iter(named_workflows.into_iter().map(
-                |NamedWorkflow(name, workflow)| (name.to_owned(), workflow),
-            ))),
+            Self(HashMap::from_iter(
+                named_workflows
+                    .into_iter()
+                    .map(|NamedWorkflow(name, workflow)| (name, workflow)),
+            )),
         ))
     }
 }
 
+/// The workflows parts are sorted with.
 #[derive(Debug)]
-struct Input {
+pub struct System {
     workflows: Workflows,
 }
 
-impl Input {
-    pub fn parse(input: &str) -> Self {
-        let (_, (workflows, _)) =
-            separated_pair(Workflows::parse, tag("\n\n"), Parts::parse)(input).unwrap();
-        Self { workflows }
+impl FromStr for System {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = separated_pair(Workflows::parse, tag("\n\n"), Parts::parse)(s);
+        let (workflows, _) = finish(s, input)?;
+        check_workflows(s, &workflows)?;
+        Ok(Self { workflows })
+    }
+}
+
+impl System {
+    pub fn acceptable_parts(&self) -> u64 {
+        self.workflows
+            .sum_acceptable(PartSpace::default(), Target::default())
+    }
+}
+
+/// Whether there's an `in` workflow, and every workflow sent to exists.
+fn check_workflows(s: &str, workflows: &Workflows) -> Result<(), ParseError> {
+    if let Some(offset) = workflows.undefined(s) {
+        return Err(ParseError::at(s, offset, "a workflow that's defined"));
+    }
+    if !workflows.0.contains_key("in") {
+        return Err(ParseError::at(s, 0, "a workflow named in"));
+    }
+    Ok(())
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 19;
+    const PART: u8 = 2;
+
+    type Input = System;
+    type Params = ();
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(input.acceptable_parts())
     }
 }
 
@@ -474,6 +425,14 @@ mod test {
             {x=2461,m=1339,a=466,s=291}
             {x=2127,m=1623,a=2188,s=1013}
         "};
-        assert_eq!(acceptable_parts_sum(example), 167409079868000);
+        assert_eq!(acceptable_parts_sum(example), Ok(167409079868000));
+    }
+
+    #[test]
+    fn undefined_workflows() {
+        let err = acceptable_parts_sum("in{x<10:A,px}\nqs{s>3448:A,lnx}\npx{A}\n\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 13));
+        let err = acceptable_parts_sum("px{x<10:A,R}\n\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (1, 1));
     }
 }
@@ -2,73 +2,89 @@ use std::{
     cell::RefCell,
     collections::{HashMap, VecDeque},
     rc::Rc,
+    str::FromStr,
 };
 
+use aoc2023_parse::{finish, parse_line, Parse, ParseError};
+use aoc2023_solution::{Result, Solution};
 use nom::{
-    bytes::complete::tag,
-    character::complete::alpha1,
-    multi::separated_list1,
-    sequence::{preceded, separated_pair},
-    IResult,
+    branch::alt, bytes::complete::tag, character::complete::alpha1, combinator::map,
+    sequence::separated_pair, IResult,
 };
+use tracing::{debug, instrument, trace};
 
-pub fn low_pulses_times_high_pulses_1k(it: impl Iterator<Item = String>) -> usize {
-    // First, set up our graph
-    let mut nodes: HashMap<String, Node> = HashMap::new();
-    let mut edge_queue: Vec<(Node, String)> = Vec::new();
-    for line in it {
-        let NodeSpec { node, output_names } = NodeSpec::parse(line.as_str());
-        let name = node.borrow().name().to_string();
-        nodes.insert(name, node.clone());
-        for output in output_names {
-            edge_queue.push((node.clone(), output));
+/// The modules, wired up, with the broadcaster to press the button on.
+pub struct Network {
+    broadcaster: Node,
+}
+
+impl Network {
+    /// Read the module specs and connect each to its outputs; anything sent
+    /// to but not specified becomes a sink.
+    pub fn read(it: impl Iterator<Item = String>) -> Result<Self, ParseError> {
+        let mut nodes: HashMap<String, Node> = HashMap::new();
+        let mut edge_queue: Vec<(Node, String)> = Vec::new();
+        let mut end = 0;
+        for (i, line) in it.enumerate() {
+            let NodeSpec { node, output_names } = parse_line(i, &line)?;
+            let name = node.borrow().name().to_string();
+            nodes.insert(name, node.clone());
+            for output in output_names {
+                edge_queue.push((node.clone(), output));
+            }
+            end = i + 1;
         }
-    }
-    for (from, to_name) in edge_queue {
-        let from_name = from.borrow().name().to_string();
-        let to = nodes
-            .entry(to_name.clone())
-            .or_insert_with(|| Rc::new(RefCell::new(Sink::new(to_name.to_string()))));
-        from.borrow_mut().connect_output(to.clone());
-        to.borrow_mut().connect_input(&from_name);
-    }
-
-    // Now process pulses
-    let mut counts = HashMap::<bool, usize>::from([(false, 0), (true, 0)]);
-    let broadcaster = nodes.get("broadcaster").unwrap();
-    let mut pulses = VecDeque::new();
-    for _ in 0..1000 {
-        pulses.push_back(Pulse::button(broadcaster.clone()));
-        while let Some(Pulse {
-            value,
-            origin,
-            destination,
-        }) = pulses.pop_front()
-        {
-            println!(
-                "{} -{}-> {}",
+        for (from, to_name) in edge_queue {
+            let from_name = from.borrow().name().to_string();
+            let to = nodes
+                .entry(to_name.clone())
+                .or_insert_with(|| Rc::new(RefCell::new(Sink::new(to_name.to_string()))));
+            from.borrow_mut().connect_output(to.clone());
+            to.borrow_mut().connect_input(&from_name);
+        }
+        let Some(broadcaster) = nodes.remove("broadcaster") else {
+            return Err(ParseError::at("", 0, "a broadcaster").at_line(end + 1));
+        };
+        Ok(Self { broadcaster })
+    }
+
+    /// Press the button 1000 times, counting the low and high pulses sent.
+    #[instrument(skip_all)]
+    pub fn press_1k(self) -> usize {
+        let mut counts = HashMap::<bool, usize>::from([(false, 0), (true, 0)]);
+        let mut pulses = VecDeque::new();
+        for _ in 0..1000 {
+            pulses.push_back(Pulse::button(self.broadcaster.clone()));
+            while let Some(Pulse {
+                value,
                 origin,
-                if value { "high" } else { "low" },
-                destination.borrow().name()
-            );
-            *counts.get_mut(&value).unwrap() += 1;
-            let mut destination = destination.borrow_mut();
-            destination.process_input_pulse(&origin, value);
-            for pulse in destination.get_output_pulses() {
-                pulses.push_back(pulse);
+                destination,
+            }) = pulses.pop_front()
+            {
+                trace!(
+                    "{} -{}-> {}",
+                    origin,
+                    if value { "high" } else { "low" },
+                    destination.borrow().name()
+                );
+                *counts.get_mut(&value).unwrap() += 1;
+                let mut destination = destination.borrow_mut();
+                destination.process_input_pulse(&origin, value);
+                for pulse in destination.get_output_pulses() {
+                    pulses.push_back(pulse);
+                }
             }
         }
-    }
 
-    dbg!(&counts);
-    counts.values().product()
+        debug!(low = counts[&false], high = counts[&true], "pulses sent");
+        counts.values().product()
+    }
 }
 
-pub trait Parse
-where
-    Self: Sized,
-{
-    fn parse(input: &str) -> IResult<&str, Self>;
+pub fn low_pulses_times_high_pulses_1k(
+    it: impl Iterator<Item = String>,
+) -> Result<usize, ParseError> {
+    Ok(Network::read(it)?.press_1k())
 }
 
 type Node = Rc<RefCell<dyn Module>>;
@@ -98,10 +114,11 @@ impl<'a> IntoIterator for &'a Outputs {
     }
 }
 
-#[derive(Debug, Default)]
-struct NodeNames(Vec<String>);
+#[derive(Debug, Default, Parse)]
+struct NodeNames(#[parse(separator = ", ", with = alpha1, nonempty)] Vec<String>);
 
 impl NodeNames {
+    #[allow(dead_code)]
     fn push<S: ToString>(&mut self, name: S) {
         self.0.push(name.to_string());
     }
@@ -123,39 +140,30 @@ impl<'a> IntoIterator for &'a NodeNames {
     }
 }
 
-impl Parse for NodeNames {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        let (rest, outputs) = separated_list1(tag(", "), alpha1)(input)?;
-        Ok((rest, Self(outputs.into_iter().map(String::from).collect())))
-    }
-}
-
 struct NodeSpec {
     node: Node,
     output_names: NodeNames,
 }
 
-impl NodeSpec {
-    fn parse(input: &str) -> Self {
-        let (_, (node, output_names)) =
-            separated_pair(Node::parse, tag(" -> "), NodeNames::parse)(input).unwrap();
-        Self { node, output_names }
+impl FromStr for NodeSpec {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spec = separated_pair(node, tag(" -> "), NodeNames::parse)(s);
+        let (node, output_names) = finish(s, spec)?;
+        Ok(Self { node, output_names })
     }
 }
 
-impl Parse for Node {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        if let Ok((rest, flipflop)) = FlipFlop::parse(input) {
-            return Ok((rest, Rc::new(RefCell::new(flipflop))));
-        }
-        if let Ok((rest, conjunction)) = Conjunction::parse(input) {
-            return Ok((rest, Rc::new(RefCell::new(conjunction))));
-        }
-        if let Ok((rest, broadcaster)) = Broadcaster::parse(input) {
-            return Ok((rest, Rc::new(RefCell::new(broadcaster))));
-        }
-        todo!("handle parse fail here");
+fn node(input: &str) -> IResult<&str, Node> {
+    fn node<M: Module + 'static>(module: M) -> Node {
+        Rc::new(RefCell::new(module))
     }
+    alt((
+        map(FlipFlop::parse, node),
+        map(Conjunction::parse, node),
+        map(Broadcaster::parse, node),
+    ))(input)
 }
 
 struct Pulse {
@@ -175,7 +183,7 @@ impl Pulse {
 }
 
 trait Module: std::fmt::Debug {
-    fn connect_input(&mut self, name: &str) {
+    fn connect_input(&mut self, _name: &str) {
         // only does something for Conjunction
     }
     // typically add a pointer to an internal list
@@ -199,21 +207,15 @@ trait Module: std::fmt::Debug {
     }
 }
 
-struct Named<T>(String, T);
-
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Parse)]
+#[parse(prefix = "broadcaster")]
 struct Broadcaster {
+    #[parse(skip)]
     state: bool,
+    #[parse(skip)]
     outputs: Outputs,
 }
 
-impl Parse for Broadcaster {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        let (rest, _) = tag("broadcaster")(input)?;
-        Ok((rest, Broadcaster::default()))
-    }
-}
-
 impl Module for Broadcaster {
     fn name(&self) -> &str {
         "broadcaster"
@@ -232,33 +234,19 @@ impl Module for Broadcaster {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Parse)]
+#[parse(prefix = "%")]
 struct FlipFlop {
+    #[parse(with = alpha1)]
     name: String,
+    #[parse(skip)]
     to_send: Option<bool>,
+    #[parse(skip)]
     state: bool,
+    #[parse(skip)]
     outputs: Outputs,
 }
 
-impl FlipFlop {
-    fn new(name: String) -> Self {
-        Self {
-            name,
-            to_send: None,
-            state: false,
-            outputs: Outputs::default(),
-        }
-    }
-}
-
-impl Parse for FlipFlop {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        use nom::character::complete::char;
-        let (rest, name) = preceded(char('%'), alpha1)(input)?;
-        Ok((rest, FlipFlop::new(name.into())))
-    }
-}
-
 impl Module for FlipFlop {
     fn name(&self) -> &str {
         self.name.as_str()
@@ -282,31 +270,17 @@ impl Module for FlipFlop {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Parse)]
+#[parse(prefix = "&")]
 struct Conjunction {
+    #[parse(with = alpha1)]
     name: String,
+    #[parse(skip)]
     outputs: Outputs,
+    #[parse(skip)]
     inputs: HashMap<String, bool>,
 }
 
-impl Conjunction {
-    fn new(name: String) -> Self {
-        Self {
-            name,
-            outputs: Outputs::default(),
-            inputs: HashMap::new(),
-        }
-    }
-}
-
-impl Parse for Conjunction {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        use nom::character::complete::char;
-        let (rest, name) = preceded(char('&'), alpha1)(input)?;
-        Ok((rest, Conjunction::new(name.into())))
-    }
-}
-
 impl Module for Conjunction {
     fn name(&self) -> &str {
         &self.name
@@ -357,6 +331,25 @@ impl Module for Sink {
     fn process_input_pulse(&mut self, _from: &str, _pulse: bool) {}
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u8 = 20;
+    const PART: u8 = 1;
+
+    type Input = Network;
+    type Params = ();
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Network::read(input.lines().map(String::from))?)
+    }
+
+    fn solve(input: Self::Input, _: &Self::Params) -> Result<Self::Answer> {
+        Ok(input.press_1k())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -373,7 +366,7 @@ mod test {
         "};
         assert_eq!(
             low_pulses_times_high_pulses_1k(example.lines().map(String::from)),
-            32000000
+            Ok(32000000)
         );
     }
 
@@ -388,7 +381,18 @@ mod test {
         "};
         assert_eq!(
             low_pulses_times_high_pulses_1k(example.lines().map(String::from)),
-            11687500
+            Ok(11687500)
         );
     }
+    #[test]
+    fn unknown_module() {
+        let err = "$a -> b".parse::<NodeSpec>().err().unwrap();
+        assert_eq!((err.line(), err.column()), (1, 1));
+    }
+
+    #[test]
+    fn no_broadcaster() {
+        let err = low_pulses_times_high_pulses_1k(["%a -> b".to_string()].into_iter()).unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 1));
+    }
 }
@@ -1,10 +1,14 @@
+use color_eyre::Result;
 use std::io;
 
 use aoc2023_20_1::low_pulses_times_high_pulses_1k;
+use aoc2023_solution::logging;
 
-fn main() {
-    tracing_subscriber::fmt::init();
-    let lines = io::stdin().lines().filter_map(|l| l.ok());
-    let answer = low_pulses_times_high_pulses_1k(lines);
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    logging::init(None).unwrap();
+    let lines = io::stdin().lines().map_while(Result::ok);
+    let answer = low_pulses_times_high_pulses_1k(lines)?;
     println!("Answer: {answer}");
+    Ok(())
 }
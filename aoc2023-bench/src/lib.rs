@@ -0,0 +1,224 @@
+//! Time every solver over generated inputs of increasing size.
+
+use std::{collections::BTreeMap, fmt, fs, hint::black_box, path::Path, time::Duration};
+
+use aoc2023_gen::generate;
+use aoc2023_runner::Puzzle;
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+
+/// Which puzzle, at which input size.
+pub type Key = (u8, u8, usize);
+
+pub fn median(mut times: Vec<Duration>) -> Duration {
+    times.sort();
+    times.get(times.len() / 2).copied().unwrap_or_default()
+}
+
+/// Median times to parse and to solve an input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timing {
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl Timing {
+    /// The two medians added together.
+    pub fn total(&self) -> Duration {
+        self.parse + self.solve
+    }
+}
+
+/// Median times for `puzzle` to parse and solve a generated input of `size`.
+pub fn time(puzzle: &Puzzle, size: usize, iterations: usize) -> Result<Timing> {
+    let input =
+        generate(puzzle.day, 0, size).ok_or(eyre!("No generator for day {}", puzzle.day))?;
+    let mut parses = Vec::with_capacity(iterations);
+    let mut solves = Vec::with_capacity(iterations);
+    for _ in 0..iterations.max(1) {
+        let timed = black_box(puzzle.solve_timed(black_box(&input))?);
+        parses.push(timed.parse);
+        solves.push(timed.solve);
+    }
+    Ok(Timing {
+        parse: median(parses),
+        solve: median(solves),
+    })
+}
+
+/// The same, with rayon limited to the calling thread.
+pub fn time_single_threaded(puzzle: &Puzzle, size: usize, iterations: usize) -> Result<Timing> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()?
+        .install(|| time(puzzle, size, iterations))
+}
+
+/// The exponent `k` for which time grows like `size^k` between two samples.
+pub fn scaling((small, t_small): (usize, Duration), (large, t_large): (usize, Duration)) -> f64 {
+    (t_large.as_secs_f64() / t_small.as_secs_f64()).ln() / (large as f64 / small as f64).ln()
+}
+
+/// Median timings, one line per `day part size nanoseconds`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline(pub BTreeMap<Key, Duration>);
+
+impl Baseline {
+    pub fn parse(baseline: &str) -> Result<Self> {
+        let mut timings = BTreeMap::new();
+        for (i, line) in baseline.lines().enumerate() {
+            let fields: Vec<u64> = line
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .wrap_err_with(|| format!("baseline line {}", i + 1))?;
+            let [day, part, size, nanos] = fields[..] else {
+                return Err(eyre!("baseline line {}: expected 4 fields", i + 1));
+            };
+            timings.insert(
+                (day as u8, part as u8, size as usize),
+                Duration::from_nanos(nanos),
+            );
+        }
+        Ok(Self(timings))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    /// Timings more than `threshold` (e.g. 0.2 for 20%) slower than ours.
+    pub fn regressions<'a>(
+        &'a self,
+        current: &'a Baseline,
+        threshold: f64,
+    ) -> impl Iterator<Item = Regression> + 'a {
+        current.0.iter().filter_map(move |(key, now)| {
+            let before = self.0.get(key)?;
+            let change = now.as_secs_f64() / before.as_secs_f64() - 1.0;
+            (change > threshold).then_some(Regression {
+                key: *key,
+                before: *before,
+                now: *now,
+                change,
+            })
+        })
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ((day, part, size), time) in &self.0 {
+            writeln!(f, "{day} {part} {size} {}", time.as_nanos())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Regression {
+    pub key: Key,
+    pub before: Duration,
+    pub now: Duration,
+    pub change: f64,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (day, part, size) = self.key;
+        write!(
+            f,
+            "{day:02}-{part} size {size}: {:.1?} vs baseline {:.1?} (+{:.0}%)",
+            self.now,
+            self.before,
+            self.change * 100.0
+        )
+    }
+}
+
+/// Median timings laid out with a column per size, and the scaling exponent
+/// between the two largest sizes.
+pub fn table(timings: &Baseline, sizes: &[usize]) -> String {
+    let mut out = format!("{:<8}", "puzzle");
+    for size in sizes {
+        out.push_str(&format!("{:>12}", format!("n={size}")));
+    }
+    out.push_str(&format!("{:>10}\n", "scaling"));
+
+    let mut rows: BTreeMap<(u8, u8), Vec<(usize, Duration)>> = BTreeMap::new();
+    for (&(day, part, size), &time) in &timings.0 {
+        rows.entry((day, part)).or_default().push((size, time));
+    }
+    for ((day, part), samples) in rows {
+        out.push_str(&format!("{:<8}", format!("{day:02}-{part}")));
+        for size in sizes {
+            match samples.iter().find(|(s, _)| s == size) {
+                Some((_, time)) => out.push_str(&format!("{:>12}", format!("{time:.1?}"))),
+                None => out.push_str(&format!("{:>12}", "-")),
+            }
+        }
+        if let [.., small, large] = samples[..] {
+            out.push_str(&format!(
+                "{:>10}",
+                format!("n^{:.1}", scaling(small, large))
+            ));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn statistics() {
+        let ms = Duration::from_millis;
+        assert_eq!(median(vec![ms(5), ms(1), ms(3)]), ms(3));
+        assert_eq!(median(Vec::new()), Duration::ZERO);
+        assert!((scaling((10, ms(1)), (20, ms(4))) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn baseline() -> Result<()> {
+        let ms = Duration::from_millis;
+        let before = Baseline(BTreeMap::from([
+            ((8, 2, 10), ms(10)),
+            ((12, 1, 10), ms(10)),
+        ]));
+        let before = Baseline::parse(&before.to_string())?;
+        let now = Baseline(BTreeMap::from([
+            ((8, 2, 10), ms(11)),
+            ((12, 1, 10), ms(15)),
+        ]));
+        let regressions: Vec<_> = before.regressions(&now, 0.2).collect();
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].key, (12, 1, 10));
+        assert!(Baseline::parse("8 2 10").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn times_a_puzzle() -> Result<()> {
+        let puzzle = aoc2023_runner::puzzle(1, 1)?;
+        let timing = time(puzzle, 5, 3)?;
+        assert_eq!(timing.total(), timing.parse + timing.solve);
+        let timings = Baseline(BTreeMap::from([
+            ((1, 1, 5), timing.total()),
+            ((1, 1, 10), time_single_threaded(puzzle, 10, 3)?.total()),
+        ]));
+        let table = table(&timings, &[5, 10]);
+        assert!(table.lines().nth(1).unwrap().starts_with("01-1"));
+        Ok(())
+    }
+}
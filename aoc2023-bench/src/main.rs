@@ -0,0 +1,99 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use aoc2023_bench::{table, time, time_single_threaded, Baseline};
+use aoc2023_runner::PUZZLES;
+use clap::Parser;
+use color_eyre::{eyre::bail, Result};
+
+/// Puzzles whose solvers fan out over rayon.
+const PARALLEL: [(u8, u8); 2] = [(8, 2), (12, 2)];
+
+#[derive(Parser)]
+#[command(about = "Time every solver over generated inputs of increasing size")]
+struct Cli {
+    /// Generator sizes to time each puzzle at
+    #[arg(short, long, value_delimiter = ',', default_values_t = [10, 20, 40, 80])]
+    sizes: Vec<usize>,
+    /// Only time these days
+    #[arg(short, long, value_delimiter = ',')]
+    days: Vec<u8>,
+    /// Runs per puzzle and size; the median is reported
+    #[arg(short, long, default_value_t = 5)]
+    iterations: usize,
+    /// Timings from an earlier run to compare against
+    #[arg(short, long, default_value = "target/bench-baseline.txt")]
+    baseline: PathBuf,
+    /// Overwrite the baseline with this run's timings
+    #[arg(long)]
+    save: bool,
+    /// Slowdown over the baseline reported as a regression, e.g. 0.2 for 20%
+    #[arg(short, long, default_value_t = 0.2)]
+    threshold: f64,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let cli = Cli::parse();
+    let puzzles = PUZZLES
+        .iter()
+        .filter(|p| cli.days.is_empty() || cli.days.contains(&p.day));
+
+    let mut timings = Baseline::default();
+    let mut parsing = Baseline::default();
+    let mut threading = Vec::new();
+    for puzzle in puzzles {
+        for &size in &cli.sizes {
+            let timing = time(puzzle, size, cli.iterations)?;
+            timings
+                .0
+                .insert((puzzle.day, puzzle.part, size), timing.total());
+            parsing
+                .0
+                .insert((puzzle.day, puzzle.part, size), timing.parse);
+        }
+        if PARALLEL.contains(&(puzzle.day, puzzle.part)) {
+            let size = *cli.sizes.iter().max().unwrap_or(&10);
+            let single = time_single_threaded(puzzle, size, cli.iterations)?.total();
+            threading.push((
+                puzzle,
+                size,
+                single,
+                timings.0[&(puzzle.day, puzzle.part, size)],
+            ));
+        }
+    }
+    println!("{}", table(&timings, &cli.sizes));
+    println!("of which parsing:\n{}", table(&parsing, &cli.sizes));
+
+    for (puzzle, size, single, parallel) in threading {
+        println!(
+            "{:02}-{} size {size}: {single:.1?} on one thread, {parallel:.1?} on {} ({:.1}x)",
+            puzzle.day,
+            puzzle.part,
+            rayon::current_num_threads(),
+            single.as_secs_f64() / parallel.as_secs_f64()
+        );
+    }
+
+    let mut regressions = 0;
+    if cli.baseline.exists() {
+        let baseline = Baseline::load(&cli.baseline)?;
+        for regression in baseline.regressions(&timings, cli.threshold) {
+            println!("regression: {regression}");
+            regressions += 1;
+        }
+    }
+    if cli.save {
+        // keep timings for puzzles and sizes not part of this run
+        let mut saved = match Baseline::load(&cli.baseline) {
+            Ok(baseline) => baseline.0,
+            Err(_) => BTreeMap::new(),
+        };
+        saved.extend(timings.0);
+        Baseline(saved).save(&cli.baseline)?;
+        println!("saved baseline to {}", cli.baseline.display());
+    } else if regressions > 0 {
+        bail!("{regressions} timing(s) regressed past the baseline");
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! Calibration lines: letters with digits and spelled-out digits mixed in.
+
+use rand::{seq::SliceRandom, Rng};
+
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// `size` lines, each holding at least one literal digit.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let mut line = String::new();
+        let tokens = rng.gen_range(1..=6);
+        let digit_at = rng.gen_range(0..tokens);
+        for token in 0..tokens {
+            if token == digit_at {
+                line.push(rng.gen_range('1'..='9'));
+            } else if rng.gen_bool(0.4) {
+                line.push_str(WORDS.choose(rng).unwrap());
+            } else {
+                let len = rng.gen_range(1..=4);
+                line.extend((0..len).map(|_| rng.gen_range('a'..='z')));
+            }
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,24 @@
+//! Cube games: `Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue`.
+
+use itertools::Itertools;
+use rand::{seq::index::sample, Rng};
+
+const COLOURS: [&str; 3] = ["red", "green", "blue"];
+
+/// `size` games of one to six rounds each.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let mut out = String::new();
+    for id in 1..=size {
+        let rounds = (0..rng.gen_range(1..=6))
+            .map(|_| {
+                let shown = rng.gen_range(1..=COLOURS.len());
+                sample(rng, COLOURS.len(), shown)
+                    .into_iter()
+                    .map(|c| format!("{} {}", rng.gen_range(1..=20), COLOURS[c]))
+                    .join(", ")
+            })
+            .join("; ");
+        out.push_str(&format!("Game {id}: {rounds}\n"));
+    }
+    out
+}
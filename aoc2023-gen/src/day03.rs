@@ -0,0 +1,29 @@
+//! Engine schematics: part numbers and symbols scattered over a `.` grid.
+
+use rand::{seq::SliceRandom, Rng};
+
+const SYMBOLS: [char; 10] = ['*', '#', '+', '$', '/', '@', '=', '%', '&', '-'];
+
+/// A `size` x `size` schematic.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let size = size.max(3);
+    let mut out = String::new();
+    for _ in 0..size {
+        let mut row = String::with_capacity(size + 1);
+        while row.len() < size {
+            let roll = rng.gen_range(0..10);
+            let number = rng.gen_range(1..1000).to_string();
+            if roll < 2 && row.len() + number.len() < size {
+                row.push_str(&number);
+                row.push('.');
+            } else if roll < 3 {
+                row.push(*SYMBOLS.choose(rng).unwrap());
+            } else {
+                row.push('.');
+            }
+        }
+        out.push_str(&row);
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,33 @@
+//! Scratchcards: `Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53`.
+
+use itertools::Itertools;
+use rand::{
+    seq::{index::sample, SliceRandom},
+    Rng,
+};
+
+const WINNING: usize = 10;
+const IN_HAND: usize = 25;
+
+/// `size` cards, none of which win copies of cards past the end.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let mut out = String::new();
+    let width = size.to_string().len();
+    for id in 1..=size {
+        let matches = rng.gen_range(0..=WINNING.min(size - id));
+        let numbers: Vec<usize> = sample(rng, 99, WINNING + IN_HAND - matches)
+            .into_iter()
+            .map(|n| n + 1)
+            .collect();
+        let (winning, rest) = numbers.split_at(WINNING);
+        let mut hand: Vec<usize> = winning[..matches].iter().chain(rest).copied().collect();
+        hand.shuffle(rng);
+        let show = |ns: &[usize]| ns.iter().map(|n| format!("{n:2}")).join(" ");
+        out.push_str(&format!(
+            "Card {id:width$}: {} | {}\n",
+            show(winning),
+            show(&hand)
+        ));
+    }
+    out
+}
@@ -0,0 +1,52 @@
+//! Almanacs: a seeds line followed by seven category-to-category maps.
+
+use itertools::Itertools;
+use rand::{seq::SliceRandom, Rng};
+
+const CATEGORIES: [&str; 8] = [
+    "seed",
+    "soil",
+    "fertilizer",
+    "water",
+    "light",
+    "temperature",
+    "humidity",
+    "location",
+];
+
+/// Each map shuffles `size` blocks of a `10_000 * size` wide range, and there
+/// are five seed ranges, each at most a fiftieth of that wide.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let size = size.max(1);
+    let span = 10_000 * size as u64;
+
+    let seeds = (0..5)
+        .map(|_| {
+            let len = rng.gen_range(1..=span / 50);
+            format!("{} {len}", rng.gen_range(0..span - len))
+        })
+        .join(" ");
+    let mut out = format!("seeds: {seeds}\n");
+
+    for (from, to) in CATEGORIES.iter().tuple_windows() {
+        let mut cuts: Vec<u64> = (1..size).map(|_| rng.gen_range(1..span)).collect();
+        cuts.extend([0, span]);
+        cuts.sort();
+        cuts.dedup();
+        let blocks: Vec<(u64, u64)> = cuts.windows(2).map(|w| (w[0], w[1] - w[0])).collect();
+        let mut order: Vec<usize> = (0..blocks.len()).collect();
+        order.shuffle(rng);
+
+        out.push_str(&format!("\n{from}-to-{to} map:\n"));
+        let mut dest = 0;
+        for i in order {
+            let (source, len) = blocks[i];
+            // leave the odd block out to exercise the identity fallback
+            if blocks.len() == 1 || rng.gen_bool(0.9) {
+                out.push_str(&format!("{dest} {source} {len}\n"));
+            }
+            dest += len;
+        }
+    }
+    out
+}
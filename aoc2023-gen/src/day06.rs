@@ -0,0 +1,34 @@
+//! Boat races: `Time:` and `Distance:` lines of aligned columns.
+
+use rand::Rng;
+
+/// Up to four races (`size` is clamped), so that the joined-up numbers of
+/// part 2 still fit in a `u64` and can still be beaten.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let races = size.clamp(1, 4);
+    loop {
+        let times: Vec<u64> = (0..races).map(|_| rng.gen_range(7..100)).collect();
+        let records: Vec<u64> = times
+            .iter()
+            .map(|&t| rng.gen_range(t..(t / 2) * (t - t / 2)))
+            .collect();
+        let join = |ns: &[u64]| ns.iter().map(u64::to_string).collect::<String>();
+        let (time, record): (u64, u64) = (
+            join(&times).parse().unwrap(),
+            join(&records).parse().unwrap(),
+        );
+        if (time / 2) * (time - time / 2) <= record {
+            continue;
+        }
+        let mut out = String::from("Time:    ");
+        for t in &times {
+            out.push_str(&format!(" {t:5}"));
+        }
+        out.push_str("\nDistance:");
+        for r in &records {
+            out.push_str(&format!(" {r:5}"));
+        }
+        out.push('\n');
+        return out;
+    }
+}
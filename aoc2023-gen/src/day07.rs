@@ -0,0 +1,20 @@
+//! Camel cards: a five card hand and a bid per line.
+
+use rand::{seq::SliceRandom, Rng};
+
+const CARDS: [char; 13] = [
+    'A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2',
+];
+
+/// `size` hands.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        // draw from a few ranks at a time so that pairs and sets turn up
+        let distinct = rng.gen_range(1..=5);
+        let ranks: Vec<char> = CARDS.choose_multiple(rng, distinct).copied().collect();
+        let hand: String = (0..5).map(|_| *ranks.choose(rng).unwrap()).collect();
+        out.push_str(&format!("{hand} {}\n", rng.gen_range(1..=1000)));
+    }
+    out
+}
@@ -0,0 +1,77 @@
+//! Haunted wasteland maps: a turn sequence, then `AAA = (BBB, CCC)` nodes.
+
+use std::{collections::HashSet, ops::RangeInclusive};
+
+use rand::{seq::SliceRandom, Rng};
+
+const PRIMES: [usize; 6] = [3, 5, 7, 11, 13, 17];
+
+/// Every ghost together needs up to `56 * turns` names out of the 16,224
+/// three-letter ones, so the turn count is capped well short of that.
+const MAX_TURNS: usize = 200;
+
+/// A name not yet in `taken`, ending in a letter from `last`.
+fn fresh(rng: &mut impl Rng, taken: &mut HashSet<String>, last: RangeInclusive<char>) -> String {
+    loop {
+        let mut name: String = (0..2).map(|_| rng.gen_range('A'..='Z')).collect();
+        name.push(rng.gen_range(last.clone()));
+        if taken.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+/// `size` turns (at most [`MAX_TURNS`]), and between two and six ghosts.
+///
+/// Like the real inputs, each ghost walks a private loop whose length is a
+/// prime multiple of the turn count, with its only `..Z` node at the end, so
+/// the LCM shortcut holds. The first ghost walks from `AAA` to `ZZZ`.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let turns = size.clamp(1, MAX_TURNS);
+    let mut out: String = (0..turns)
+        .map(|_| if rng.gen() { 'L' } else { 'R' })
+        .collect();
+    out.push_str("\n\n");
+
+    let mut taken = HashSet::from(["AAA".to_string(), "ZZZ".to_string()]);
+    let ghosts = rng.gen_range(2..=PRIMES.len());
+    let mut nodes = Vec::new();
+    for (ghost, &prime) in PRIMES.choose_multiple(rng, ghosts).enumerate() {
+        let (start, end) = if ghost == 0 {
+            ("AAA".to_string(), "ZZZ".to_string())
+        } else {
+            (
+                fresh(rng, &mut taken, 'A'..='A'),
+                fresh(rng, &mut taken, 'Z'..='Z'),
+            )
+        };
+        let mut path: Vec<String> = (1..prime * turns)
+            .map(|_| fresh(rng, &mut taken, 'B'..='Y'))
+            .collect();
+        path.push(end);
+        nodes.push((start, path[0].clone()));
+        for pair in path.windows(2) {
+            nodes.push((pair[0].clone(), pair[1].clone()));
+        }
+        nodes.push((path[path.len() - 1].clone(), path[0].clone()));
+    }
+    nodes.shuffle(rng);
+    for (node, next) in nodes {
+        out.push_str(&format!("{node} = ({next}, {next})\n"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn large() -> color_eyre::Result<()> {
+        // past the names there are to give out, so only stops if capped
+        let input = generate(&mut crate::rng(8), 100_000);
+        assert_eq!(input.lines().next().map(str::len), Some(MAX_TURNS));
+        aoc2023_runner::puzzle(8, 2)?.solve(&input)?;
+        Ok(())
+    }
+}
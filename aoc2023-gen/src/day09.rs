@@ -0,0 +1,27 @@
+//! OASIS reports: lines of values sampled from a polynomial.
+
+use itertools::Itertools;
+use rand::Rng;
+
+const SAMPLES: usize = 21;
+
+/// `size` histories, each from a polynomial of degree at most five.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let degree = rng.gen_range(0..=5);
+        // integrate a constant difference `degree` times
+        let mut values = vec![rng.gen_range(-5i64..=5); SAMPLES - degree];
+        for level in (0..degree).rev() {
+            let mut next = vec![rng.gen_range(-20..=20)];
+            for delta in &values {
+                next.push(next.last().unwrap() + delta);
+            }
+            values = next;
+            debug_assert_eq!(values.len(), SAMPLES - level);
+        }
+        out.push_str(&values.iter().join(" "));
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,81 @@
+//! Pipe mazes: one loop through `S`, surrounded by junk pipes.
+
+use std::collections::HashSet;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::shapes::{Heading, Loop};
+
+const JUNK: [char; 7] = ['.', '|', '-', 'L', 'J', '7', 'F'];
+
+fn pipe(arrived: Heading, leaving: Heading) -> char {
+    use Heading::*;
+    // the pipe joins where we came from with where we're going
+    match (arrived, leaving) {
+        (Up | Down, Up | Down) => '|',
+        (Left | Right, Left | Right) => '-',
+        (Up, Right) | (Left, Down) => 'F',
+        (Up, Left) | (Right, Down) => '7',
+        (Down, Right) | (Left, Up) => 'L',
+        (Down, Left) | (Right, Up) => 'J',
+    }
+}
+
+fn neighbours((row, col): (usize, usize)) -> [(usize, usize); 4] {
+    [
+        (row.wrapping_sub(1), col),
+        (row + 1, col),
+        (row, col.wrapping_sub(1)),
+        (row, col + 1),
+    ]
+}
+
+/// A maze roughly `size` x `size` tiles.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let size = size.max(3);
+    let pipes = Loop::random(rng, size - 1, size - 1);
+    let (height, width) = (pipes.height + 1, pipes.width + 1);
+    let mut tiles: Vec<Vec<char>> = (0..height)
+        .map(|_| (0..width).map(|_| *JUNK.choose(rng).unwrap()).collect())
+        .collect();
+    let points = pipes.points();
+    for &((row, col), arrived, leaving) in &points {
+        tiles[row][col] = pipe(arrived, leaving);
+    }
+
+    // only the loop either side of the start may look like it joins up with
+    // it, so that the start's own shape is unambiguous
+    let on_loop: HashSet<(usize, usize)> = points.iter().map(|&(p, ..)| p).collect();
+    let joins = |p: (usize, usize), pipes: &[char]| {
+        on_loop.contains(&p) && pipes.contains(&tiles[p.0][p.1])
+    };
+    let unambiguous = |&(row, col): &(usize, usize)| {
+        [
+            joins((row.wrapping_sub(1), col), &['|', '7', 'F']),
+            joins((row + 1, col), &['|', 'L', 'J']),
+            joins((row, col.wrapping_sub(1)), &['-', 'L', 'F']),
+            joins((row, col + 1), &['-', 'J', '7']),
+        ]
+        .into_iter()
+        .filter(|joins| *joins)
+        .count()
+            == 2
+    };
+    let candidates: Vec<(usize, usize)> = points
+        .iter()
+        .map(|&(p, ..)| p)
+        .filter(unambiguous)
+        .collect();
+    let (row, col) = *candidates.choose(rng).unwrap_or(&pipes.start);
+    tiles[row][col] = 'S';
+    for (r, c) in neighbours((row, col)) {
+        if r < height && c < width && !on_loop.contains(&(r, c)) {
+            tiles[r][c] = '.';
+        }
+    }
+
+    tiles
+        .into_iter()
+        .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+        .collect()
+}
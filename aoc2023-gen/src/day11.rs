@@ -0,0 +1,18 @@
+//! Telescope images: `#` galaxies on `.`, with some rows and columns empty.
+
+use rand::Rng;
+
+/// A `size` x `size` image.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let empty_rows: Vec<bool> = (0..size).map(|_| rng.gen_bool(0.1)).collect();
+    let empty_cols: Vec<bool> = (0..size).map(|_| rng.gen_bool(0.1)).collect();
+    let mut out = String::with_capacity((size + 1) * size);
+    for &empty_row in &empty_rows {
+        for &empty_col in &empty_cols {
+            let galaxy = !empty_row && !empty_col && rng.gen_bool(0.05);
+            out.push(if galaxy { '#' } else { '.' });
+        }
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,79 @@
+//! Spring condition records: `???.### 1,1,3`.
+
+use itertools::Itertools;
+use rand::{seq::index::sample, Rng};
+
+/// At most this many springs per record are unknown, which keeps the brute
+/// force count in [`generate_with_answer`] cheap.
+const MAX_UNKNOWN: usize = 12;
+
+fn groups(springs: &[bool]) -> Vec<usize> {
+    springs
+        .iter()
+        .group_by(|damaged| **damaged)
+        .into_iter()
+        .filter(|(damaged, _)| *damaged)
+        .map(|(_, run)| run.count())
+        .collect()
+}
+
+/// How many ways the unknowns in `record` can be filled in to give `sizes`.
+fn arrangements(record: &[Option<bool>], sizes: &[usize]) -> u64 {
+    let unknown: Vec<usize> = record.iter().positions(Option::is_none).collect();
+    let mut springs: Vec<bool> = record.iter().map(|s| s.unwrap_or(false)).collect();
+    (0..1u32 << unknown.len())
+        .filter(|mask| {
+            for (bit, &i) in unknown.iter().enumerate() {
+                springs[i] = mask & (1 << bit) != 0;
+            }
+            groups(&springs) == sizes
+        })
+        .count() as u64
+}
+
+/// `size` records, along with the part 1 answer for them.
+pub fn generate_with_answer(rng: &mut impl Rng, size: usize) -> (String, u64) {
+    let mut out = String::new();
+    let mut answer = 0;
+    for _ in 0..size {
+        let len = rng.gen_range(4..=20);
+        let mut springs: Vec<bool> = (0..len).map(|_| rng.gen_bool(0.5)).collect();
+        if !springs.contains(&true) {
+            springs[rng.gen_range(0..len)] = true;
+        }
+        let sizes = groups(&springs);
+        let mut record: Vec<Option<bool>> = springs.into_iter().map(Some).collect();
+        let unknown = rng.gen_range(1..=len.min(MAX_UNKNOWN));
+        for i in sample(rng, len, unknown) {
+            record[i] = None;
+        }
+        answer += arrangements(&record, &sizes);
+        let record: String = record
+            .into_iter()
+            .map(|s| match s {
+                Some(true) => '#',
+                Some(false) => '.',
+                None => '?',
+            })
+            .collect();
+        out.push_str(&format!("{record} {}\n", sizes.iter().join(",")));
+    }
+    (out, answer)
+}
+
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    generate_with_answer(rng, size).0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn known_answer() -> color_eyre::Result<()> {
+        let (input, answer) = generate_with_answer(&mut crate::rng(12), 20);
+        let solved = aoc2023_runner::puzzle(12, 1)?.solve(&input)?;
+        assert_eq!(solved, answer.to_string());
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+//! Mirror valleys: blank-line separated patterns of `#` and `.`.
+
+use rand::Rng;
+
+type Pattern = Vec<Vec<bool>>;
+
+fn transpose(pattern: &Pattern) -> Pattern {
+    (0..pattern[0].len())
+        .map(|c| pattern.iter().map(|row| row[c]).collect())
+        .collect()
+}
+
+/// How many cells differ across each horizontal line of reflection.
+fn smudges(pattern: &Pattern) -> Vec<(usize, usize)> {
+    (1..pattern.len())
+        .map(|i| {
+            let differing = (0..i.min(pattern.len() - i))
+                .map(|j| {
+                    let (above, below) = (&pattern[i - j - 1], &pattern[i + j]);
+                    above.iter().zip(below).filter(|(a, b)| a != b).count()
+                })
+                .sum();
+            (i, differing)
+        })
+        .collect()
+}
+
+/// Exactly one perfect reflection and exactly one off-by-one-cell reflection,
+/// across both orientations.
+fn valid(pattern: &Pattern) -> bool {
+    let lines: Vec<usize> = smudges(pattern)
+        .into_iter()
+        .chain(smudges(&transpose(pattern)))
+        .map(|(_, differing)| differing)
+        .collect();
+    lines.iter().filter(|d| **d == 0).count() == 1 && lines.iter().filter(|d| **d == 1).count() == 1
+}
+
+/// Mirror the rows of `pattern` below line `at`, as far as they'll go.
+fn reflect(pattern: &mut Pattern, at: usize) {
+    for j in 0..at.min(pattern.len() - at) {
+        pattern[at + j] = pattern[at - j - 1].clone();
+    }
+}
+
+fn pattern(rng: &mut impl Rng) -> Pattern {
+    loop {
+        let (height, width) = (rng.gen_range(5..=17), rng.gen_range(5..=17));
+        let mut pattern: Pattern = (0..height)
+            .map(|_| (0..width).map(|_| rng.gen_bool(0.5)).collect())
+            .collect();
+        // a perfect vertical reflection, then a horizontal one with a
+        // smudge, in a row the vertical reflection doesn't reach
+        let vertical = rng.gen_range(1..width);
+        let horizontal = rng.gen_range(1..height);
+        pattern = transpose(&pattern);
+        reflect(&mut pattern, vertical);
+        pattern = transpose(&pattern);
+        reflect(&mut pattern, horizontal);
+        let reach = vertical.min(width - vertical);
+        let unmatched: Vec<usize> = (0..width)
+            .filter(|c| *c < vertical - reach || *c >= vertical + reach)
+            .collect();
+        let mirrored = horizontal.min(height - horizontal);
+        if unmatched.is_empty() {
+            continue;
+        }
+        let col = unmatched[rng.gen_range(0..unmatched.len())];
+        let row = rng.gen_range(horizontal - mirrored..horizontal + mirrored);
+        pattern[row][col] = !pattern[row][col];
+        if rng.gen() {
+            pattern = transpose(&pattern);
+        }
+        if valid(&pattern) {
+            return pattern;
+        }
+    }
+}
+
+/// `size` patterns.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            pattern(rng)
+                .into_iter()
+                .map(|row| {
+                    let mut line: String =
+                        row.into_iter().map(|c| if c { '#' } else { '.' }).collect();
+                    line.push('\n');
+                    line
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,14 @@
+//! Reflector dishes: round `O` and cube `#` rocks on `.`.
+
+use rand::Rng;
+
+use crate::grid;
+
+/// A `size` x `size` platform.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    grid(rng, size, size, |rng| match rng.gen_range(0..10) {
+        0..=1 => 'O',
+        2 => '#',
+        _ => '.',
+    })
+}
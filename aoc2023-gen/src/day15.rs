@@ -0,0 +1,22 @@
+//! Initialization sequences: `rn=1,cm-,qp=3` on a single line.
+
+use itertools::Itertools;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::unique_words;
+
+/// `size` steps over a pool of labels, so that lenses get replaced and removed.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let labels = unique_words(rng, size / 4 + 1, 2, 6, &[]);
+    let steps = (0..size)
+        .map(|_| {
+            let label = labels.choose(rng).unwrap();
+            if rng.gen_bool(0.3) {
+                format!("{label}-")
+            } else {
+                format!("{label}={}", rng.gen_range(1..=9))
+            }
+        })
+        .join(",");
+    format!("{steps}\n")
+}
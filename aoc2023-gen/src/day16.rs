@@ -0,0 +1,16 @@
+//! Mirror contraptions: mirrors and splitters on `.`.
+
+use rand::Rng;
+
+use crate::grid;
+
+/// A `size` x `size` contraption.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    grid(rng, size, size, |rng| match rng.gen_range(0..20) {
+        0 => '/',
+        1 => '\\',
+        2 => '|',
+        3 => '-',
+        _ => '.',
+    })
+}
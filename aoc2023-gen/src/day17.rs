@@ -0,0 +1,10 @@
+//! Heat loss maps: a grid of digits 1-9.
+
+use rand::Rng;
+
+use crate::grid;
+
+/// A `size` x `size` map.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    grid(rng, size, size, |rng| rng.gen_range('1'..='9'))
+}
@@ -0,0 +1,56 @@
+//! Dig plans: `R 6 (#70c710)`, where both the plain and the hex
+//! instructions trace out a closed loop.
+
+use rand::Rng;
+
+use crate::shapes::{Heading, Loop};
+
+/// Cumulative positions of `len + 1` lattice lines, spaced up to `max` apart.
+fn spacing(rng: &mut impl Rng, len: usize, max: usize) -> Vec<usize> {
+    let mut at = vec![0];
+    for _ in 0..len {
+        at.push(at.last().unwrap() + rng.gen_range(1..=max));
+    }
+    at
+}
+
+/// One loop outline, with roughly `size` rows of turns, scaled up twice over.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let size = size.max(1);
+    let outline = Loop::random(rng, size, size);
+    let (height, width) = (outline.height, outline.width);
+    let small = (spacing(rng, height, 8), spacing(rng, width, 8));
+    let step = 0xfffff / height.max(width);
+    let large = (spacing(rng, height, step), spacing(rng, width, step));
+
+    let (mut row, mut col) = outline.start;
+    let mut out = String::new();
+    for &(heading, len) in &outline.runs {
+        let (from, to) = match heading {
+            Heading::Up => ((row, 0), (row - len, 0)),
+            Heading::Down => ((row, 0), (row + len, 0)),
+            Heading::Left => ((0, col), (0, col - len)),
+            Heading::Right => ((0, col), (0, col + len)),
+        };
+        let scaled = |(rows, cols): &(Vec<usize>, Vec<usize>)| match heading {
+            Heading::Up | Heading::Down => rows[from.0].abs_diff(rows[to.0]),
+            Heading::Left | Heading::Right => cols[from.1].abs_diff(cols[to.1]),
+        };
+        let (letter, digit) = match heading {
+            Heading::Right => ('R', 0),
+            Heading::Down => ('D', 1),
+            Heading::Left => ('L', 2),
+            Heading::Up => ('U', 3),
+        };
+        out.push_str(&format!(
+            "{letter} {} (#{:05x}{digit})\n",
+            scaled(&small),
+            scaled(&large)
+        ));
+        (row, col) = match heading {
+            Heading::Up | Heading::Down => (to.0, col),
+            Heading::Left | Heading::Right => (row, to.1),
+        };
+    }
+    out
+}
@@ -0,0 +1,52 @@
+//! Part sorting systems: workflows, a blank line, then part ratings.
+
+use itertools::Itertools;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::unique_words;
+
+const CATEGORIES: [char; 4] = ['x', 'm', 'a', 's'];
+
+/// `size` workflows and `size` parts.
+///
+/// Workflows form a tree rooted at `in`, so every part is eventually
+/// accepted or rejected.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let size = size.max(1);
+    let mut names = vec!["in".to_string()];
+    names.extend(unique_words(rng, size - 1, 2, 3, &["in"]));
+
+    // each workflow after the first hangs off a random earlier one
+    let mut children: Vec<Vec<&str>> = vec![Vec::new(); size];
+    for (i, name) in names.iter().enumerate().skip(1) {
+        children[rng.gen_range(0..i)].push(name);
+    }
+
+    let mut out = String::new();
+    for (name, children) in names.iter().zip(children) {
+        let rules = (children.len() + 1).max(rng.gen_range(2..=4));
+        let mut targets: Vec<String> = children.into_iter().map(String::from).collect();
+        while targets.len() < rules {
+            targets.push(if rng.gen() { "A" } else { "R" }.to_string());
+        }
+        targets.shuffle(rng);
+        let fallback = targets.pop().unwrap();
+        let conditions = targets
+            .into_iter()
+            .map(|target| {
+                let category = CATEGORIES.choose(rng).unwrap();
+                let operator = if rng.gen() { '<' } else { '>' };
+                format!("{category}{operator}{}:{target}", rng.gen_range(1..4000))
+            })
+            .chain([fallback])
+            .join(",");
+        out.push_str(&format!("{name}{{{conditions}}}\n"));
+    }
+
+    out.push('\n');
+    for _ in 0..size {
+        let [x, m, a, s] = [(); 4].map(|_| rng.gen_range(1..=4000));
+        out.push_str(&format!("{{x={x},m={m},a={a},s={s}}}\n"));
+    }
+    out
+}
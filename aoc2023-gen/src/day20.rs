@@ -0,0 +1,56 @@
+//! Pulse propagation circuits: `%a -> b, c`, `&inv -> a`, `broadcaster -> a`.
+
+use itertools::Itertools;
+use rand::{seq::index::sample, Rng};
+
+use crate::unique_words;
+
+/// Counters modelled on the real inputs: the broadcaster feeds up to four
+/// chains of `size` flip-flops, each watched by a conjunction that feeds
+/// back into its chain and, through an inverter, into a final conjunction
+/// driving `rx`.
+pub fn generate(rng: &mut impl Rng, size: usize) -> String {
+    let size = size.max(2);
+    let chains = rng.gen_range(1..=4);
+    let mut names = unique_words(rng, chains * (size + 2) + 1, 2, 3, &["rx"]).into_iter();
+    let collector = names.next().unwrap();
+
+    let mut lines = Vec::new();
+    let mut starts = Vec::new();
+    for _ in 0..chains {
+        let flipflops: Vec<String> = names.by_ref().take(size).collect();
+        let (watcher, inverter) = (names.next().unwrap(), names.next().unwrap());
+        starts.push(flipflops[0].clone());
+
+        // the watcher sees some bits and sets the rest, so the counter
+        // resets at a particular value
+        let watched: Vec<bool> = (0..size).map(|_| rng.gen_bool(0.5)).collect();
+        let mut resets = vec![inverter.clone()];
+        for (i, flipflop) in flipflops.iter().enumerate() {
+            let mut outputs = Vec::new();
+            if let Some(next) = flipflops.get(i + 1) {
+                outputs.push(next.as_str());
+            }
+            if watched[i] || i == size - 1 {
+                outputs.push(&watcher);
+            } else {
+                resets.push(flipflop.clone());
+            }
+            lines.push(format!("%{flipflop} -> {}", outputs.join(", ")));
+        }
+        resets.push(flipflops[0].clone());
+        lines.push(format!(
+            "&{watcher} -> {}",
+            resets.iter().unique().join(", ")
+        ));
+        lines.push(format!("&{inverter} -> {collector}"));
+    }
+    lines.push(format!("&{collector} -> rx"));
+    lines.push(format!("broadcaster -> {}", starts.join(", ")));
+
+    let order = sample(rng, lines.len(), lines.len());
+    order
+        .into_iter()
+        .map(|i| format!("{}\n", lines[i]))
+        .collect()
+}
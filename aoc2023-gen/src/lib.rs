@@ -0,0 +1,136 @@
+//! Seeded generators for well-formed puzzle inputs of any size.
+//!
+//! Every day has a module with a `generate(rng, size)` function. What `size`
+//! means depends on the format: lines for line-oriented days, the side length
+//! for grids, and so on. The same seed and size always give the same input.
+
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+mod shapes;
+
+pub fn rng(seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+/// Generate an input for `day`, or `None` if there's no generator for it.
+pub fn generate(day: u8, seed: u64, size: usize) -> Option<String> {
+    let rng = &mut rng(seed);
+    let input = match day {
+        1 => day01::generate(rng, size),
+        2 => day02::generate(rng, size),
+        3 => day03::generate(rng, size),
+        4 => day04::generate(rng, size),
+        5 => day05::generate(rng, size),
+        6 => day06::generate(rng, size),
+        7 => day07::generate(rng, size),
+        8 => day08::generate(rng, size),
+        9 => day09::generate(rng, size),
+        10 => day10::generate(rng, size),
+        11 => day11::generate(rng, size),
+        12 => day12::generate(rng, size),
+        13 => day13::generate(rng, size),
+        14 => day14::generate(rng, size),
+        15 => day15::generate(rng, size),
+        16 => day16::generate(rng, size),
+        17 => day17::generate(rng, size),
+        18 => day18::generate(rng, size),
+        19 => day19::generate(rng, size),
+        20 => day20::generate(rng, size),
+        _ => return None,
+    };
+    Some(input)
+}
+
+/// A grid of `height` rows of `width` characters each picked by `cell`.
+fn grid<R: Rng>(
+    rng: &mut R,
+    width: usize,
+    height: usize,
+    mut cell: impl FnMut(&mut R) -> char,
+) -> String {
+    let mut out = String::with_capacity((width + 1) * height);
+    for _ in 0..height {
+        out.extend((0..width).map(|_| cell(rng)));
+        out.push('\n');
+    }
+    out
+}
+
+/// A lowercase name, between `min` and `max` letters long.
+fn word(rng: &mut impl Rng, min: usize, max: usize) -> String {
+    let len = rng.gen_range(min..=max);
+    (0..len).map(|_| rng.gen_range('a'..='z')).collect()
+}
+
+/// `count` distinct names, none of which are in `reserved`.
+fn unique_words(
+    rng: &mut impl Rng,
+    count: usize,
+    min: usize,
+    max: usize,
+    reserved: &[&str],
+) -> Vec<String> {
+    let mut seen = std::collections::HashSet::<String>::new();
+    seen.extend(reserved.iter().map(|s| s.to_string()));
+    let mut words = Vec::with_capacity(count);
+    while words.len() < count {
+        let w = word(rng, min, max);
+        if seen.insert(w.clone()) {
+            words.push(w);
+        }
+    }
+    words.shuffle(rng);
+    words
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc2023_runner::PUZZLES;
+
+    #[test]
+    fn deterministic() {
+        for day in 1..=20 {
+            assert_eq!(generate(day, 7, 8), generate(day, 7, 8), "day {day}");
+        }
+        assert_ne!(generate(17, 1, 8), generate(17, 2, 8));
+        assert_eq!(generate(21, 0, 8), None);
+    }
+
+    /// Every generated input should be accepted and solved by the real solvers.
+    #[test]
+    fn solvable() {
+        for seed in 0..3 {
+            for puzzle in PUZZLES {
+                let input = generate(puzzle.day, seed, 6).unwrap();
+                if let Err(err) = puzzle.solve(&input) {
+                    panic!(
+                        "day {} part {} seed {seed}: {err:?}\n{input}",
+                        puzzle.day, puzzle.part
+                    );
+                }
+            }
+        }
+    }
+}
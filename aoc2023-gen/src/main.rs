@@ -0,0 +1,22 @@
+use aoc2023_gen::generate;
+use clap::Parser;
+use color_eyre::{eyre::eyre, Result};
+
+#[derive(Parser)]
+#[command(about = "Generate synthetic Advent of Code 2023 inputs")]
+struct Cli {
+    day: u8,
+    /// Scale of the input: lines, grid side length, etc. depending on the day
+    #[arg(short, long, default_value_t = 100)]
+    size: usize,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let Cli { day, size, seed } = Cli::parse();
+    let input = generate(day, seed, size).ok_or(eyre!("No generator for day {day}"))?;
+    print!("{input}");
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heading {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+/// A simple closed rectilinear loop, walked clockwise from `start`.
+///
+/// Built as the outline of a stack of overlapping row intervals, which can
+/// have neither holes nor pinch points, so the outline never touches itself.
+/// Consecutive runs always turn.
+#[derive(Debug)]
+pub struct Loop {
+    /// `(row, col)` of the first corner on the lattice of cell corners.
+    pub start: (usize, usize),
+    pub runs: Vec<(Heading, usize)>,
+    pub height: usize,
+    pub width: usize,
+}
+
+impl Loop {
+    /// A loop fitting inside a `height` x `width` lattice of cells.
+    pub fn random(rng: &mut impl Rng, height: usize, width: usize) -> Self {
+        let (height, width) = (height.max(1), width.max(2));
+        let mut rows: Vec<(usize, usize)> = Vec::with_capacity(height);
+        let mut prev = (0, width);
+        for _ in 0..height {
+            // overlap the previous row by at least one cell
+            let start = rng.gen_range(0..prev.1);
+            let end = rng.gen_range(start.max(prev.0) + 1..=width);
+            rows.push((start, end));
+            prev = (start, end);
+        }
+
+        let mut corners = vec![(0, rows[0].0), (0, rows[0].1)];
+        for (r, &(_, end)) in rows.iter().enumerate() {
+            corners.push((r + 1, end));
+            if let Some(&(_, next)) = rows.get(r + 1) {
+                corners.push((r + 1, next));
+            }
+        }
+        for (r, &(start, _)) in rows.iter().enumerate().rev() {
+            corners.push((r + 1, start));
+            corners.push((r, start));
+        }
+
+        let mut runs: Vec<(Heading, usize)> = Vec::new();
+        for pair in corners.windows(2) {
+            let ((r0, c0), (r1, c1)) = (pair[0], pair[1]);
+            let run = match (r0.cmp(&r1), c0.cmp(&c1)) {
+                (std::cmp::Ordering::Less, _) => (Heading::Down, r1 - r0),
+                (std::cmp::Ordering::Greater, _) => (Heading::Up, r0 - r1),
+                (_, std::cmp::Ordering::Less) => (Heading::Right, c1 - c0),
+                (_, std::cmp::Ordering::Greater) => (Heading::Left, c0 - c1),
+                _ => continue,
+            };
+            match runs.last_mut() {
+                Some((heading, len)) if *heading == run.0 => *len += run.1,
+                _ => runs.push(run),
+            }
+        }
+        // the walk ends where it started, heading up into the first corner
+        Self {
+            start: corners[0],
+            runs,
+            height,
+            width,
+        }
+    }
+
+    /// Every lattice point on the loop, with the heading used to arrive at
+    /// it and the heading used to leave.
+    pub fn points(&self) -> Vec<((usize, usize), Heading, Heading)> {
+        let mut steps = Vec::new();
+        for &(heading, len) in &self.runs {
+            steps.extend(std::iter::repeat_n(heading, len));
+        }
+        let mut points = Vec::with_capacity(steps.len());
+        let (mut row, mut col) = self.start;
+        let mut arrived = *steps.last().unwrap();
+        for &heading in &steps {
+            points.push(((row, col), arrived, heading));
+            match heading {
+                Heading::Up => row -= 1,
+                Heading::Right => col += 1,
+                Heading::Down => row += 1,
+                Heading::Left => col -= 1,
+            }
+            arrived = heading;
+        }
+        points
+    }
+}
@@ -0,0 +1,446 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GridError {
+    #[error("Row {row} has {found} cells, expected {expected}")]
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("{len} cells cannot be laid out in rows of {width}")]
+    Shape { len: usize, width: usize },
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+}
+
+/// A rectangular board stored row-major in a flat `Vec`.
+///
+/// Cells are addressed either by `(row, col)` or by their flat index
+/// `row * width + col`.
+#[derive(Debug, Clone, Default, Hash, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+}
+
+impl Grid<char> {
+    pub fn from_lines<S: AsRef<str>>(
+        lines: impl IntoIterator<Item = S>,
+    ) -> Result<Self, GridError> {
+        Self::parse_lines(lines, |c| c)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Build a grid from lines of text, mapping each character to a cell.
+    pub fn parse_lines<S: AsRef<str>>(
+        lines: impl IntoIterator<Item = S>,
+        mut f: impl FnMut(char) -> T,
+    ) -> Result<Self, GridError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        for (row, line) in lines.into_iter().enumerate() {
+            let before = cells.len();
+            cells.extend(line.as_ref().chars().map(&mut f));
+            let found = cells.len() - before;
+            match width {
+                None => width = Some(found),
+                Some(expected) if expected != found => {
+                    return Err(GridError::Ragged {
+                        row,
+                        expected,
+                        found,
+                    })
+                }
+                _ => {}
+            }
+        }
+        Ok(Self {
+            cells,
+            width: width.unwrap_or_default(),
+        })
+    }
+
+    pub fn from_vec(cells: Vec<T>, width: usize) -> Result<Self, GridError> {
+        if !cells.len().is_multiple_of(width) {
+            return Err(GridError::Shape {
+                len: cells.len(),
+                width,
+            });
+        }
+        Ok(Self { cells, width })
+    }
+
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![fill; width * height],
+            width,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len().checked_div(self.width).unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn index_of(&self, (row, col): (usize, usize)) -> Option<usize> {
+        (row < self.height() && col < self.width).then_some(row * self.width + col)
+    }
+
+    pub fn coords_of(&self, idx: usize) -> Option<(usize, usize)> {
+        (idx < self.cells.len()).then(|| (idx / self.width, idx % self.width))
+    }
+
+    pub fn get(&self, coords: (usize, usize)) -> Option<&T> {
+        self.index_of(coords).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, coords: (usize, usize)) -> Option<&mut T> {
+        self.index_of(coords).map(|idx| &mut self.cells[idx])
+    }
+
+    pub fn get_index(&self, idx: usize) -> Option<&T> {
+        self.cells.get(idx)
+    }
+
+    pub fn get_index_mut(&mut self, idx: usize) -> Option<&mut T> {
+        self.cells.get_mut(idx)
+    }
+
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.cells.swap(a, b);
+    }
+
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        self.cells.fill(value);
+    }
+
+    /// The cell one step away in `direction`, if it is on the grid.
+    pub fn step(&self, (row, col): (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        let next = match direction {
+            Direction::North => (row.checked_sub(1)?, col),
+            Direction::East => (row, col + 1),
+            Direction::South => (row + 1, col),
+            Direction::West => (row, col.checked_sub(1)?),
+        };
+        self.index_of(next).map(|_| next)
+    }
+
+    /// Orthogonal neighbours, in `Direction::ALL` order.
+    pub fn neighbours4(
+        &self,
+        coords: (usize, usize),
+    ) -> impl Iterator<Item = (Direction, (usize, usize))> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |d| self.step(coords, d).map(|c| (d, c)))
+    }
+
+    /// Orthogonal and diagonal neighbours, clockwise from north-west.
+    pub fn neighbours8(
+        &self,
+        (row, col): (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        const OFFSETS: [(isize, isize); 8] = [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+            (1, 0),
+            (1, -1),
+            (0, -1),
+        ];
+        OFFSETS.into_iter().filter_map(move |(dr, dc)| {
+            let next = (row.checked_add_signed(dr)?, col.checked_add_signed(dc)?);
+            self.index_of(next).map(|_| next)
+        })
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height()).map(|row| self.row(row))
+    }
+
+    /// The cells of column `col`, top to bottom, or none if it is off the
+    /// grid.
+    pub fn col(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        // past the last column, stepping from `col` would wander into others
+        let from = if col < self.width {
+            col
+        } else {
+            self.cells.len()
+        };
+        let cells: &[T] = self.cells.get(from..).unwrap_or_default();
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    pub fn cols(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(|col| self.col(col))
+    }
+
+    /// Cells running down and to the right from `(row, col)`.
+    pub fn diagonal(&self, (row, col): (usize, usize)) -> impl Iterator<Item = &T> + '_ {
+        (0..).map_while(move |i| self.get((row + i, col + i)))
+    }
+
+    /// Cells running down and to the left from `(row, col)`.
+    pub fn anti_diagonal(&self, (row, col): (usize, usize)) -> impl Iterator<Item = &T> + '_ {
+        (0..=col).map_while(move |i| self.get((row + i, col - i)))
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    /// Every cell alongside its `(row, col)`.
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(idx, cell)| ((idx / self.width, idx % self.width), cell))
+    }
+
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .and_then(|idx| self.coords_of(idx))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+        }
+    }
+
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height();
+        Self {
+            cells: (0..self.width)
+                .flat_map(|col| self.col(col).cloned())
+                .collect(),
+            width: height,
+        }
+    }
+
+    /// Rotate a quarter turn clockwise, so the west edge becomes the north edge.
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height();
+        Self {
+            cells: (0..self.width)
+                .flat_map(|col| self.col(col).rev().cloned())
+                .collect(),
+            width: height,
+        }
+    }
+
+    /// Rotate a quarter turn anticlockwise, so the east edge becomes the north edge.
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height();
+        Self {
+            cells: (0..self.width)
+                .rev()
+                .flat_map(|col| self.col(col).cloned())
+                .collect(),
+            width: height,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        assert!(col < self.width, "column {col} out of bounds");
+        &self.cells[row * self.width + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        assert!(col < self.width, "column {col} out of bounds");
+        &mut self.cells[row * self.width + col]
+    }
+}
+
+impl<T> Index<usize> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.cells[idx]
+    }
+}
+
+impl<T> IndexMut<usize> for Grid<T> {
+    fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+        &mut self.cells[idx]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+
+    fn example() -> Grid<char> {
+        Grid::from_lines(["abc", "def"]).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let grid = example();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid[4], 'e');
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(
+            Grid::from_lines(["abc", "de"]),
+            Err(GridError::Ragged {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            Grid::parse_lines(["12", "34"], |c| c.to_digit(10).unwrap())
+                .unwrap()
+                .iter()
+                .sum::<u32>(),
+            10
+        );
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = example();
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            vec![(Direction::East, (0, 1)), (Direction::South, (1, 0))]
+        );
+        assert_eq!(
+            grid.neighbours8((1, 1)).collect::<Vec<_>>(),
+            vec![(0, 0), (0, 1), (0, 2), (1, 2), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn lines() {
+        let grid = example();
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.col(1).collect::<String>(), "be");
+        assert_eq!(grid.col(2).rev().collect::<String>(), "fc");
+        assert_eq!(grid.col(3).count(), 0);
+        assert_eq!(grid.col(7).count(), 0);
+        assert_eq!(
+            grid.cols()
+                .map(|c| c.collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["ad", "be", "cf"]
+        );
+        assert_eq!(grid.diagonal((0, 1)).collect::<String>(), "bf");
+        assert_eq!(grid.anti_diagonal((0, 1)).collect::<String>(), "bd");
+    }
+
+    #[test]
+    fn transforms() {
+        let grid = example();
+        assert_eq!(
+            grid.transpose().to_string(),
+            indoc! {"
+                ad
+                be
+                cf
+            "}
+        );
+        assert_eq!(
+            grid.rotate_cw().to_string(),
+            indoc! {"
+                da
+                eb
+                fc
+            "}
+        );
+        assert_eq!(
+            grid.rotate_ccw().to_string(),
+            indoc! {"
+                cf
+                be
+                ad
+            "}
+        );
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+    }
+}
@@ -0,0 +1,273 @@
+//! `#[derive(Parse)]` for `aoc2023_parse::Parse`; the attributes are
+//! documented on the re-export in that crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Error, Fields,
+    GenericArgument, LitStr, Path, PathArguments, Result, Type,
+};
+
+#[proc_macro_derive(Parse, attributes(parse))]
+pub fn derive_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+const CONTAINER: &[&str] = &["prefix", "suffix", "separator", "cut"];
+const VARIANT: &[&str] = &["tag", "suffix", "separator", "cut"];
+const FIELD: &[&str] = &["prefix", "suffix", "separator", "with", "skip", "nonempty"];
+
+#[derive(Default)]
+struct Attrs {
+    prefix: Option<LitStr>,
+    suffix: Option<LitStr>,
+    separator: Option<LitStr>,
+    with: Option<Path>,
+    cut: bool,
+    skip: bool,
+    nonempty: bool,
+}
+
+impl Attrs {
+    /// Collect `#[parse(...)]` options, rejecting any not in `allowed`.
+    fn from(attrs: &[Attribute], allowed: &[&str]) -> Result<Self> {
+        let mut out = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("parse")) {
+            attr.parse_nested_meta(|meta| {
+                let key = meta
+                    .path
+                    .get_ident()
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                if !allowed.contains(&key.as_str()) {
+                    return Err(meta.error(format!("expected one of {} here", allowed.join(", "))));
+                }
+                match key.as_str() {
+                    // an enum variant's tag is just its prefix
+                    "prefix" | "tag" => out.prefix = Some(meta.value()?.parse()?),
+                    "suffix" => out.suffix = Some(meta.value()?.parse()?),
+                    "separator" => out.separator = Some(meta.value()?.parse()?),
+                    "with" => out.with = Some(meta.value()?.parse()?),
+                    "cut" => out.cut = true,
+                    "skip" => out.skip = true,
+                    "nonempty" => out.nonempty = true,
+                    _ => unreachable!(),
+                }
+                Ok(())
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "Parse can't be derived for generic types",
+        ));
+    }
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let attrs = Attrs::from(&input.attrs, CONTAINER)?;
+            let sequence = sequence(name, quote!(#name), &data.fields, &attrs)?;
+            quote!(#sequence body(input))
+        }
+        Data::Enum(data) => {
+            Attrs::from(&input.attrs, &[])?;
+            let mut variants = Vec::new();
+            for variant in &data.variants {
+                let attrs = Attrs::from(&variant.attrs, VARIANT)?;
+                let ident = &variant.ident;
+                let sequence = sequence(name, quote!(#name::#ident), &variant.fields, &attrs)?;
+                variants.push(quote!({ #sequence body }));
+            }
+            let Some(last) = variants.pop() else {
+                return Err(Error::new(
+                    name.span(),
+                    "Parse can't be derived for empty enums",
+                ));
+            };
+            // like `alt`: first success or hard failure wins, otherwise the
+            // last variant's error is reported
+            quote! {
+                #(
+                    match (#variants)(input) {
+                        ::core::result::Result::Err(::aoc2023_parse::nom::Err::Error(_)) => {}
+                        result => return result,
+                    }
+                )*
+                (#last)(input)
+            }
+        }
+        Data::Union(_) => return Err(Error::new(name.span(), "Parse can't be derived for unions")),
+    };
+    Ok(quote! {
+        impl ::aoc2023_parse::Parse for #name {
+            fn parse(input: &str) -> ::aoc2023_parse::nom::IResult<&str, Self> {
+                #body
+            }
+        }
+    })
+}
+
+/// An inner `fn body` parsing `fields` in order into `constructor`, wrapped
+/// in the prefix, separators and suffix from `attrs`.
+fn sequence(
+    name: &syn::Ident,
+    constructor: TokenStream2,
+    fields: &Fields,
+    attrs: &Attrs,
+) -> Result<TokenStream2> {
+    let mut steps = Vec::new();
+    let mut vars = Vec::new();
+    let mut parsed_any = false;
+    for (i, field) in fields.iter().enumerate() {
+        let field_attrs = Attrs::from(&field.attrs, FIELD)?;
+        let var = format_ident!("field_{i}");
+        if field_attrs.skip {
+            steps.push(quote!(let #var = ::core::default::Default::default();));
+        } else {
+            if let (true, Some(separator)) = (parsed_any, &attrs.separator) {
+                let separator = literal(separator);
+                steps.push(quote!(let (rest, _) = (#separator)(rest)?;));
+            }
+            let parser = field_parser(&field.ty, &field_attrs)?;
+            steps.push(quote!(let (rest, #var) = (#parser)(rest)?;));
+            parsed_any = true;
+        }
+        vars.push(var);
+    }
+    if let Some(suffix) = &attrs.suffix {
+        let suffix = literal(suffix);
+        steps.push(quote!(let (rest, _) = (#suffix)(rest)?;));
+    }
+    let value = match fields {
+        Fields::Named(named) => {
+            let idents = named.named.iter().map(|f| &f.ident);
+            quote!(#constructor { #(#idents: #vars),* })
+        }
+        Fields::Unnamed(_) => quote!(#constructor(#(#vars),*)),
+        Fields::Unit => constructor,
+    };
+
+    let mut body = quote!(inner);
+    if attrs.cut {
+        body = quote!(::aoc2023_parse::nom::combinator::cut(#body));
+    }
+    if let Some(prefix) = &attrs.prefix {
+        let prefix = literal(prefix);
+        body = quote!(::aoc2023_parse::nom::sequence::preceded(#prefix, #body));
+    }
+    Ok(quote! {
+        fn inner(rest: &str) -> ::aoc2023_parse::nom::IResult<&str, #name> {
+            #(#steps)*
+            ::core::result::Result::Ok((rest, #value))
+        }
+        fn body(input: &str) -> ::aoc2023_parse::nom::IResult<&str, #name> {
+            #body(input)
+        }
+    })
+}
+
+/// The parser for one field, honouring `Option` and `Vec` fields.
+///
+/// An optional field's prefix and suffix are part of what's optional, while
+/// a list's wrap the whole list.
+fn field_parser(ty: &Type, attrs: &Attrs) -> Result<TokenStream2> {
+    if let Some(inner) = type_argument(ty, "Option") {
+        reject_list_options(ty, attrs)?;
+        let parser = wrap(element(inner, attrs), attrs);
+        return Ok(quote!(::aoc2023_parse::nom::combinator::opt(#parser)));
+    }
+    let Some(inner) = type_argument(ty, "Vec") else {
+        reject_list_options(ty, attrs)?;
+        return Ok(wrap(element(ty, attrs), attrs));
+    };
+    let element = element(inner, attrs);
+    let list = match (&attrs.separator, attrs.nonempty) {
+        (Some(separator), false) => {
+            let separator = literal(separator);
+            quote!(::aoc2023_parse::nom::multi::separated_list0(#separator, #element))
+        }
+        (Some(separator), true) => {
+            let separator = literal(separator);
+            quote!(::aoc2023_parse::nom::multi::separated_list1(#separator, #element))
+        }
+        (None, false) => quote!(::aoc2023_parse::nom::multi::many0(#element)),
+        (None, true) => quote!(::aoc2023_parse::nom::multi::many1(#element)),
+    };
+    Ok(wrap(list, attrs))
+}
+
+fn reject_list_options(ty: &Type, attrs: &Attrs) -> Result<()> {
+    if attrs.separator.is_some() || attrs.nonempty {
+        return Err(Error::new(
+            ty.span(),
+            "`separator` and `nonempty` only apply to Vec fields",
+        ));
+    }
+    Ok(())
+}
+
+/// `Ty::parse`, or the `with` parser converted into `Ty`.
+fn element(ty: &Type, attrs: &Attrs) -> TokenStream2 {
+    match &attrs.with {
+        Some(with) => quote! {
+            ::aoc2023_parse::nom::combinator::map(#with, |o| -> #ty { ::core::convert::Into::into(o) })
+        },
+        None => quote!(<#ty as ::aoc2023_parse::Parse>::parse),
+    }
+}
+
+fn wrap(parser: TokenStream2, attrs: &Attrs) -> TokenStream2 {
+    match (&attrs.prefix, &attrs.suffix) {
+        (None, None) => parser,
+        (Some(prefix), None) => {
+            let prefix = literal(prefix);
+            quote!(::aoc2023_parse::nom::sequence::preceded(#prefix, #parser))
+        }
+        (None, Some(suffix)) => {
+            let suffix = literal(suffix);
+            quote!(::aoc2023_parse::nom::sequence::terminated(#parser, #suffix))
+        }
+        (Some(prefix), Some(suffix)) => {
+            let (prefix, suffix) = (literal(prefix), literal(suffix));
+            quote!(::aoc2023_parse::nom::sequence::delimited(#prefix, #parser, #suffix))
+        }
+    }
+}
+
+/// Single characters become `char` so errors read the same as hand-written
+/// parsers; anything longer is a `tag`.
+fn literal(lit: &LitStr) -> TokenStream2 {
+    let value = lit.value();
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => quote!(::aoc2023_parse::nom::character::complete::char(#c)),
+        _ => quote!(::aoc2023_parse::nom::bytes::complete::tag(#lit)),
+    }
+}
+
+/// `T` if `ty` is written as `wrapper<T>`.
+fn type_argument<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
@@ -0,0 +1,316 @@
+use std::{fmt, str::FromStr};
+
+use nom::{error::ErrorKind, IResult};
+use thiserror::Error;
+
+// lets the derive's `::aoc2023_parse` paths resolve in our own tests
+extern crate self as aoc2023_parse;
+
+pub use nom;
+
+/// A type with one canonical nom parser.
+pub trait Parse: Sized {
+    fn parse(input: &str) -> IResult<&str, Self>;
+}
+
+/// Derive [`Parse`] by parsing fields in declaration order.
+///
+/// Structs, and each enum variant, are a sequence of fields, configured
+/// with `#[parse(...)]`:
+///
+/// - on a struct: `prefix = "{"`, `suffix = "}"`, `separator = ","` between
+///   fields, and `cut` to make any failure after the prefix a hard error
+/// - on an enum variant: `tag = ">"` in place of `prefix`, otherwise as for
+///   structs. Variants are tried in order, like `alt`
+/// - on a field: `prefix`, `suffix`, `with = path::to::parser` (whose output
+///   is converted with `Into`), and `skip` to use `Default` instead
+///
+/// `Option<T>` fields are optional along with their prefix and suffix.
+/// `Vec<T>` fields are lists, of `T` separated by a field-level `separator`
+/// if given, and `nonempty` to require at least one.
+///
+/// ```
+/// use aoc2023_parse::Parse;
+///
+/// #[derive(Parse)]
+/// enum Sign {
+///     #[parse(tag = "+")]
+///     Plus,
+///     #[parse(tag = "-")]
+///     Minus,
+/// }
+///
+/// #[derive(Parse)]
+/// #[parse(prefix = "[", suffix = "]")]
+/// struct Signs(#[parse(separator = ",")] Vec<Sign>);
+///
+/// let (_, Signs(signs)) = Signs::parse("[+,-,+]").unwrap();
+/// assert!(matches!(signs[..], [Sign::Plus, Sign::Minus, Sign::Plus]));
+/// ```
+pub use aoc2023_parse_derive::Parse;
+
+macro_rules! parse_integers {
+    ($($int:ident),*) => {
+        $(
+            impl Parse for $int {
+                fn parse(input: &str) -> IResult<&str, Self> {
+                    nom::character::complete::$int(input)
+                }
+            }
+        )*
+    };
+}
+
+parse_integers!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+/// A parse failure pinned to a position in the puzzle input.
+///
+/// Renders as a one-line summary followed by the offending line with a
+/// caret under the column where parsing gave up.
+#[derive(Error, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    column: usize,
+    expected: String,
+    source_line: String,
+}
+
+impl ParseError {
+    /// Error at byte `offset` into `input`, which may span several lines.
+    pub fn at(input: &str, offset: usize, expected: impl Into<String>) -> Self {
+        let offset = offset.min(input.len());
+        let consumed = &input[..offset];
+        let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        Self {
+            line: consumed.matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            expected: expected.into(),
+            source_line: input[line_start..line_end].to_string(),
+        }
+    }
+
+    /// Error at the point where `rest`, a suffix of `input`, begins.
+    pub fn new(input: &str, rest: &str, expected: impl Into<String>) -> Self {
+        Self::at(input, input.len() - rest.len(), expected)
+    }
+
+    pub fn from_nom(input: &str, err: nom::Err<nom::error::Error<&str>>) -> Self {
+        match err {
+            nom::Err::Incomplete(_) => Self::at(input, input.len(), "more input"),
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                Self::new(input, e.input, describe(e.code))
+            }
+        }
+    }
+
+    /// Move the error onto `line` of a larger input, for parsers which only
+    /// ever see one line at a time.
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line += line.saturating_sub(1);
+        self
+    }
+
+    /// Replace nom's generic description with something more specific.
+    pub fn expecting(mut self, expected: impl Into<String>) -> Self {
+        self.expected = expected.into();
+        self
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = self.line.to_string();
+        let pad = " ".repeat(gutter.len());
+        writeln!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+        writeln!(f, "{pad} |")?;
+        writeln!(f, "{gutter} | {}", self.source_line)?;
+        write!(f, "{pad} | {}^", " ".repeat(self.column - 1))
+    }
+}
+
+// `unwrap` and `color_eyre` reach for `Debug`, and the caret is the useful bit.
+impl fmt::Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+fn describe(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Tag => "a fixed token".into(),
+        ErrorKind::Char => "a fixed character".into(),
+        ErrorKind::Digit => "a number".into(),
+        ErrorKind::Alpha => "a name".into(),
+        ErrorKind::AlphaNumeric => "a name or number".into(),
+        ErrorKind::Space | ErrorKind::MultiSpace => "whitespace".into(),
+        ErrorKind::Eof => "end of input".into(),
+        ErrorKind::MapRes | ErrorKind::MapOpt => "a recognised token".into(),
+        kind => kind.description().to_lowercase(),
+    }
+}
+
+/// Unwrap the result of running a nom parser over all of `input`.
+///
+/// Anything left over other than trailing whitespace is an error.
+pub fn finish<'a, O>(input: &'a str, result: IResult<&'a str, O>) -> Result<O, ParseError> {
+    match result {
+        Ok((rest, output)) if rest.trim_end().is_empty() => Ok(output),
+        Ok((rest, _)) => Err(ParseError::new(input, rest, "end of input")),
+        Err(err) => Err(ParseError::from_nom(input, err)),
+    }
+}
+
+/// Parse the line at `index` (0-based, as from `enumerate`) of a puzzle file.
+pub fn parse_line<T: FromStr<Err = ParseError>>(index: usize, line: &str) -> Result<T, ParseError> {
+    line.parse().map_err(|e: ParseError| e.at_line(index + 1))
+}
+
+/// Parse every line of a puzzle file as a `T`.
+pub fn parse_lines<T: FromStr<Err = ParseError>>(
+    lines: impl IntoIterator<Item = impl AsRef<str>>,
+) -> Result<Vec<T>, ParseError> {
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| parse_line(i, line.as_ref()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{alpha1, u32},
+        sequence::preceded,
+    };
+
+    #[derive(Debug)]
+    struct Id(u32);
+
+    impl FromStr for Id {
+        type Err = ParseError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            finish(s, preceded(tag("id "), u32)(s)).map(Id)
+        }
+    }
+
+    #[test]
+    fn caret() {
+        let err = "id x1".parse::<Id>().unwrap_err();
+        assert_eq!((err.line(), err.column()), (1, 4));
+        assert_eq!(
+            err.to_string(),
+            indoc! {"
+                line 1, column 4: expected a number
+                  |
+                1 | id x1
+                  |    ^"}
+        );
+    }
+
+    #[test]
+    fn trailing_input() {
+        assert_eq!("id 7\n".parse::<Id>().map(|id| id.0), Ok(7));
+        let err = "id 7!".parse::<Id>().unwrap_err();
+        assert_eq!((err.column(), err.expected()), (5, "end of input"));
+    }
+
+    #[test]
+    fn line_numbers() {
+        let err = parse_lines::<Id>("id 1\nid 2\nid3\n".lines()).unwrap_err();
+        assert_eq!((err.line(), err.column()), (3, 1));
+        assert_eq!(err.expected(), "a fixed token");
+        let ids = parse_lines::<Id>("id 1\nid 2".lines()).unwrap();
+        assert_eq!(ids.iter().map(|id| id.0).collect::<Vec<_>>(), [1, 2]);
+        let err = parse_lines::<Id>(vec!["id 1".to_string(), "x".to_string()]).unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 1));
+
+        let err = ParseError::at("ab\ncde\nf", 5, "nothing");
+        assert_eq!((err.line(), err.column()), (2, 3));
+        assert!(err.to_string().ends_with("2 | cde\n  |   ^"));
+    }
+
+    #[derive(Debug, PartialEq, Parse)]
+    enum Op {
+        #[parse(tag = "<=")]
+        Le(i64),
+        #[parse(tag = "<")]
+        Lt(i64),
+        #[parse(tag = "set ")]
+        Set(#[parse(with = alpha1)] String),
+        Bare,
+    }
+
+    #[derive(Debug, PartialEq, Parse)]
+    #[parse(separator = "x")]
+    struct Size(u32, u32);
+
+    #[derive(Debug, PartialEq, Parse)]
+    #[parse(prefix = "{", suffix = "}", cut)]
+    struct Block {
+        #[parse(prefix = "#")]
+        id: u32,
+        #[parse(prefix = " ", suffix = ":")]
+        label: Option<Op>,
+        #[parse(prefix = " ", separator = ",", nonempty)]
+        ops: Vec<Op>,
+        #[parse(skip)]
+        seen: bool,
+    }
+
+    impl FromStr for Block {
+        type Err = ParseError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            finish(s, Block::parse(s))
+        }
+    }
+
+    #[test]
+    fn derive() {
+        assert_eq!(Op::parse("<=-3"), Ok(("", Op::Le(-3))));
+        assert_eq!(Op::parse("<3"), Ok(("", Op::Lt(3))));
+        assert_eq!(Op::parse("set ab!"), Ok(("!", Op::Set("ab".into()))));
+        assert_eq!(Op::parse("?"), Ok(("?", Op::Bare)));
+        assert_eq!(Size::parse("3x4"), Ok(("", Size(3, 4))));
+
+        let block = "{#7 <2: <=1,set x}".parse::<Block>().unwrap();
+        assert_eq!(
+            block,
+            Block {
+                id: 7,
+                label: Some(Op::Lt(2)),
+                ops: vec![Op::Le(1), Op::Set("x".into())],
+                seen: false,
+            }
+        );
+        let block = "{#7 <2}".parse::<Block>().unwrap();
+        assert_eq!((block.label, block.ops), (None, vec![Op::Lt(2)]));
+
+        // inside the braces, failures are hard errors at the right place
+        let err = "{#7 <2,<3]".parse::<Block>().unwrap_err();
+        assert_eq!((err.column(), err.expected()), (10, "a fixed character"));
+    }
+}
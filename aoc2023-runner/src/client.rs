@@ -0,0 +1,382 @@
+//! Fetch puzzle inputs and submit answers to the Advent of Code site.
+//!
+//! Configured by a plain text file of `key = value` lines:
+//!
+//! ```text
+//! # the `session` cookie from a logged in browser
+//! session  = 53616c7465645f5f...
+//! # optional, shown with their defaults
+//! base_url = https://adventofcode.com
+//! cooldown = 60
+//! ```
+//!
+//! Inputs are cached as `DD.txt` alongside the config, which is where
+//! `aoc all` looks for them. After a wrong answer no more are sent until
+//! `cooldown` seconds have passed, or as long as the site asks when it
+//! rate limits us.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use nom::{
+    bytes::complete::{tag, take_until},
+    character::complete::{char, u64},
+    combinator::opt,
+    sequence::{preceded, terminated, tuple},
+    IResult,
+};
+use thiserror::Error;
+
+const YEAR: u16 = 2023;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub session: String,
+    pub base_url: String,
+    /// Where inputs and the cooldown are kept.
+    pub cache: PathBuf,
+    pub cooldown: Duration,
+}
+
+impl Config {
+    pub fn parse(config: &str, cache: &Path) -> Result<Self> {
+        let mut session = None;
+        let mut base_url = "https://adventofcode.com".to_string();
+        let mut cooldown = Duration::from_secs(60);
+        for (i, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                bail!("config line {}: expected `key = value`", i + 1);
+            };
+            let value = value.trim();
+            match key.trim() {
+                "session" => session = Some(value.to_string()),
+                "base_url" => base_url = value.trim_end_matches('/').to_string(),
+                "cooldown" => {
+                    cooldown = Duration::from_secs(
+                        value
+                            .parse()
+                            .wrap_err_with(|| format!("config line {}: cooldown", i + 1))?,
+                    )
+                }
+                key => bail!("config line {}: unknown key {key:?}", i + 1),
+            }
+        }
+        Ok(Self {
+            session: session.ok_or(eyre!("no session in config"))?,
+            base_url,
+            cache: cache.to_path_buf(),
+            cooldown,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let config = fs::read_to_string(path)
+            .wrap_err_with(|| format!("reading client config {}", path.display()))?;
+        Self::parse(&config, path.parent().unwrap_or(Path::new("")))
+    }
+}
+
+/// What the site made of a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after the last answer, with the time left if given.
+    RateLimited(Option<Duration>),
+    /// The part was already solved, so the answer wasn't checked.
+    AlreadySolved,
+}
+
+impl Verdict {
+    /// Recognise the verdict in the HTML the site responds with.
+    pub fn parse(response: &str) -> Option<Self> {
+        if response.contains("That's the right answer") {
+            Some(Self::Right)
+        } else if response.contains("You gave an answer too recently") {
+            Some(Self::RateLimited(wait(response)))
+        } else if response.contains("That's not the right answer") {
+            Some(if response.contains("your answer is too high") {
+                Self::TooHigh
+            } else if response.contains("your answer is too low") {
+                Self::TooLow
+            } else {
+                Self::Wrong
+            })
+        } else if response.contains("You don't seem to be solving the right level") {
+            Some(Self::AlreadySolved)
+        } else {
+            None
+        }
+    }
+}
+
+/// The `You have 1m 23s left to wait` in a rate limiting response.
+fn wait(response: &str) -> Option<Duration> {
+    let minutes = opt(terminated(u64, tag("m ")));
+    let seconds = terminated(u64, char('s'));
+    let found: IResult<&str, _> = preceded(
+        tuple((take_until("You have "), tag("You have "))),
+        tuple((minutes, seconds)),
+    )(response);
+    let (_, (minutes, seconds)) = found.ok()?;
+    Some(Duration::from_secs(minutes.unwrap_or(0) * 60 + seconds))
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Right => write!(f, "right"),
+            Self::Wrong => write!(f, "wrong"),
+            Self::TooHigh => write!(f, "wrong, too high"),
+            Self::TooLow => write!(f, "wrong, too low"),
+            Self::RateLimited(Some(wait)) => write!(f, "rate limited, {wait:?} left to wait"),
+            Self::RateLimited(None) => write!(f, "rate limited"),
+            Self::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+/// Refused to submit, to respect the site's rate limit.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("submitted too recently, wait another {0:?}")]
+pub struct Cooldown(pub Duration);
+
+pub struct Client {
+    config: Config,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(config: Config) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent("github.com/olidacombe/aoc2023")
+            .timeout(Duration::from_secs(30))
+            .build();
+        Self { config, agent }
+    }
+
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.config.cache.join(format!("{day:02}.txt"))
+    }
+
+    /// The input for `day`, downloaded only if it isn't cached already.
+    pub fn input(&self, day: u8) -> Result<String> {
+        let path = self.input_path(day);
+        if let Ok(input) = fs::read_to_string(&path) {
+            return Ok(input);
+        }
+        let url = format!("{}/{YEAR}/day/{day}/input", self.config.base_url);
+        let input = self
+            .agent
+            .get(&url)
+            .set("Cookie", &self.cookie())
+            .call()
+            .wrap_err_with(|| format!("downloading input for day {day}"))?
+            .into_string()?;
+        fs::create_dir_all(&self.config.cache)?;
+        fs::write(&path, &input)?;
+        Ok(input)
+    }
+
+    /// Submit `answer` for `day`/`part`, unless we're still cooling down.
+    pub fn submit(&self, day: u8, part: u8, answer: &str) -> Result<Verdict> {
+        if let Some(wait) = self.cooldown_left() {
+            return Err(Cooldown(wait).into());
+        }
+        let url = format!("{}/{YEAR}/day/{day}/answer", self.config.base_url);
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &self.cookie())
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+            .wrap_err_with(|| format!("submitting answer for day {day} part {part}"))?
+            .into_string()?;
+        let verdict =
+            Verdict::parse(&response).ok_or_else(|| eyre!("unrecognised response:\n{response}"))?;
+        match verdict {
+            Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow => {
+                self.cool_down(self.config.cooldown)?
+            }
+            Verdict::RateLimited(wait) => self.cool_down(wait.unwrap_or(self.config.cooldown))?,
+            Verdict::Right | Verdict::AlreadySolved => {}
+        }
+        Ok(verdict)
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.config.session)
+    }
+
+    fn cooldown_path(&self) -> PathBuf {
+        self.config.cache.join(".cooldown")
+    }
+
+    /// Time left before we may submit again, kept on disk as a unix timestamp.
+    fn cooldown_left(&self) -> Option<Duration> {
+        let until: u64 = fs::read_to_string(self.cooldown_path())
+            .ok()?
+            .trim()
+            .parse()
+            .ok()?;
+        (UNIX_EPOCH + Duration::from_secs(until))
+            .duration_since(SystemTime::now())
+            .ok()
+    }
+
+    fn cool_down(&self, wait: Duration) -> Result<()> {
+        let until = (SystemTime::now() + wait).duration_since(UNIX_EPOCH)?;
+        fs::create_dir_all(&self.config.cache)?;
+        // round up, so we never wake a moment early
+        Ok(fs::write(
+            self.cooldown_path(),
+            (until.as_secs() + 1).to_string(),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    /// A one-shot HTTP server answering each request in turn with `bodies`,
+    /// returning the requests it saw.
+    fn stub(bodies: Vec<&'static str>) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            bodies
+                .into_iter()
+                .map(|body| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut request = String::new();
+                    let mut length = 0;
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                            length = len.trim().parse().unwrap();
+                        }
+                        request.push_str(&line);
+                        if line == "\r\n" {
+                            break;
+                        }
+                    }
+                    let mut content = vec![0; length];
+                    reader.read_exact(&mut content).unwrap();
+                    request.push_str(&String::from_utf8(content).unwrap());
+                    write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .unwrap();
+                    request
+                })
+                .collect()
+        });
+        (url, server)
+    }
+
+    fn client(url: &str, name: &str) -> Result<Client> {
+        let cache = std::env::temp_dir().join(format!("aoc2023-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&cache);
+        let config = Config::parse(&format!("session = s3cret\nbase_url = {url}/\n"), &cache)?;
+        Ok(Client::new(config))
+    }
+
+    #[test]
+    fn config() -> Result<()> {
+        let config = Config::parse("# x\nsession = abc\ncooldown = 5\n", Path::new("inputs"))?;
+        assert_eq!(config.base_url, "https://adventofcode.com");
+        assert_eq!(config.cooldown, Duration::from_secs(5));
+        assert!(Config::parse("cooldown = 5\n", Path::new("")).is_err());
+        assert!(Config::parse("session = abc\nsesion = abc\n", Path::new("")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn verdicts() {
+        let article = |text: &str| format!("<main><article><p>{text}</p></article></main>");
+        assert_eq!(
+            Verdict::parse(&article(
+                "That's the right answer! You are one gold star closer."
+            )),
+            Some(Verdict::Right)
+        );
+        assert_eq!(
+            Verdict::parse(&article(
+                "That's not the right answer; your answer is too low. Please wait one minute."
+            )),
+            Some(Verdict::TooLow)
+        );
+        assert_eq!(
+            Verdict::parse(&article(
+                "That's not the right answer. Please wait one minute."
+            )),
+            Some(Verdict::Wrong)
+        );
+        assert_eq!(
+            Verdict::parse(&article(
+                "You gave an answer too recently. You have 1m 23s left to wait."
+            )),
+            Some(Verdict::RateLimited(Some(Duration::from_secs(83))))
+        );
+        assert_eq!(
+            Verdict::parse(&article(
+                "You gave an answer too recently. You have 9s left."
+            )),
+            Some(Verdict::RateLimited(Some(Duration::from_secs(9))))
+        );
+        assert_eq!(Verdict::parse("<html>Internal Server Error</html>"), None);
+    }
+
+    #[test]
+    fn fetch_and_cache() -> Result<()> {
+        let (url, server) = stub(vec!["1abc2\n"]);
+        let client = client(&url, "fetch")?;
+        assert_eq!(client.input(1)?, "1abc2\n");
+        // served from disk, the stub only answers once
+        assert_eq!(client.input(1)?, "1abc2\n");
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2023/day/1/input HTTP/1.1"));
+        assert!(requests[0].contains("session=s3cret"));
+        fs::remove_dir_all(&client.config.cache)?;
+        Ok(())
+    }
+
+    #[test]
+    fn submit_with_cooldown() -> Result<()> {
+        let (url, server) = stub(vec![
+            "That's not the right answer; your answer is too high.",
+        ]);
+        let client = client(&url, "submit")?;
+        assert_eq!(client.submit(5, 2, "42")?, Verdict::TooHigh);
+        // refused locally, without troubling the server
+        let err = client.submit(5, 2, "41").unwrap_err();
+        assert!(err.downcast_ref::<Cooldown>().is_some());
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2023/day/5/answer HTTP/1.1"));
+        assert!(requests[0].ends_with("level=2&answer=42"));
+        fs::remove_dir_all(&client.config.cache)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use aoc2023_solution::{Solution, Timed};
+use color_eyre::{eyre::eyre, Result};
+
+pub mod client;
+pub mod manifest;
+
+type Runner = fn(&str) -> Result<Timed<String>>;
+
+pub struct Puzzle {
+    pub day: u8,
+    pub part: u8,
+    runner: Runner,
+}
+
+impl Puzzle {
+    pub fn solve(&self, input: &str) -> Result<String> {
+        Ok(self.solve_timed(input)?.answer)
+    }
+
+    /// Solve, timing the parse and the solve separately.
+    pub fn solve_timed(&self, input: &str) -> Result<Timed<String>> {
+        (self.runner)(input)
+    }
+}
+
+const fn register<S: Solution>() -> Puzzle {
+    Puzzle {
+        day: S::DAY,
+        part: S::PART,
+        runner: |input| Ok(S::run_timed(input)?.map(|answer| answer.to_string())),
+    }
+}
+
+pub const PUZZLES: &[Puzzle] = &[
+    register::<aoc2023_01_1::Solver>(),
+    register::<aoc2023_01_2::Solver>(),
+    register::<aoc2023_02_1::Solver>(),
+    register::<aoc2023_02_2::Solver>(),
+    register::<aoc2023_03_1::Solver>(),
+    register::<aoc2023_03_2::Solver>(),
+    register::<aoc2023_04_1::Solver>(),
+    register::<aoc2023_04_2::Solver>(),
+    register::<aoc2023_05_1::Solver>(),
+    register::<aoc2023_05_2::Solver>(),
+    register::<aoc2023_06_1::Solver>(),
+    register::<aoc2023_06_2::Solver>(),
+    register::<aoc2023_07_1::Solver>(),
+    register::<aoc2023_07_2::Solver>(),
+    register::<aoc2023_08_1::Solver>(),
+    register::<aoc2023_08_2::Solver>(),
+    register::<aoc2023_09_1::Solver>(),
+    register::<aoc2023_09_2::Solver>(),
+    register::<aoc2023_10_1::Solver>(),
+    register::<aoc2023_10_2::Solver>(),
+    register::<aoc2023_11_1::Solver>(),
+    register::<aoc2023_11_2::Solver>(),
+    register::<aoc2023_12_1::Solver>(),
+    register::<aoc2023_12_2::Solver>(),
+    register::<aoc2023_13_1::Solver>(),
+    register::<aoc2023_13_2::Solver>(),
+    register::<aoc2023_14_1::Solver>(),
+    register::<aoc2023_14_2::Solver>(),
+    register::<aoc2023_15_1::Solver>(),
+    register::<aoc2023_15_2::Solver>(),
+    register::<aoc2023_16_1::Solver>(),
+    register::<aoc2023_16_2::Solver>(),
+    register::<aoc2023_17_1::Solver>(),
+    register::<aoc2023_17_2::Solver>(),
+    register::<aoc2023_18_1::Solver>(),
+    register::<aoc2023_18_2::Solver>(),
+    register::<aoc2023_19_1::Solver>(),
+    register::<aoc2023_19_2::Solver>(),
+    register::<aoc2023_20_1::Solver>(),
+];
+
+pub fn puzzle(day: u8, part: u8) -> Result<&'static Puzzle> {
+    PUZZLES
+        .iter()
+        .find(|p| p.day == day && p.part == part)
+        .ok_or(eyre!("No solution for day {day} part {part}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn dispatch() -> Result<()> {
+        let example = indoc! {"
+            1abc2
+            pqr3stu8vwx
+            a1b2c3d4e5f
+            treb7uchet
+        "};
+        assert_eq!(puzzle(1, 1)?.solve(example)?, "142");
+        let timed = puzzle(1, 1)?.solve_timed(example)?;
+        assert_eq!(timed.answer, "142");
+        assert!(puzzle(20, 2).is_err());
+        assert!(puzzle(4, 1)?.solve("Card 1: 41 48 | 83 x").is_err());
+        Ok(())
+    }
+}
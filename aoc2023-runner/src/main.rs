@@ -0,0 +1,171 @@
+use std::{
+    fs,
+    io::{self, Read},
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+};
+
+use aoc2023_runner::{
+    client::{Client, Config},
+    manifest::{self, panic_message, Check, Summary},
+    puzzle, Puzzle, PUZZLES,
+};
+use aoc2023_solution::logging::{self, LogFormat};
+use clap::{Parser, Subcommand};
+use color_eyre::{eyre::bail, Result};
+
+#[derive(Parser)]
+#[command(about = "Advent of Code 2023 solutions")]
+struct Cli {
+    /// Print solver diagnostics to stderr as `pretty` or `json` (default: $AOC_LOG)
+    #[arg(long, global = true)]
+    log: Option<LogFormat>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve a single day/part, reading the input from a file or stdin
+    Run {
+        day: u8,
+        part: u8,
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// Solve every day/part in sequence, reading `<INPUTS>/<DD>.txt` for each day
+    All {
+        #[arg(short, long, default_value = "inputs")]
+        inputs: PathBuf,
+    },
+    /// Check answers for real inputs against a manifest of expected answers
+    Verify {
+        #[arg(short, long, default_value = "inputs/answers.txt")]
+        manifest: PathBuf,
+    },
+    /// Download a day's input into the cache, unless it's there already
+    Fetch {
+        day: u8,
+        #[arg(short, long, default_value = "inputs/client.conf")]
+        config: PathBuf,
+    },
+    /// Submit an answer, by default the one solved from the day's input
+    Submit {
+        day: u8,
+        part: u8,
+        answer: Option<String>,
+        #[arg(short, long, default_value = "inputs/client.conf")]
+        config: PathBuf,
+    },
+}
+
+fn read_input(path: Option<&PathBuf>) -> Result<String> {
+    Ok(match path {
+        Some(path) => fs::read_to_string(path)?,
+        None => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    })
+}
+
+fn solve(puzzle: &Puzzle, input: &str) -> Result<()> {
+    let timed = puzzle.solve_timed(input)?;
+    println!("{}", timed.answer);
+    eprintln!(
+        "day {} part {}: {:?} (parse {:?}, solve {:?})",
+        puzzle.day,
+        puzzle.part,
+        timed.total(),
+        timed.parse,
+        timed.solve
+    );
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let cli = Cli::parse();
+    logging::init(cli.log)?;
+    match cli.command {
+        Command::Run { day, part, input } => {
+            let puzzle = puzzle(day, part)?;
+            solve(puzzle, &read_input(input.as_ref())?)?;
+        }
+        Command::All { inputs } => {
+            // like verify, a puzzle failing doesn't stop the rest
+            let mut failed = 0;
+            for puzzle in PUZZLES {
+                let path = inputs.join(format!("{:02}.txt", puzzle.day));
+                let input = match fs::read_to_string(&path) {
+                    Ok(input) => input,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                        eprintln!(
+                            "day {} part {}: skipped, no input at {}",
+                            puzzle.day,
+                            puzzle.part,
+                            path.display()
+                        );
+                        continue;
+                    }
+                    Err(err) => {
+                        println!(
+                            "{:02}-{}: FAIL reading {}: {err}",
+                            puzzle.day,
+                            puzzle.part,
+                            path.display()
+                        );
+                        failed += 1;
+                        continue;
+                    }
+                };
+                print!("{:02}-{}: ", puzzle.day, puzzle.part);
+                let why = match panic::catch_unwind(AssertUnwindSafe(|| solve(puzzle, &input))) {
+                    Ok(Ok(())) => continue,
+                    Ok(Err(err)) => format!("{err:#}"),
+                    Err(payload) => format!("panicked: {}", panic_message(&*payload)),
+                };
+                println!("FAIL {why}");
+                failed += 1;
+            }
+            if failed > 0 {
+                bail!("{failed} puzzle(s) failed");
+            }
+        }
+        Command::Verify { manifest } => {
+            let mut summary = Summary::default();
+            for entry in manifest::load(&manifest)? {
+                let check = Check::run(entry);
+                println!("{check}");
+                summary.record(&check);
+            }
+            println!("{summary}");
+            if summary.failed > 0 {
+                bail!("{} answer(s) did not match", summary.failed);
+            }
+        }
+        Command::Fetch { day, config } => {
+            let client = Client::new(Config::load(&config)?);
+            client.input(day)?;
+            println!("{}", client.input_path(day).display());
+        }
+        Command::Submit {
+            day,
+            part,
+            answer,
+            config,
+        } => {
+            let client = Client::new(Config::load(&config)?);
+            let answer = match answer {
+                Some(answer) => answer,
+                None => puzzle(day, part)?.solve(&client.input(day)?)?,
+            };
+            println!(
+                "{day:02}-{part} {answer}: {}",
+                client.submit(day, part, &answer)?
+            );
+        }
+    }
+    Ok(())
+}
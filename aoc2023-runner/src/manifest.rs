@@ -0,0 +1,252 @@
+//! Expected answers for real puzzle inputs, kept outside git.
+//!
+//! A manifest is a plain text file with one entry per line:
+//!
+//! ```text
+//! # day part input        answer
+//! 05    1    inputs/05.txt 35
+//! ```
+//!
+//! Blank lines and lines starting with `#` are ignored. Relative input paths
+//! are resolved against the directory holding the manifest.
+
+use std::{
+    any::Any,
+    fmt, fs, io,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use aoc2023_parse::{finish, parse_line, ParseError};
+use color_eyre::{eyre::WrapErr, Result};
+use nom::{
+    bytes::complete::is_not,
+    character::complete::{space1, u8},
+    sequence::{preceded, tuple},
+};
+
+use crate::puzzle;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub day: u8,
+    pub part: u8,
+    pub input: PathBuf,
+    pub expected: String,
+}
+
+impl FromStr for Entry {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let word = || preceded(space1, is_not(" \t"));
+        let entry = tuple((u8, preceded(space1, u8), word(), word()))(s.trim_end());
+        let (day, part, input, expected) = finish(s, entry)?;
+        Ok(Self {
+            day,
+            part,
+            input: input.into(),
+            expected: expected.to_string(),
+        })
+    }
+}
+
+pub fn parse(manifest: &str, base: &Path) -> Result<Vec<Entry>, ParseError> {
+    manifest
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| {
+            let mut entry: Entry = parse_line(i, line)?;
+            entry.input = base.join(entry.input);
+            Ok(entry)
+        })
+        .collect()
+}
+
+pub fn load(path: &Path) -> Result<Vec<Entry>> {
+    let manifest = fs::read_to_string(path)
+        .wrap_err_with(|| format!("reading manifest {}", path.display()))?;
+    Ok(parse(&manifest, path.parent().unwrap_or(Path::new("")))?)
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Pass,
+    Fail(String),
+    Skipped,
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub entry: Entry,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+impl Check {
+    /// Solve the entry's input, a missing input being skipped and a solver
+    /// that errors or panics failing.
+    pub fn run(entry: Entry) -> Self {
+        let input = match fs::read_to_string(&entry.input) {
+            Ok(input) => input,
+            Err(err) => {
+                let outcome = match err.kind() {
+                    io::ErrorKind::NotFound => Outcome::Skipped,
+                    _ => Outcome::Fail(format!("reading {}: {err}", entry.input.display())),
+                };
+                return Self {
+                    entry,
+                    outcome,
+                    elapsed: Duration::ZERO,
+                };
+            }
+        };
+        let start = Instant::now();
+        let answer = panic::catch_unwind(AssertUnwindSafe(|| {
+            puzzle(entry.day, entry.part).and_then(|puzzle| puzzle.solve(&input))
+        }));
+        let elapsed = start.elapsed();
+        let outcome = outcome(&entry.expected, answer);
+        Self {
+            entry,
+            outcome,
+            elapsed,
+        }
+    }
+}
+
+fn outcome(expected: &str, answer: Result<Result<String>, Box<dyn Any + Send>>) -> Outcome {
+    match answer {
+        Ok(Ok(answer)) if answer == expected => Outcome::Pass,
+        Ok(Ok(answer)) => Outcome::Fail(format!("expected {expected}, got {answer}")),
+        Ok(Err(err)) => Outcome::Fail(format!("{err:#}")),
+        Err(payload) => Outcome::Fail(format!("panicked: {}", panic_message(&*payload))),
+    }
+}
+
+/// What a solver panicked with, if it was a message.
+pub fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("no message")
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Entry {
+            day, part, input, ..
+        } = &self.entry;
+        write!(f, "{day:02}-{part} ")?;
+        match &self.outcome {
+            Outcome::Pass => write!(f, "pass    {:>10.1?}", self.elapsed),
+            Outcome::Fail(why) => write!(f, "FAIL    {:>10.1?}  {why}", self.elapsed),
+            Outcome::Skipped => write!(f, "skipped {:>10}  no input at {}", "", input.display()),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+impl Summary {
+    pub fn record(&mut self, check: &Check) {
+        match check.outcome {
+            Outcome::Pass => self.passed += 1,
+            Outcome::Fail(_) => self.failed += 1,
+            Outcome::Skipped => self.skipped += 1,
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} skipped",
+            self.passed, self.failed, self.skipped
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn parse_manifest() -> Result<()> {
+        let manifest = indoc! {"
+            # day part input answer
+            01 1 01.txt 142
+
+            20  1  /tmp/20.txt  32000000
+        "};
+        let entries = parse(manifest, Path::new("inputs"))?;
+        assert_eq!(
+            entries,
+            vec![
+                Entry {
+                    day: 1,
+                    part: 1,
+                    input: "inputs/01.txt".into(),
+                    expected: "142".into()
+                },
+                Entry {
+                    day: 20,
+                    part: 1,
+                    input: "/tmp/20.txt".into(),
+                    expected: "32000000".into()
+                },
+            ]
+        );
+        let err = parse("01 1 01.txt\n", Path::new("")).unwrap_err();
+        assert_eq!((err.line(), err.column()), (1, 12));
+        Ok(())
+    }
+
+    #[test]
+    fn check() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc2023-manifest-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("01.txt"), "1abc2\npqr3stu8vwx\n")?;
+        fs::create_dir_all(dir.join("02.txt"))?;
+        let manifest = "01 1 01.txt 50\n01 1 missing.txt 1\n01 1 01.txt 51\n02 1 02.txt 8\n";
+        let mut summary = Summary::default();
+        for entry in parse(manifest, &dir)? {
+            summary.record(&Check::run(entry));
+        }
+        fs::remove_dir_all(&dir)?;
+        assert_eq!(
+            summary,
+            Summary {
+                passed: 1,
+                failed: 2,
+                skipped: 1
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn panics_fail() {
+        let answer = panic::catch_unwind(|| -> Result<String> { panic!("index out of bounds") });
+        assert!(matches!(
+            outcome("1", answer),
+            Outcome::Fail(why) if why == "panicked: index out of bounds"
+        ));
+        let answer = panic::catch_unwind(|| -> Result<String> { panic!("{} > {}", 2, 1) });
+        assert!(matches!(
+            outcome("1", answer),
+            Outcome::Fail(why) if why == "panicked: 2 > 1"
+        ));
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+pub use color_eyre::Result;
+use tracing::info_span;
+
+pub mod logging;
+
+/// Common interface to every day/part, so tooling can drive solutions generically.
+pub trait Solution {
+    const DAY: u8;
+    const PART: u8;
+
+    /// The parsed puzzle input handed to [`Solution::solve`].
+    type Input;
+    /// Optional knobs, `Default` being the values the puzzle asks for.
+    type Params: Default;
+    type Answer: Display;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+    fn solve(input: Self::Input, params: &Self::Params) -> Result<Self::Answer>;
+
+    /// Parse and solve with default parameters, each under its own span.
+    fn run(input: &str) -> Result<Self::Answer> {
+        Ok(Self::run_timed(input)?.answer)
+    }
+
+    /// As [`Solution::run`], timing the parse and the solve separately.
+    fn run_timed(input: &str) -> Result<Timed<Self::Answer>> {
+        let _solution = info_span!("solution", day = Self::DAY, part = Self::PART).entered();
+        let start = Instant::now();
+        let input = info_span!("parse").in_scope(|| Self::parse(input))?;
+        let parse = start.elapsed();
+        let answer =
+            info_span!("solve").in_scope(|| Self::solve(input, &Self::Params::default()))?;
+        Ok(Timed {
+            answer,
+            parse,
+            solve: start.elapsed() - parse,
+        })
+    }
+}
+
+/// An answer, with how long parsing the input and solving it took.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timed<A> {
+    pub answer: A,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl<A> Timed<A> {
+    pub fn map<B>(self, f: impl FnOnce(A) -> B) -> Timed<B> {
+        Timed {
+            answer: f(self.answer),
+            parse: self.parse,
+            solve: self.solve,
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        self.parse + self.solve
+    }
+}
+
+/// Split raw input into owned lines, for solutions driven by line iterators.
+pub fn lines(input: &str) -> Vec<String> {
+    input.lines().map(String::from).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Doubler;
+
+    #[derive(Default)]
+    struct Factor(Option<u32>);
+
+    impl Solution for Doubler {
+        const DAY: u8 = 0;
+        const PART: u8 = 1;
+
+        type Input = Vec<u32>;
+        type Params = Factor;
+        type Answer = u32;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            Ok(input.lines().map(str::parse).collect::<Result<_, _>>()?)
+        }
+
+        fn solve(input: Self::Input, params: &Self::Params) -> Result<Self::Answer> {
+            Ok(input.iter().sum::<u32>() * params.0.unwrap_or(2))
+        }
+    }
+
+    #[test]
+    fn run_uses_default_params() -> Result<()> {
+        assert_eq!(Doubler::run("1\n2\n3")?, 12);
+        assert_eq!(
+            Doubler::solve(Doubler::parse("1\n2")?, &Factor(Some(3)))?,
+            9
+        );
+        assert!(Doubler::run("1\nx").is_err());
+        let timed = Doubler::run_timed("1\n2")?.map(|answer| answer.to_string());
+        assert_eq!(timed.answer, "6");
+        assert_eq!(timed.total(), timed.parse + timed.solve);
+        Ok(())
+    }
+
+    #[test]
+    fn log_formats() {
+        assert_eq!("json".parse(), Ok(logging::LogFormat::Json));
+        assert!("yaml".parse::<logging::LogFormat>().is_err());
+        assert_eq!(logging::init(None), Ok(()));
+    }
+}
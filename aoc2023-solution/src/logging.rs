@@ -0,0 +1,58 @@
+//! Opt-in diagnostics for solvers, which only ever emit `tracing` events.
+//!
+//! Nothing is printed unless a format is chosen, either by the caller or via
+//! the `AOC_LOG` environment variable (`pretty` or `json`). Output goes to
+//! stderr so it never mixes with answers, and `RUST_LOG` filters it as usual,
+//! defaulting to `info`.
+
+use std::{env, str::FromStr};
+
+use thiserror::Error;
+use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Pretty,
+    Json,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("unknown log format {0:?}, expected \"pretty\" or \"json\"")]
+pub struct UnknownFormat(String);
+
+impl FromStr for LogFormat {
+    type Err = UnknownFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pretty" => Ok(Self::Pretty),
+            "json" => Ok(Self::Json),
+            _ => Err(UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// The format asked for by `AOC_LOG`, if any.
+pub fn from_env() -> Result<Option<LogFormat>, UnknownFormat> {
+    env::var("AOC_LOG").ok().map(|s| s.parse()).transpose()
+}
+
+/// Install a global subscriber for `format`, or for `AOC_LOG` if `None`.
+///
+/// Span closes are logged too, so each solver's parse and solve times show up.
+/// Does nothing if neither is set, or a subscriber is already installed.
+pub fn init(format: Option<LogFormat>) -> Result<(), UnknownFormat> {
+    let Some(format) = format.map_or_else(from_env, |f| Ok(Some(f)))? else {
+        return Ok(());
+    };
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()))
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr);
+    // an error here only means someone beat us to it
+    let _ = match format {
+        LogFormat::Pretty => subscriber.pretty().try_init(),
+        LogFormat::Json => subscriber.json().try_init(),
+    };
+    Ok(())
+}